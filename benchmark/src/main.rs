@@ -4,8 +4,12 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use solana_client::nonblocking::rpc_client::RpcClient;
 use sqlx::postgres::PgPoolOptions;
+use stakenet_simulator_db::store::PostgresStore;
 use std::{env, error::Error, sync::Arc};
-use steward_simulator_cli::commands::{BacktestArgs, handle_backtest};
+use steward_simulator_cli::{
+    backtester::{Backtest, BacktestDataset, validator_historical_start_offset},
+    steward_utils::fetch_config,
+};
 use tracing::info;
 
 const EPOCH_DURATION_SECS: i64 = 2 * 24 * 3600;
@@ -108,7 +112,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let offset = rpc_epoch - est_now;
 
     let epoch_ranges = vec![
-        (600, 700), 
+        (600, 700),
         (550, 600),
         (735, 800),
         (732, 800),
@@ -117,6 +121,24 @@ async fn main() -> Result<(), Box<dyn Error>> {
         (740, 850),
     ];
 
+    // Load the data for every range once and share it between the backtests
+    let steward_config = fetch_config(&rpc_client).await?;
+    let dataset_start_epoch = epoch_ranges
+        .iter()
+        .map(|(start, _)| *start)
+        .min()
+        .unwrap_or(0);
+    let dataset_end_epoch = epoch_ranges.iter().map(|(_, end)| *end).max().unwrap_or(0);
+    let dataset = Arc::new(
+        BacktestDataset::load(
+            &PostgresStore::new(db_conn_pool.as_ref().clone()),
+            dataset_start_epoch,
+            dataset_end_epoch,
+            validator_historical_start_offset(&steward_config),
+        )
+        .await?,
+    );
+
     for (start_epoch, end_epoch) in epoch_ranges {
         let apy_with_epochs: Vec<ApyWithEpoch> = jito_json
             .apy
@@ -149,20 +171,17 @@ async fn main() -> Result<(), Box<dyn Error>> {
             avg_apy * 100.0
         );
 
-        let args = BacktestArgs::default();
-        let calculated_apy = handle_backtest(
-            args,
-            &db_conn_pool,
-            &rpc_client,
-            end_epoch,
-            end_epoch - start_epoch,
-        )
-        .await?;
+        let report = Backtest::builder()
+            .dataset(Arc::clone(&dataset))
+            .config(steward_config)
+            .window(start_epoch, end_epoch)
+            .run()
+            .await?;
         println!(
             "Epochs {}-{} => Backtest APY: {:.4}%",
             start_epoch,
             end_epoch,
-            calculated_apy * 100.0
+            report.final_apy * 100.0
         );
     }
 
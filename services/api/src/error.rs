@@ -0,0 +1,88 @@
+use axum::{
+    Json,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use sqlx::Error as SqlxError;
+use stakenet_simulator_db::{backtest_job::BacktestJobStatus, error::StakenetSimulatorDbError};
+use steward_simulator_cli::error::CliError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ApiError {
+    #[error("SqlxError: {0}")]
+    SqlxError(#[from] SqlxError),
+
+    #[error("Database error: {0}")]
+    DbError(#[from] StakenetSimulatorDbError),
+
+    #[error("Serde JSON error: {0}")]
+    SerdeJsonError(#[from] serde_json::Error),
+
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Backtest error: {0}")]
+    BacktestError(#[from] CliError),
+
+    #[error("Backtest job {0} not found")]
+    JobNotFound(String),
+
+    #[error("Backtest job {id} has no report, its status is {status:?}")]
+    ReportNotReady {
+        id: String,
+        status: BacktestJobStatus,
+    },
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            ApiError::BacktestError(error) if is_invalid_request(error) => StatusCode::BAD_REQUEST,
+            ApiError::JobNotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::ReportNotReady { .. } => StatusCode::CONFLICT,
+            ApiError::BacktestError(_)
+            | ApiError::SqlxError(_)
+            | ApiError::DbError(_)
+            | ApiError::SerdeJsonError(_)
+            | ApiError::IoError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        (status, Json(json!({ "error": self.to_string() }))).into_response()
+    }
+}
+
+/// Returns true for the backtest errors caused by the submitted request rather than by the
+/// database, RPC or the simulator itself
+fn is_invalid_request(error: &CliError) -> bool {
+    matches!(
+        error,
+        CliError::InvalidEpochWindow { .. }
+            | CliError::EpochWindowNotInDataset { .. }
+            | CliError::MissingBacktestInput(_)
+            | CliError::LookBackPeriodTooBig
+            | CliError::DataCoverageGaps(_)
+            | CliError::ParsePubkeyError(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backtest_error_status() {
+        let invalid = ApiError::from(CliError::InvalidEpochWindow {
+            start_epoch: 800,
+            end_epoch: 700,
+        });
+        assert_eq!(invalid.into_response().status(), StatusCode::BAD_REQUEST);
+
+        let internal = ApiError::from(CliError::ArithmeticError);
+        assert_eq!(
+            internal.into_response().status(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use stakenet_simulator_db::backtest_job::{BacktestJob, BacktestJobReport, BacktestJobStatus};
+use steward_simulator_cli::{
+    backtester::{
+        Backtest, CoveragePolicy, DelegationStrategyOptions, DirectedStakeTarget,
+        ScoringModelOptions, ValidatorListOptions,
+    },
+    error::CliError,
+    steward_utils::StewardParameterOverrides,
+};
+use tracing::{error, info};
+
+/// Body of a backtest job submission. Every field is optional, unset fields fall back to the
+/// loaded steward config and the window of the preloaded dataset.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BacktestJobRequest {
+    pub parameters: StewardParameterOverrides,
+    pub strategy: DelegationStrategyOptions,
+    pub scoring: ScoringModelOptions,
+    pub validators: ValidatorListOptions,
+    pub directed_stake: Vec<DirectedStakeTarget>,
+    pub start_epoch: Option<u16>,
+    /// Epoch the simulation stops at (exclusive)
+    pub end_epoch: Option<u16>,
+    pub seed: Option<u64>,
+    pub number_of_validator_delegations: Option<usize>,
+    pub steward_cycle_rate: Option<u16>,
+    pub steward_phases: bool,
+    pub stake_deposit_unstake: bool,
+    pub coverage_policy: CoveragePolicy,
+    pub initial_pool_lamports: Option<u64>,
+    pub transaction_fee_lamports: Option<u64>,
+}
+
+impl BacktestJobRequest {
+    /// Validates the request against the preloaded dataset and builds the backtest to run
+    pub fn build_backtest(&self, state: &AppState) -> Result<Backtest, CliError> {
+        let mut steward_config = state.steward_config;
+        self.parameters.update_steward_config(&mut steward_config);
+
+        let mut backtest = Backtest::builder()
+            .dataset(state.dataset.clone())
+            .config(steward_config)
+            .window(
+                self.start_epoch.unwrap_or(state.dataset.start_epoch),
+                self.end_epoch.unwrap_or(state.dataset.end_epoch),
+            )
+            .delegation_strategy(self.strategy.build())
+            .scoring_model(self.scoring.build())
+            .validator_filter(self.validators.build())
+            .directed_stake(self.directed_stake.clone())
+            .steward_phases(self.steward_phases)
+            .stake_deposit_unstake(self.stake_deposit_unstake)
+            .coverage_policy(self.coverage_policy);
+        if let Some(seed) = self.seed {
+            backtest = backtest.seed(seed);
+        }
+        if let Some(number_of_validator_delegations) = self.number_of_validator_delegations {
+            backtest = backtest.number_of_validator_delegations(number_of_validator_delegations);
+        }
+        if let Some(steward_cycle_rate) = self.steward_cycle_rate {
+            backtest = backtest.steward_cycle_rate(steward_cycle_rate);
+        }
+        if let Some(initial_pool_lamports) = self.initial_pool_lamports {
+            backtest = backtest.initial_pool_lamports(initial_pool_lamports);
+        }
+        if let Some(transaction_fee_lamports) = self.transaction_fee_lamports {
+            backtest = backtest.transaction_fee_lamports(transaction_fee_lamports);
+        }
+
+        backtest.build()
+    }
+}
+
+/// Runs the backtest of a submitted job and stores the report, or the error if it fails
+pub async fn run_job(state: AppState, id: String, backtest: Backtest) {
+    // The semaphore is never closed
+    let _permit = state.job_permits.acquire().await;
+    let db_connection = &state.db_connection;
+
+    info!("Running backtest job {}", id);
+    if let Err(e) =
+        BacktestJob::update_status(db_connection, &id, BacktestJobStatus::Running, None).await
+    {
+        error!("Unable to update backtest job {}: {}", id, e);
+    }
+
+    let result = match backtest.run().await {
+        Ok(report) => match serde_json::to_value(&report) {
+            Ok(report) => BacktestJobReport::insert(db_connection, &id, report)
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        },
+        Err(e) => Err(e.to_string()),
+    };
+
+    let update = match result {
+        Ok(()) => {
+            info!("Backtest job {} completed", id);
+            BacktestJob::update_status(db_connection, &id, BacktestJobStatus::Completed, None).await
+        }
+        Err(e) => {
+            error!("Backtest job {} failed: {}", id, e);
+            BacktestJob::update_status(db_connection, &id, BacktestJobStatus::Failed, Some(e)).await
+        }
+    };
+    if let Err(e) = update {
+        error!("Unable to update backtest job {}: {}", id, e);
+    }
+}
@@ -0,0 +1,4 @@
+pub mod error;
+pub mod jobs;
+pub mod routes;
+pub mod state;
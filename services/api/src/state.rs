@@ -0,0 +1,32 @@
+use jito_steward::Config;
+use sqlx::{Pool, Postgres};
+use std::sync::Arc;
+use steward_simulator_cli::backtester::BacktestDataset;
+use tokio::sync::Semaphore;
+
+#[derive(Clone)]
+pub struct AppState {
+    pub db_connection: Arc<Pool<Postgres>>,
+    /// Dataset preloaded on startup and shared by every job
+    pub dataset: Arc<BacktestDataset>,
+    /// Steward config the parameter overrides of every job are applied to
+    pub steward_config: Config,
+    /// Limits the number of backtests running at the same time
+    pub job_permits: Arc<Semaphore>,
+}
+
+impl AppState {
+    pub fn new(
+        db_connection: Arc<Pool<Postgres>>,
+        dataset: Arc<BacktestDataset>,
+        steward_config: Config,
+        max_concurrent_jobs: usize,
+    ) -> Self {
+        Self {
+            db_connection,
+            dataset,
+            steward_config,
+            job_permits: Arc::new(Semaphore::new(max_concurrent_jobs)),
+        }
+    }
+}
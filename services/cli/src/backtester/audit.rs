@@ -0,0 +1,333 @@
+use crate::backtester::{BacktestDataset, CoverageGaps};
+use serde::{Deserialize, Serialize};
+use stakenet_simulator_db::domain::{Bps, Lamports, VotePubkey};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    ops::Range,
+};
+use validator_history::ValidatorHistoryEntry as JitoValidatorHistoryEntry;
+
+/// Completeness of a table over the audited epochs
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct TableAudit {
+    pub table: String,
+    pub records: usize,
+    /// Audited epochs without any record, not checked for tables that are sparse by nature
+    pub missing_epochs: Vec<u64>,
+}
+
+impl TableAudit {
+    /// Audits the epochs of the records of a table. Ids are the primary keys of the tables, so
+    /// the stores can't hold duplicates.
+    fn new(table: &str, epochs: Vec<u64>, expected_epochs: Option<Range<u64>>) -> Self {
+        let recorded_epochs: HashSet<u64> = epochs.iter().copied().collect();
+        let missing_epochs = expected_epochs
+            .map(|expected_epochs| missing_epochs(&recorded_epochs, expected_epochs))
+            .unwrap_or_default();
+        Self {
+            table: table.to_string(),
+            records: epochs.len(),
+            missing_epochs,
+        }
+    }
+
+    pub fn issues(&self) -> usize {
+        self.missing_epochs.len()
+    }
+}
+
+/// Field holding the default value of the program, meaning it was never recorded
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct SentinelValues {
+    /// Table and field, as `<table>.<field>`
+    pub field: String,
+    pub count: usize,
+    pub epochs: Vec<u64>,
+}
+
+/// Epochs a validator has history with active stake for but no recorded rewards
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct MissingRewards {
+    pub vote_pubkey: VotePubkey,
+    pub epochs: Vec<u64>,
+}
+
+/// Gaps and inconsistencies in the data a backtest of `start_epoch..end_epoch` would read
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct AuditReport {
+    pub start_epoch: u16,
+    pub end_epoch: u16,
+    pub tables: Vec<TableAudit>,
+    pub validators_without_rewards: Vec<MissingRewards>,
+    pub sentinel_values: Vec<SentinelValues>,
+    /// Ids of the rewards recorded with no active stake, which have no APY
+    pub zero_stake_rewards: Vec<String>,
+    /// Active and inactive stake need the same number of records to compute the utilization
+    pub active_stake_records: usize,
+    pub inactive_stake_records: usize,
+    /// Total number of issues found, the audit passes when there are none
+    pub issues: usize,
+}
+
+impl AuditReport {
+    pub fn passed(&self) -> bool {
+        self.issues == 0
+    }
+}
+
+/// Audits the data of `start_epoch..end_epoch` in the dataset. Validator history is audited
+/// after the repairs of the tracker were applied, as the simulator sees it.
+pub fn audit_dataset(dataset: &BacktestDataset, start_epoch: u16, end_epoch: u16) -> AuditReport {
+    let epochs = u64::from(start_epoch)..u64::from(end_epoch);
+    let in_range = |epoch: u64| epochs.contains(&epoch);
+
+    let entries: Vec<_> = dataset
+        .entries_by_validator
+        .values()
+        .flatten()
+        .filter(|entry| in_range(entry.validator_history_entry.epoch.into()))
+        .collect();
+    let rewards: Vec<_> = dataset
+        .epoch_rewards
+        .values()
+        .flatten()
+        .filter(|reward| in_range(reward.epoch.into()))
+        .collect();
+    let active_stake: Vec<_> = dataset
+        .active_stake
+        .iter()
+        .filter(|stake| in_range(stake.epoch.into()))
+        .collect();
+    let inactive_stake: Vec<_> = dataset
+        .inactive_stake
+        .iter()
+        .filter(|stake| in_range(stake.epoch.into()))
+        .collect();
+    let cluster_history_epochs: Vec<u64> = dataset
+        .jito_cluster_history
+        .history
+        .arr
+        .iter()
+        .map(|entry| u64::from(entry.epoch))
+        .filter(|epoch| in_range(*epoch))
+        .collect();
+
+    let tables = vec![
+        TableAudit::new(
+            "validator_history_entries",
+            entries
+                .iter()
+                .map(|entry| entry.validator_history_entry.epoch.into())
+                .collect(),
+            Some(epochs.clone()),
+        ),
+        TableAudit::new(
+            "cluster_history_entries",
+            cluster_history_epochs,
+            Some(epochs.clone()),
+        ),
+        TableAudit::new(
+            "epoch_rewards",
+            rewards.iter().map(|reward| reward.epoch.into()).collect(),
+            Some(epochs.clone()),
+        ),
+        TableAudit::new(
+            "active_stake_jito_sol",
+            active_stake
+                .iter()
+                .map(|stake| stake.epoch.into())
+                .collect(),
+            Some(epochs.clone()),
+        ),
+        TableAudit::new(
+            "inactive_stake_jito_sol",
+            inactive_stake
+                .iter()
+                .map(|stake| stake.epoch.into())
+                .collect(),
+            Some(epochs.clone()),
+        ),
+        TableAudit::new(
+            "withdraw_and_deposit_sol",
+            dataset
+                .withdraw_and_deposit_sol
+                .iter()
+                .map(|record| record.epoch.into())
+                .filter(|epoch| in_range(*epoch))
+                .collect(),
+            Some(epochs.clone()),
+        ),
+        TableAudit::new(
+            "withdraw_and_deposit_stakes",
+            dataset
+                .withdraws_and_deposit_stakes
+                .iter()
+                .map(|record| record.epoch.into())
+                .filter(|epoch| in_range(*epoch))
+                .collect(),
+            None,
+        ),
+    ];
+
+    let mut missing_rewards: BTreeMap<VotePubkey, Vec<u64>> = BTreeMap::new();
+    for (vote_pubkey, epoch) in
+        CoverageGaps::compute(dataset, start_epoch, end_epoch).missing_rewards
+    {
+        missing_rewards
+            .entry(vote_pubkey)
+            .or_default()
+            .push(epoch.into());
+    }
+    let validators_without_rewards: Vec<MissingRewards> = missing_rewards
+        .into_iter()
+        .map(|(vote_pubkey, epochs)| MissingRewards {
+            vote_pubkey,
+            epochs,
+        })
+        .collect();
+
+    let mut sentinels: BTreeMap<String, (usize, BTreeSet<u64>)> = BTreeMap::new();
+    let mut record_sentinel = |field: String, epoch: u64| {
+        let (count, epochs) = sentinels.entry(field).or_default();
+        *count += 1;
+        epochs.insert(epoch);
+    };
+    for entry in &entries {
+        let history = &entry.validator_history_entry;
+        for field in history_sentinel_fields(history) {
+            record_sentinel(
+                format!("validator_history_entries.{field}"),
+                history.epoch.into(),
+            );
+        }
+    }
+    for reward in &rewards {
+        let fields = [
+            (
+                "inflation_commission_bps",
+                reward.inflation_commission_bps == Bps(u16::MAX),
+            ),
+            (
+                "total_inflation_rewards",
+                reward.total_inflation_rewards == Lamports(u64::MAX),
+            ),
+            (
+                "mev_commission_bps",
+                reward.mev_commission_bps == Bps(u16::MAX),
+            ),
+            (
+                "total_mev_rewards",
+                reward.total_mev_rewards == Lamports(u64::MAX),
+            ),
+            (
+                "priority_fee_commission_bps",
+                reward.priority_fee_commission_bps == Bps(u16::MAX),
+            ),
+            (
+                "total_priority_fee_rewards",
+                reward.total_priority_fee_rewards == Lamports(u64::MAX),
+            ),
+            ("active_stake", reward.active_stake == Lamports(u64::MAX)),
+        ];
+        for (field, _) in fields.iter().filter(|(_, is_sentinel)| *is_sentinel) {
+            record_sentinel(format!("epoch_rewards.{field}"), reward.epoch.into());
+        }
+    }
+    let sentinel_values: Vec<SentinelValues> = sentinels
+        .into_iter()
+        .map(|(field, (count, epochs))| SentinelValues {
+            field,
+            count,
+            epochs: epochs.into_iter().collect(),
+        })
+        .collect();
+
+    let mut zero_stake_rewards: Vec<String> = rewards
+        .iter()
+        .filter(|reward| reward.active_stake == Lamports(0))
+        .map(|reward| reward.id.clone())
+        .collect();
+    zero_stake_rewards.sort();
+
+    let active_stake_records = active_stake.len();
+    let inactive_stake_records = inactive_stake.len();
+    let issues = tables.iter().map(TableAudit::issues).sum::<usize>()
+        + validators_without_rewards.len()
+        + sentinel_values.len()
+        + zero_stake_rewards.len()
+        + usize::from(active_stake_records != inactive_stake_records);
+
+    AuditReport {
+        start_epoch,
+        end_epoch,
+        tables,
+        validators_without_rewards,
+        sentinel_values,
+        zero_stake_rewards,
+        active_stake_records,
+        inactive_stake_records,
+        issues,
+    }
+}
+
+/// Fields of a validator history entry the scoring reads that still hold the default value of
+/// the program
+fn history_sentinel_fields(entry: &JitoValidatorHistoryEntry) -> Vec<&'static str> {
+    [
+        (
+            "activated_stake_lamports",
+            entry.activated_stake_lamports == u64::MAX,
+        ),
+        ("mev_commission", entry.mev_commission == u16::MAX),
+        ("epoch_credits", entry.epoch_credits == u32::MAX),
+        ("commission", entry.commission == u8::MAX),
+        (
+            "priority_fee_commission",
+            entry.priority_fee_commission == u16::MAX,
+        ),
+        ("priority_fee_tips", entry.priority_fee_tips == u64::MAX),
+        ("total_leader_slots", entry.total_leader_slots == u32::MAX),
+        ("blocks_produced", entry.blocks_produced == u32::MAX),
+    ]
+    .into_iter()
+    .filter(|(_, is_sentinel)| *is_sentinel)
+    .map(|(field, _)| field)
+    .collect()
+}
+
+fn missing_epochs(recorded_epochs: &HashSet<u64>, epochs: Range<u64>) -> Vec<u64> {
+    epochs
+        .filter(|epoch| !recorded_epochs.contains(epoch))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_audit() {
+        let audit = TableAudit::new("epoch_rewards", vec![700, 700, 702], Some(700..704));
+        assert_eq!(audit.records, 3);
+        assert_eq!(audit.missing_epochs, vec![701, 703]);
+        assert_eq!(audit.issues(), 2);
+
+        let sparse = TableAudit::new("withdraw_and_deposit_stakes", vec![], None);
+        assert_eq!(sparse.issues(), 0);
+
+        let entry = JitoValidatorHistoryEntry {
+            epoch: 700,
+            activated_stake_lamports: 1_000,
+            mev_commission: 800,
+            epoch_credits: 6_000_000,
+            commission: 5,
+            priority_fee_commission: 0,
+            priority_fee_tips: 0,
+            ..JitoValidatorHistoryEntry::default()
+        };
+        assert_eq!(
+            history_sentinel_fields(&entry),
+            vec!["total_leader_slots", "blocks_produced"]
+        );
+    }
+}
@@ -0,0 +1,384 @@
+use crate::{
+    backtester::{
+        BacktestDataset, CoveragePolicy, CoverageReport, DecentralizationMetrics,
+        DelegationStrategy, DirectedStakeReport, DirectedStakeTarget, OnChainScoringModel,
+        PhaseTransition, ScoringEpochSummary, ScoringModel, StewardStrategy, TurnoverReport,
+        ValidatorFilter, ValidatorScoreRecord, cost_adjusted_apy, steward_cycle_length,
+    },
+    error::CliError,
+    steward_utils::StewardParameterOverrides,
+    utils::{RebalancingSimulator, calculate_aggregated_apy, calculate_stake_utilization_rate},
+};
+use jito_steward::Config;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+pub use crate::utils::{EpochTrace, RebalancingCycle, ValidatorResult, ValidatorWithScore};
+
+/// Git revision the simulator was built from
+pub const GIT_REVISION: &str = env!("GIT_REVISION");
+
+pub const DEFAULT_NUMBER_OF_VALIDATOR_DELEGATIONS: usize = 200;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BacktestReport {
+    pub scenario: Option<String>,
+    pub git_revision: String,
+    pub simulation_start_epoch: u16,
+    pub simulation_end_epoch: u16,
+    pub seed: u64,
+    pub number_of_validator_delegations: usize,
+    /// Name and settings of the delegation strategy of the simulated pool
+    pub delegation_strategy: String,
+    /// Name and settings of the scoring model of the simulated steward
+    pub scoring_model: String,
+    /// Effective steward parameters used for the backtest
+    pub parameters: StewardParameterOverrides,
+    pub rebalancing_cycles: Vec<RebalancingCycle>,
+    pub validator_results: Vec<ValidatorResult>,
+    /// Yield of the directed stake next to the yield of the stake delegated by the steward, only
+    /// set when part of the pool is directed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub directed_stake: Option<DirectedStakeReport>,
+    /// Concentration of the pool stake at the end of every simulated epoch
+    #[serde(default)]
+    pub decentralization: Vec<DecentralizationMetrics>,
+    /// Stake moved by the steward and what moving it cost
+    #[serde(default)]
+    pub turnover: TurnoverReport,
+    /// States the simulated steward went through, only recorded when simulating the steward
+    /// phases
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub phase_transitions: Vec<PhaseTransition>,
+    /// Rewards coverage of the window and the rewards imputed for its gaps
+    #[serde(default)]
+    pub coverage: CoverageReport,
+    /// Scoring outcome of every scoring epoch, with the number of validators per error
+    #[serde(default)]
+    pub scoring_summaries: Vec<ScoringEpochSummary>,
+    /// Score breakdown of every validator in every scoring epoch, only kept when requested
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub score_breakdowns: Vec<ValidatorScoreRecord>,
+    /// State of the pool at the end of every epoch, only kept when requested
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub epoch_traces: Vec<EpochTrace>,
+    pub aggregated_apy: f64,
+    pub stake_utilization_ratio: f64,
+    pub final_apy: f64,
+}
+
+/// Number of epochs of validator history needed before the first simulated epoch to score
+/// validators with the given steward config
+pub fn validator_historical_start_offset(config: &Config) -> u16 {
+    std::cmp::max(
+        config.parameters.mev_commission_range,
+        std::cmp::max(
+            config.parameters.epoch_credits_range,
+            config.parameters.commission_range,
+        ),
+    )
+}
+
+/// A fully configured backtest, created through [`Backtest::builder`]
+pub struct Backtest {
+    dataset: Arc<BacktestDataset>,
+    config: Config,
+    start_epoch: u16,
+    end_epoch: u16,
+    seed: u64,
+    steward_cycle_rate: u16,
+    number_of_validator_delegations: usize,
+    initial_pool_lamports: Option<u64>,
+    delegation_strategy: Arc<dyn DelegationStrategy>,
+    scoring_model: Arc<dyn ScoringModel>,
+    validator_filter: ValidatorFilter,
+    directed_stake: Vec<DirectedStakeTarget>,
+    transaction_fee_lamports: Option<u64>,
+    steward_phases: bool,
+    stake_deposit_unstake: bool,
+    coverage_policy: CoveragePolicy,
+    record_score_breakdowns: bool,
+    record_epoch_traces: bool,
+}
+
+impl Backtest {
+    pub fn builder() -> BacktestBuilder {
+        BacktestBuilder::default()
+    }
+
+    pub async fn run(self) -> Result<BacktestReport, CliError> {
+        let mut simulator = RebalancingSimulator::new(
+            Arc::clone(&self.dataset),
+            self.config,
+            self.start_epoch,
+            self.end_epoch,
+            self.steward_cycle_rate,
+            self.number_of_validator_delegations,
+            self.config.parameters.instant_unstake_cap_bps,
+            self.config.parameters.scoring_unstake_cap_bps,
+            self.seed,
+            self.initial_pool_lamports,
+            self.coverage_policy,
+        )?
+        .with_delegation_strategy(Arc::clone(&self.delegation_strategy))
+        .with_scoring_model(Arc::clone(&self.scoring_model))
+        .with_validator_filter(self.validator_filter.clone())
+        .with_directed_stake(self.directed_stake.clone())
+        .with_score_breakdowns(self.record_score_breakdowns)
+        .with_epoch_traces(self.record_epoch_traces)
+        .with_steward_phases(self.steward_phases)
+        .with_stake_deposit_unstake(self.stake_deposit_unstake);
+        let rebalancing_cycles = simulator.run_simulation().await?;
+        let validator_results = simulator.validator_results();
+
+        let directed_stake = (!self.directed_stake.is_empty()).then(|| DirectedStakeReport {
+            targets: self.directed_stake.clone(),
+            final_stake_lamports: simulator
+                .directed_stake_states
+                .iter()
+                .map(|(vote_account, state)| (*vote_account, state.total()))
+                .collect(),
+            directed_rewards_lamports: simulator.directed_returns.rewards_lamports,
+            directed_apy: simulator.directed_returns.apy(),
+            steward_rewards_lamports: simulator.steward_returns.rewards_lamports,
+            steward_apy: simulator.steward_returns.apy(),
+        });
+
+        let look_back_period = self.end_epoch - self.start_epoch;
+        let aggregated_apy = calculate_aggregated_apy(&rebalancing_cycles, look_back_period)?;
+        let stake_utilization_ratio =
+            calculate_stake_utilization_rate(&self.dataset, look_back_period, self.end_epoch)?;
+
+        let starting_lamports = rebalancing_cycles
+            .first()
+            .map(|cycle| cycle.starting_total_lamports)
+            .unwrap_or(0);
+        let ending_lamports = rebalancing_cycles
+            .last()
+            .map(|cycle| cycle.ending_total_lamports)
+            .unwrap_or(0);
+        let mut turnover = TurnoverReport::new(
+            std::mem::take(&mut simulator.turnover),
+            simulator.forgone_rewards_lamports,
+            self.transaction_fee_lamports,
+            starting_lamports,
+        );
+        turnover.cost_adjusted_apy = cost_adjusted_apy(
+            starting_lamports,
+            ending_lamports,
+            turnover.transaction_fee_lamports,
+            look_back_period,
+        ) * stake_utilization_ratio;
+
+        Ok(BacktestReport {
+            scenario: None,
+            git_revision: GIT_REVISION.to_string(),
+            simulation_start_epoch: self.start_epoch,
+            simulation_end_epoch: self.end_epoch,
+            seed: self.seed,
+            number_of_validator_delegations: self.number_of_validator_delegations,
+            delegation_strategy: self.delegation_strategy.name(),
+            scoring_model: self.scoring_model.name(),
+            parameters: StewardParameterOverrides::from_config(&self.config),
+            rebalancing_cycles,
+            validator_results,
+            directed_stake,
+            decentralization: std::mem::take(&mut simulator.decentralization),
+            turnover,
+            phase_transitions: std::mem::take(&mut simulator.phase_transitions),
+            coverage: std::mem::take(&mut simulator.coverage),
+            scoring_summaries: std::mem::take(&mut simulator.scoring_summaries),
+            score_breakdowns: std::mem::take(&mut simulator.score_breakdowns),
+            epoch_traces: std::mem::take(&mut simulator.epoch_traces),
+            aggregated_apy,
+            stake_utilization_ratio,
+            final_apy: aggregated_apy * stake_utilization_ratio,
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct BacktestBuilder {
+    dataset: Option<Arc<BacktestDataset>>,
+    config: Option<Config>,
+    window: Option<(u16, u16)>,
+    seed: Option<u64>,
+    steward_cycle_rate: Option<u16>,
+    number_of_validator_delegations: Option<usize>,
+    initial_pool_lamports: Option<u64>,
+    delegation_strategy: Option<Arc<dyn DelegationStrategy>>,
+    scoring_model: Option<Arc<dyn ScoringModel>>,
+    validator_filter: Option<ValidatorFilter>,
+    directed_stake: Vec<DirectedStakeTarget>,
+    transaction_fee_lamports: Option<u64>,
+    steward_phases: bool,
+    stake_deposit_unstake: bool,
+    coverage_policy: CoveragePolicy,
+    record_score_breakdowns: bool,
+    record_epoch_traces: bool,
+}
+
+impl BacktestBuilder {
+    /// Data to run the backtest against, can be shared between backtests
+    pub fn dataset(mut self, dataset: impl Into<Arc<BacktestDataset>>) -> Self {
+        self.dataset = Some(dataset.into());
+        self
+    }
+
+    /// Steward config the simulated steward runs with
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Epochs `start_epoch..end_epoch` to simulate, defaults to the whole dataset
+    pub fn window(mut self, start_epoch: u16, end_epoch: u16) -> Self {
+        self.window = Some((start_epoch, end_epoch));
+        self
+    }
+
+    /// Seed for the random parts of the simulation, a random seed is picked when not set
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Number of epochs in a steward cycle, defaults to `num_epochs_between_scoring` of the config
+    pub fn steward_cycle_rate(mut self, steward_cycle_rate: u16) -> Self {
+        self.steward_cycle_rate = Some(steward_cycle_rate);
+        self
+    }
+
+    pub fn number_of_validator_delegations(
+        mut self,
+        number_of_validator_delegations: usize,
+    ) -> Self {
+        self.number_of_validator_delegations = Some(number_of_validator_delegations);
+        self
+    }
+
+    /// Size of the simulated pool at the start, defaults to 1 SOL per delegated validator
+    pub fn initial_pool_lamports(mut self, initial_pool_lamports: u64) -> Self {
+        self.initial_pool_lamports = Some(initial_pool_lamports);
+        self
+    }
+
+    /// Strategy picking the validators and their stake every cycle, defaults to the steward
+    pub fn delegation_strategy(mut self, delegation_strategy: Arc<dyn DelegationStrategy>) -> Self {
+        self.delegation_strategy = Some(delegation_strategy);
+        self
+    }
+
+    /// Model scoring the validators every cycle, defaults to the on-chain scoring
+    pub fn scoring_model(mut self, scoring_model: Arc<dyn ScoringModel>) -> Self {
+        self.scoring_model = Some(scoring_model);
+        self
+    }
+
+    /// Validators that can't be delegated to, defaults to the recorded steward blacklist
+    pub fn validator_filter(mut self, validator_filter: ValidatorFilter) -> Self {
+        self.validator_filter = Some(validator_filter);
+        self
+    }
+
+    /// Stake delegated to validators regardless of their score, before the steward distributes
+    /// the rest of the pool
+    pub fn directed_stake(mut self, directed_stake: Vec<DirectedStakeTarget>) -> Self {
+        self.directed_stake = directed_stake;
+        self
+    }
+
+    /// Fee paid for every stake activation and deactivation, deducted in the cost adjusted APY
+    pub fn transaction_fee_lamports(mut self, transaction_fee_lamports: u64) -> Self {
+        self.transaction_fee_lamports = Some(transaction_fee_lamports);
+        self
+    }
+
+    /// Step through the phases of the steward program every epoch
+    pub fn steward_phases(mut self, steward_phases: bool) -> Self {
+        self.steward_phases = steward_phases;
+        self
+    }
+
+    /// Unstake the stake deposited to the pool's validators, up to `stake_deposit_unstake_cap_bps`
+    /// of the pool per cycle
+    pub fn stake_deposit_unstake(mut self, stake_deposit_unstake: bool) -> Self {
+        self.stake_deposit_unstake = stake_deposit_unstake;
+        self
+    }
+
+    /// What to do when validators have stake but no recorded rewards, defaults to failing
+    pub fn coverage_policy(mut self, coverage_policy: CoveragePolicy) -> Self {
+        self.coverage_policy = coverage_policy;
+        self
+    }
+
+    /// Keep the score breakdown of every validator in every scoring epoch in the report
+    pub fn record_score_breakdowns(mut self, record_score_breakdowns: bool) -> Self {
+        self.record_score_breakdowns = record_score_breakdowns;
+        self
+    }
+
+    /// Keep the state of the pool at the end of every epoch in the report
+    pub fn record_epoch_traces(mut self, record_epoch_traces: bool) -> Self {
+        self.record_epoch_traces = record_epoch_traces;
+        self
+    }
+
+    pub fn build(self) -> Result<Backtest, CliError> {
+        let dataset = self
+            .dataset
+            .ok_or(CliError::MissingBacktestInput("dataset"))?;
+        let config = self
+            .config
+            .ok_or(CliError::MissingBacktestInput("config"))?;
+        let (start_epoch, end_epoch) = self
+            .window
+            .unwrap_or((dataset.start_epoch, dataset.end_epoch));
+
+        if start_epoch >= end_epoch {
+            return Err(CliError::InvalidEpochWindow {
+                start_epoch,
+                end_epoch,
+            });
+        }
+        if !dataset.covers(start_epoch, end_epoch) {
+            return Err(CliError::EpochWindowNotInDataset {
+                start_epoch,
+                end_epoch,
+            });
+        }
+
+        Ok(Backtest {
+            dataset,
+            config,
+            start_epoch,
+            end_epoch,
+            seed: self.seed.unwrap_or_else(rand::random),
+            steward_cycle_rate: steward_cycle_length(&config, self.steward_cycle_rate),
+            number_of_validator_delegations: self
+                .number_of_validator_delegations
+                .unwrap_or(DEFAULT_NUMBER_OF_VALIDATOR_DELEGATIONS),
+            initial_pool_lamports: self.initial_pool_lamports,
+            delegation_strategy: self
+                .delegation_strategy
+                .unwrap_or_else(|| Arc::new(StewardStrategy)),
+            scoring_model: self
+                .scoring_model
+                .unwrap_or_else(|| Arc::new(OnChainScoringModel)),
+            validator_filter: self.validator_filter.unwrap_or_default(),
+            directed_stake: self.directed_stake,
+            transaction_fee_lamports: self.transaction_fee_lamports,
+            steward_phases: self.steward_phases,
+            stake_deposit_unstake: self.stake_deposit_unstake,
+            coverage_policy: self.coverage_policy,
+            record_score_breakdowns: self.record_score_breakdowns,
+            record_epoch_traces: self.record_epoch_traces,
+        })
+    }
+
+    /// Builds and runs the backtest
+    pub async fn run(self) -> Result<BacktestReport, CliError> {
+        self.build()?.run().await
+    }
+}
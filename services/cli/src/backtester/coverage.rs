@@ -0,0 +1,237 @@
+use crate::backtester::BacktestDataset;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use stakenet_simulator_db::{
+    domain::{Epoch, VotePubkey},
+    epoch_rewards::EpochRewards,
+};
+use std::{collections::HashSet, fmt};
+
+/// Number of validator epochs without rewards listed in the error message
+const LISTED_MISSING_REWARDS: usize = 10;
+
+/// What the simulator does when validators have stake but no recorded rewards in the window
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum CoveragePolicy {
+    /// Abort the backtest and list the gaps
+    #[default]
+    Fail,
+    /// Give the stake of validators without rewards the median return of the cluster that epoch
+    ImputeMedian,
+}
+
+/// Data missing from the simulated window
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct CoverageGaps {
+    /// Validator history entries with active stake in the window
+    pub validator_epochs: usize,
+    /// Vote accounts and epochs with active stake but no recorded rewards
+    pub missing_rewards: Vec<(VotePubkey, u16)>,
+    /// Epochs without any validator history entry
+    pub missing_history_epochs: Vec<u16>,
+    /// Epochs without a cluster history entry
+    pub missing_cluster_history_epochs: Vec<u16>,
+}
+
+impl CoverageGaps {
+    /// Finds the gaps in the data of `start_epoch..end_epoch`
+    pub fn compute(dataset: &BacktestDataset, start_epoch: u16, end_epoch: u16) -> Self {
+        let epochs = start_epoch..end_epoch;
+
+        let rewarded: HashSet<(VotePubkey, Epoch)> = dataset
+            .epoch_rewards
+            .values()
+            .flatten()
+            .map(|reward| (reward.vote_pubkey, reward.epoch))
+            .collect();
+        let mut history_epochs = HashSet::new();
+        let mut validator_epochs = 0;
+        let mut missing_rewards = Vec::new();
+        for entry in dataset.entries_by_validator.values().flatten() {
+            let history = &entry.validator_history_entry;
+            if !epochs.contains(&history.epoch) {
+                continue;
+            }
+            history_epochs.insert(history.epoch);
+            if history.activated_stake_lamports == 0 || history.activated_stake_lamports == u64::MAX
+            {
+                continue;
+            }
+            validator_epochs += 1;
+            if !rewarded.contains(&(entry.vote_pubkey, Epoch::from(history.epoch))) {
+                missing_rewards.push((entry.vote_pubkey, history.epoch));
+            }
+        }
+        missing_rewards.sort();
+
+        let cluster_history_epochs: HashSet<u16> = dataset
+            .jito_cluster_history
+            .history
+            .arr
+            .iter()
+            .map(|entry| entry.epoch)
+            .collect();
+
+        Self {
+            validator_epochs,
+            missing_rewards,
+            missing_history_epochs: epochs
+                .clone()
+                .filter(|epoch| !history_epochs.contains(epoch))
+                .collect(),
+            missing_cluster_history_epochs: epochs
+                .filter(|epoch| !cluster_history_epochs.contains(epoch))
+                .collect(),
+        }
+    }
+
+    /// Returns true if the simulation can't run with these gaps under the policy. Missing
+    /// history can't be imputed.
+    pub fn is_blocking(&self, policy: CoveragePolicy) -> bool {
+        !self.missing_history_epochs.is_empty()
+            || !self.missing_cluster_history_epochs.is_empty()
+            || (policy == CoveragePolicy::Fail && !self.missing_rewards.is_empty())
+    }
+}
+
+impl fmt::Display for CoverageGaps {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} validator epochs without rewards",
+            self.missing_rewards.len(),
+            self.validator_epochs
+        )?;
+        if !self.missing_rewards.is_empty() {
+            let listed: Vec<String> = self
+                .missing_rewards
+                .iter()
+                .take(LISTED_MISSING_REWARDS)
+                .map(|(vote_account, epoch)| format!("{vote_account} at {epoch}"))
+                .collect();
+            write!(f, " ({}", listed.join(", "))?;
+            if self.missing_rewards.len() > LISTED_MISSING_REWARDS {
+                write!(f, ", ...")?;
+            }
+            write!(f, ")")?;
+        }
+        write!(
+            f,
+            ", epochs without validator history: {:?}, epochs without cluster history: {:?}",
+            self.missing_history_epochs, self.missing_cluster_history_epochs
+        )
+    }
+}
+
+/// Rewards given to stake on a validator without recorded rewards for the epoch
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ImputedReward {
+    pub vote_account: VotePubkey,
+    pub epoch: u16,
+    pub rewards_lamports: u64,
+}
+
+/// Coverage of the rewards of the simulated window and the rewards imputed for the gaps
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct CoverageReport {
+    pub policy: CoveragePolicy,
+    /// Share of the validator epochs with active stake that have recorded rewards
+    pub rewards_coverage: f64,
+    /// Number of validator epochs the pool had stake on that got imputed rewards
+    pub imputed_epochs: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub imputed_rewards: Vec<ImputedReward>,
+}
+
+impl CoverageReport {
+    pub fn new(policy: CoveragePolicy, gaps: &CoverageGaps) -> Self {
+        let rewards_coverage = if gaps.validator_epochs > 0 {
+            1.0 - gaps.missing_rewards.len() as f64 / gaps.validator_epochs as f64
+        } else {
+            1.0
+        };
+        Self {
+            policy,
+            rewards_coverage,
+            imputed_epochs: 0,
+            imputed_rewards: Vec::new(),
+        }
+    }
+
+    pub fn record_imputed(&mut self, imputed: ImputedReward) {
+        self.imputed_epochs += 1;
+        self.imputed_rewards.push(imputed);
+    }
+}
+
+/// Median return of a lamport staked during the epoch across the validators with rewards
+pub fn median_epoch_return(rewards: &[EpochRewards]) -> Option<f64> {
+    let mut returns: Vec<f64> = rewards
+        .iter()
+        .filter(|reward| reward.active_stake.0 > 0)
+        .map(|reward| {
+            let active_stake = reward.active_stake.0;
+            (reward.stake_after_epoch(active_stake) - active_stake) as f64 / active_stake as f64
+        })
+        .collect();
+    if returns.is_empty() {
+        return None;
+    }
+    returns.sort_by(f64::total_cmp);
+    let middle = returns.len() / 2;
+    Some(if returns.len().is_multiple_of(2) {
+        (returns[middle - 1] + returns[middle]) / 2.0
+    } else {
+        returns[middle]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stakenet_simulator_db::domain::{Bps, Lamports};
+
+    fn rewards(total_inflation_rewards: u64) -> EpochRewards {
+        EpochRewards {
+            id: String::new(),
+            vote_pubkey: VotePubkey::default(),
+            epoch: Epoch(700),
+            inflation_commission_bps: Bps(0),
+            total_inflation_rewards: Lamports(total_inflation_rewards),
+            mev_commission_bps: Bps(0),
+            total_mev_rewards: Lamports(0),
+            priority_fee_commission_bps: Bps(0),
+            total_priority_fee_rewards: Lamports(0),
+            active_stake: Lamports(1_000_000),
+        }
+    }
+
+    #[test]
+    fn test_coverage() {
+        let epoch_rewards = vec![rewards(100), rewards(300), rewards(200)];
+        assert_eq!(median_epoch_return(&epoch_rewards), Some(0.0002));
+        let even_median = median_epoch_return(&epoch_rewards[..2]).unwrap();
+        assert!((even_median - 0.0002).abs() < 1e-12);
+        assert_eq!(median_epoch_return(&[]), None);
+
+        let gaps = CoverageGaps {
+            validator_epochs: 4,
+            missing_rewards: vec![(VotePubkey::default(), 700)],
+            ..CoverageGaps::default()
+        };
+        assert!(gaps.is_blocking(CoveragePolicy::Fail));
+        assert!(!gaps.is_blocking(CoveragePolicy::ImputeMedian));
+        assert_eq!(
+            CoverageReport::new(CoveragePolicy::ImputeMedian, &gaps).rewards_coverage,
+            0.75
+        );
+        assert!(
+            CoverageGaps {
+                missing_cluster_history_epochs: vec![701],
+                ..CoverageGaps::default()
+            }
+            .is_blocking(CoveragePolicy::ImputeMedian)
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use crate::error::CliError;
+use solana_sdk::epoch_schedule::EpochSchedule;
+use stakenet_simulator_db::{
+    active_stake_jito_sol::ActiveStakeJitoSol,
+    domain::{Epoch, VotePubkey},
+    epoch_rewards::EpochRewards,
+    inactive_stake_jito_sol::InactiveStakeJitoSol,
+    steward_blacklist::StewardBlacklist,
+    store::SimulatorStore,
+    validator_history::ValidatorHistory,
+    validator_history_entry::ValidatorHistoryEntry,
+    validator_history_repair::ValidatorHistoryRepair,
+    withdraw_and_deposit_sol::WithdrawAndDepositSol,
+    withdraw_and_deposits_stake::WithdrawsAndDepositStakes,
+};
+use std::{collections::HashMap, sync::Arc};
+use tracing::{info, warn};
+use validator_history::ClusterHistory as JitoClusterHistory;
+
+/// All the data a backtest needs, loaded in memory so that simulations can be run without a
+/// database or RPC connection. A dataset can be shared between backtests of any window within
+/// `start_epoch..end_epoch`.
+pub struct BacktestDataset {
+    /// First epoch that can be simulated
+    pub start_epoch: u16,
+    /// Last epoch (exclusive) that can be simulated
+    pub end_epoch: u16,
+    pub histories: Vec<ValidatorHistory>,
+    pub jito_cluster_history: Arc<JitoClusterHistory>,
+    /// Epoch schedule of the cluster, to find the slots of every epoch
+    pub epoch_schedule: EpochSchedule,
+    /// Validator history entries grouped by vote account, including the history loaded before
+    /// `start_epoch` that is needed for scoring
+    pub entries_by_validator: Arc<HashMap<VotePubkey, Vec<ValidatorHistoryEntry>>>,
+    pub epoch_rewards: HashMap<u64, Vec<EpochRewards>>,
+    pub withdraw_and_deposit_sol: Vec<WithdrawAndDepositSol>,
+    pub withdraws_and_deposit_stakes: Vec<WithdrawsAndDepositStakes>,
+    pub active_stake: Vec<ActiveStakeJitoSol>,
+    pub inactive_stake: Vec<InactiveStakeJitoSol>,
+    /// Steward blacklists recorded before `end_epoch`, sorted by epoch
+    pub steward_blacklists: Vec<StewardBlacklist>,
+}
+
+impl BacktestDataset {
+    /// Loads every record needed to backtest epochs `start_epoch..end_epoch`. Validator history
+    /// entries are loaded from `validator_historical_start_offset` epochs before the start so the
+    /// first scoring cycle has the history it needs.
+    pub async fn load(
+        store: &dyn SimulatorStore,
+        start_epoch: u16,
+        end_epoch: u16,
+        validator_historical_start_offset: u16,
+    ) -> Result<Self, CliError> {
+        info!(
+            "Loading backtest dataset for epochs {} to {}...",
+            start_epoch, end_epoch
+        );
+
+        let histories = store.fetch_validator_histories().await?;
+        let cluster_history = store.fetch_cluster_history().await?;
+        let cluster_history_entries = store.fetch_cluster_history_entries().await?;
+        let jito_cluster_history =
+            Arc::new(cluster_history.convert_to_jito_cluster_history(cluster_history_entries)?);
+        let epoch_schedule = match store.fetch_epoch_schedule().await? {
+            Some(epoch_schedule) => epoch_schedule.into(),
+            None => {
+                warn!(
+                    "No epoch schedule recorded, assuming epochs of 432,000 slots without warmup"
+                );
+                EpochSchedule::without_warmup()
+            }
+        };
+
+        info!("Fetching all validator history entries...");
+        let history_start_epoch = start_epoch.saturating_sub(validator_historical_start_offset);
+        let mut all_entries = store
+            .fetch_validator_history_entries(history_start_epoch.into(), end_epoch.into())
+            .await?;
+        let repairs = store
+            .fetch_validator_history_repairs(history_start_epoch.into(), end_epoch.into())
+            .await?;
+        Self::apply_repairs(&mut all_entries, repairs);
+
+        let vote_accounts: Vec<VotePubkey> = histories
+            .iter()
+            .map(|history| history.vote_account)
+            .collect();
+        let rewards = store
+            .fetch_epoch_rewards(&vote_accounts, start_epoch.into(), end_epoch.into())
+            .await?;
+        let mut epoch_rewards: HashMap<u64, Vec<EpochRewards>> = HashMap::new();
+        for reward in rewards {
+            epoch_rewards
+                .entry(reward.epoch.0)
+                .or_default()
+                .push(reward);
+        }
+
+        let withdraw_and_deposit_sol = store
+            .fetch_withdraw_and_deposit_sol(start_epoch.into(), end_epoch.into())
+            .await?;
+
+        let withdraws_and_deposit_stakes = store
+            .fetch_withdraw_and_deposit_stakes(start_epoch.into(), end_epoch.into())
+            .await?;
+
+        let active_stake = store
+            .fetch_active_stake(start_epoch.into(), end_epoch.into())
+            .await?;
+
+        let inactive_stake = store
+            .fetch_inactive_stake(start_epoch.into(), end_epoch.into())
+            .await?;
+
+        let steward_blacklists = store
+            .fetch_steward_blacklists_before(end_epoch.into())
+            .await?;
+
+        let entries_by_validator = Self::build_entries_by_validator(all_entries);
+
+        info!(
+            "Grouped {} validators' history entries",
+            entries_by_validator.len()
+        );
+
+        Ok(Self {
+            start_epoch,
+            end_epoch,
+            histories,
+            jito_cluster_history,
+            epoch_schedule,
+            entries_by_validator: Arc::new(entries_by_validator),
+            epoch_rewards,
+            withdraw_and_deposit_sol,
+            withdraws_and_deposit_stakes,
+            active_stake,
+            inactive_stake,
+            steward_blacklists,
+        })
+    }
+
+    /// Returns true if the dataset holds the data to simulate `start_epoch..end_epoch`
+    pub fn covers(&self, start_epoch: u16, end_epoch: u16) -> bool {
+        start_epoch >= self.start_epoch && end_epoch <= self.end_epoch
+    }
+
+    /// Latest steward blacklist recorded at or before the epoch
+    pub fn steward_blacklist_at(&self, epoch: u16) -> Option<&StewardBlacklist> {
+        self.steward_blacklists
+            .iter()
+            .rev()
+            .find(|blacklist| blacklist.epoch <= Epoch::from(epoch))
+    }
+
+    /// Overwrites the fields of the entries reconstructed by the tracker's repair pipeline
+    fn apply_repairs(entries: &mut [ValidatorHistoryEntry], repairs: Vec<ValidatorHistoryRepair>) {
+        if repairs.is_empty() {
+            return;
+        }
+        let repairs: HashMap<(Epoch, VotePubkey), ValidatorHistoryRepair> = repairs
+            .into_iter()
+            .map(|repair| ((repair.epoch, repair.vote_pubkey), repair))
+            .collect();
+        let mut num_repaired = 0;
+        for entry in entries.iter_mut() {
+            let key = (
+                Epoch::from(entry.validator_history_entry.epoch),
+                entry.vote_pubkey,
+            );
+            if let Some(repair) = repairs.get(&key) {
+                repair.apply(&mut entry.validator_history_entry);
+                num_repaired += 1;
+            }
+        }
+        info!(
+            "Applied repairs to {} validator history entries",
+            num_repaired
+        );
+    }
+
+    /// This returns a hashmap of validator votekey to it's entries in the db
+    fn build_entries_by_validator(
+        all_entries: Vec<ValidatorHistoryEntry>,
+    ) -> HashMap<VotePubkey, Vec<ValidatorHistoryEntry>> {
+        let mut entries_by_validator: HashMap<VotePubkey, Vec<ValidatorHistoryEntry>> =
+            HashMap::new();
+        for entry in all_entries {
+            entries_by_validator
+                .entry(entry.vote_pubkey)
+                .or_default()
+                .push(entry);
+        }
+        entries_by_validator
+    }
+}
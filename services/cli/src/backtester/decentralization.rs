@@ -0,0 +1,179 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Stake of the pool on a validator with the validator's latest recorded details
+pub struct DelegatedValidator {
+    pub pool_stake_lamports: u64,
+    pub is_superminority: bool,
+    /// Unset when the validator never reported it
+    pub client_type: Option<u8>,
+    /// Unset when the validator never reported it
+    pub ip: Option<[u8; 4]>,
+    /// Part of the smallest set of validators holding more than a third of the network stake
+    pub in_nakamoto_set: bool,
+}
+
+/// Concentration of the stake of the pool at the end of an epoch
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct DecentralizationMetrics {
+    pub epoch: u16,
+    pub validators_delegated: u32,
+    /// Smallest number of pool validators holding more than a third of the pool stake
+    pub nakamoto_coefficient: u32,
+    /// Share of the pool stake on the validators that can halt the network together
+    pub nakamoto_set_share: f64,
+    /// Herfindahl-Hirschman index of the pool stake, between 1 / validators and 1
+    pub hhi: f64,
+    /// Gini coefficient of the pool stake between the delegated validators
+    pub gini: f64,
+    pub superminority_share: f64,
+    /// Share of the pool stake by client type, `unknown` when the validator never reported it
+    pub client_type_shares: BTreeMap<String, f64>,
+    /// Number of distinct /16 subnets the pool stake is on
+    pub ip_subnets: u32,
+    /// Largest share of the pool stake in a single /16 subnet
+    pub largest_ip_subnet_share: f64,
+}
+
+impl DecentralizationMetrics {
+    pub fn new(epoch: u16, validators: &[DelegatedValidator]) -> Self {
+        let stakes: Vec<u64> = validators
+            .iter()
+            .map(|validator| validator.pool_stake_lamports)
+            .filter(|stake| *stake > 0)
+            .collect();
+        let total: u64 = stakes.iter().sum();
+        if total == 0 {
+            return Self {
+                epoch,
+                ..Default::default()
+            };
+        }
+        let share = |lamports: u64| lamports as f64 / total as f64;
+        let share_where = |filter: &dyn Fn(&DelegatedValidator) -> bool| {
+            share(
+                validators
+                    .iter()
+                    .filter(|validator| filter(validator))
+                    .map(|validator| validator.pool_stake_lamports)
+                    .sum(),
+            )
+        };
+
+        let mut client_type_stakes: BTreeMap<String, u64> = BTreeMap::new();
+        let mut subnet_stakes: HashMap<[u8; 2], u64> = HashMap::new();
+        for validator in validators
+            .iter()
+            .filter(|validator| validator.pool_stake_lamports > 0)
+        {
+            let client_type = validator
+                .client_type
+                .map(|client_type| client_type.to_string())
+                .unwrap_or_else(|| "unknown".to_string());
+            *client_type_stakes.entry(client_type).or_default() += validator.pool_stake_lamports;
+            if let Some(ip) = validator.ip {
+                *subnet_stakes.entry([ip[0], ip[1]]).or_default() += validator.pool_stake_lamports;
+            }
+        }
+
+        Self {
+            epoch,
+            validators_delegated: stakes.len() as u32,
+            nakamoto_coefficient: nakamoto_coefficient(&stakes),
+            nakamoto_set_share: share_where(&|validator| validator.in_nakamoto_set),
+            hhi: herfindahl_hirschman_index(&stakes),
+            gini: gini_coefficient(&stakes),
+            superminority_share: share_where(&|validator| validator.is_superminority),
+            client_type_shares: client_type_stakes
+                .into_iter()
+                .map(|(client_type, lamports)| (client_type, share(lamports)))
+                .collect(),
+            ip_subnets: subnet_stakes.len() as u32,
+            largest_ip_subnet_share: subnet_stakes.values().copied().max().map_or(0.0, share),
+        }
+    }
+}
+
+/// Smallest number of stakes holding more than a third of the total
+pub fn nakamoto_coefficient(stakes: &[u64]) -> u32 {
+    let mut sorted = stakes.to_vec();
+    sorted.sort_unstable_by(|a, b| b.cmp(a));
+    let total: u128 = sorted.iter().map(|stake| u128::from(*stake)).sum();
+
+    let mut held = 0u128;
+    for (count, stake) in sorted.iter().enumerate() {
+        held += u128::from(*stake);
+        if held * 3 > total {
+            return count as u32 + 1;
+        }
+    }
+    0
+}
+
+pub fn herfindahl_hirschman_index(stakes: &[u64]) -> f64 {
+    let total: u64 = stakes.iter().sum();
+    if total == 0 {
+        return 0.0;
+    }
+    stakes
+        .iter()
+        .map(|stake| (*stake as f64 / total as f64).powi(2))
+        .sum()
+}
+
+/// 0 when every stake is equal, close to 1 when a single stake holds everything
+pub fn gini_coefficient(stakes: &[u64]) -> f64 {
+    let total: f64 = stakes.iter().map(|stake| *stake as f64).sum();
+    if stakes.is_empty() || total == 0.0 {
+        return 0.0;
+    }
+    let mut sorted = stakes.to_vec();
+    sorted.sort_unstable();
+    let n = sorted.len() as f64;
+    let weighted: f64 = sorted
+        .iter()
+        .enumerate()
+        .map(|(i, stake)| (i as f64 + 1.0) * *stake as f64)
+        .sum();
+    (2.0 * weighted) / (n * total) - (n + 1.0) / n
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concentration_metrics() {
+        assert_eq!(nakamoto_coefficient(&[10, 10, 10]), 2);
+        assert_eq!(nakamoto_coefficient(&[50, 10, 10, 10]), 1);
+        assert_eq!(nakamoto_coefficient(&[]), 0);
+
+        assert_eq!(herfindahl_hirschman_index(&[25, 25, 25, 25]), 0.25);
+        assert_eq!(herfindahl_hirschman_index(&[100]), 1.0);
+
+        assert_eq!(gini_coefficient(&[5, 5, 5, 5]), 0.0);
+        assert_eq!(gini_coefficient(&[0, 0, 0, 100]), 0.75);
+
+        let validator =
+            |pool_stake_lamports, is_superminority, ip: Option<[u8; 4]>| DelegatedValidator {
+                pool_stake_lamports,
+                is_superminority,
+                client_type: None,
+                ip,
+                in_nakamoto_set: is_superminority,
+            };
+        let metrics = DecentralizationMetrics::new(
+            700,
+            &[
+                validator(300, true, Some([10, 0, 0, 1])),
+                validator(100, false, Some([10, 0, 1, 1])),
+                validator(0, false, Some([20, 0, 0, 1])),
+            ],
+        );
+        assert_eq!(metrics.validators_delegated, 2);
+        assert_eq!(metrics.superminority_share, 0.75);
+        assert_eq!(metrics.ip_subnets, 1);
+        assert_eq!(metrics.largest_ip_subnet_share, 1.0);
+        assert_eq!(metrics.client_type_shares["unknown"], 1.0);
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{commands::DAYS_PER_YEAR, utils::calculate_apy};
+use serde::{Deserialize, Serialize};
+use stakenet_simulator_db::domain::VotePubkey;
+use std::{collections::HashMap, str::FromStr};
+
+/// Stake the pool delegates to a validator outside of the steward scoring
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DirectedStakeAmount {
+    Lamports(u64),
+    /// Share of the whole pool
+    Bps(u16),
+}
+
+/// A directed stake target, written as `<vote account>:<lamports>` or `<vote account>:<bps>bps`
+/// on the CLI and as `{ vote_account = "...", lamports = ... }` or
+/// `{ vote_account = "...", bps = ... }` in scenario files
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct DirectedStakeTarget {
+    pub vote_account: VotePubkey,
+    #[serde(flatten)]
+    pub amount: DirectedStakeAmount,
+}
+
+impl FromStr for DirectedStakeTarget {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (vote_account, amount) = value
+            .split_once(':')
+            .ok_or_else(|| format!("expected <vote account>:<amount>, got {}", value))?;
+        let vote_account = vote_account
+            .parse()
+            .map_err(|_| format!("invalid vote account {}", vote_account))?;
+        let amount = match amount.strip_suffix("bps") {
+            Some(bps) => DirectedStakeAmount::Bps(
+                bps.parse()
+                    .map_err(|_| format!("invalid bps amount {}", amount))?,
+            ),
+            None => DirectedStakeAmount::Lamports(
+                amount
+                    .parse()
+                    .map_err(|_| format!("invalid lamports amount {}", amount))?,
+            ),
+        };
+        Ok(Self {
+            vote_account,
+            amount,
+        })
+    }
+}
+
+/// Target stake of every directed validator for a pool of `pool_lamports`. Targets are honored
+/// in order and the ones that don't fit in the pool are cut down.
+pub fn directed_stake_targets(
+    directed_stake: &[DirectedStakeTarget],
+    pool_lamports: u64,
+) -> HashMap<VotePubkey, u64> {
+    let mut remaining = pool_lamports;
+    let mut targets = HashMap::new();
+    for target in directed_stake {
+        let lamports = match target.amount {
+            DirectedStakeAmount::Lamports(lamports) => lamports,
+            DirectedStakeAmount::Bps(bps) => {
+                (u128::from(pool_lamports) * u128::from(bps) / 10_000) as u64
+            }
+        }
+        .min(remaining);
+        remaining -= lamports;
+        *targets.entry(target.vote_account).or_default() += lamports;
+    }
+    targets
+}
+
+/// Compounded return of the stake over the simulated epochs, from the rewards earned on the
+/// active stake of every epoch
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct EpochReturns {
+    pub epochs: u16,
+    pub rewards_lamports: u64,
+    pub compounded_return: f64,
+}
+
+impl EpochReturns {
+    pub fn new() -> Self {
+        Self {
+            epochs: 0,
+            rewards_lamports: 0,
+            compounded_return: 1.0,
+        }
+    }
+
+    pub fn record_epoch(&mut self, active_lamports: u64, rewards_lamports: u64) {
+        self.epochs += 1;
+        self.rewards_lamports += rewards_lamports;
+        if active_lamports > 0 {
+            self.compounded_return *= 1.0 + rewards_lamports as f64 / active_lamports as f64;
+        }
+    }
+
+    /// Assuming 2 days per epoch like the aggregated APY
+    pub fn apy(&self) -> f64 {
+        if self.epochs == 0 {
+            return 0.0;
+        }
+        calculate_apy(
+            self.compounded_return - 1.0,
+            f64::from(self.epochs) * 2.0,
+            DAYS_PER_YEAR,
+        )
+    }
+}
+
+/// Outcome of the directed part of the pool, next to the part delegated by the steward
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DirectedStakeReport {
+    pub targets: Vec<DirectedStakeTarget>,
+    /// Stake of the pool on every directed validator at the end of the simulation
+    pub final_stake_lamports: HashMap<VotePubkey, u64>,
+    pub directed_rewards_lamports: u64,
+    pub directed_apy: f64,
+    pub steward_rewards_lamports: u64,
+    pub steward_apy: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+
+    #[test]
+    fn test_directed_stake_targets() {
+        let [a, b, c] = [(); 3].map(|_| VotePubkey(Pubkey::new_unique()));
+        let directed_stake: Vec<DirectedStakeTarget> = [
+            format!("{a}:600"),
+            format!("{b}:2500bps"),
+            format!("{c}:600"),
+        ]
+        .iter()
+        .map(|target| target.parse().unwrap())
+        .collect();
+        assert_eq!(directed_stake[1].amount, DirectedStakeAmount::Bps(2500));
+        assert!(a.to_string().parse::<DirectedStakeTarget>().is_err());
+        assert!("a:600".parse::<DirectedStakeTarget>().is_err());
+
+        // `c` only gets what is left of the pool
+        let targets = directed_stake_targets(&directed_stake, 1000);
+        assert_eq!(targets[&a], 600);
+        assert_eq!(targets[&b], 250);
+        assert_eq!(targets[&c], 150);
+
+        let mut returns = EpochReturns::new();
+        returns.record_epoch(1000, 10);
+        returns.record_epoch(1010, 0);
+        assert_eq!(returns.rewards_lamports, 10);
+        assert!((returns.compounded_return - 1.01).abs() < 1e-12);
+    }
+}
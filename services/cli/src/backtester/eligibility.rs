@@ -0,0 +1,158 @@
+use crate::backtester::BacktestDataset;
+use clap::Args;
+use serde::{Deserialize, Serialize};
+use stakenet_simulator_db::domain::VotePubkey;
+use std::collections::HashSet;
+
+/// Allow and deny lists of vote accounts, from the CLI or a scenario file
+#[derive(Args, Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ValidatorListOptions {
+    /// Only delegate to these vote accounts, comma separated. Every validator is allowed when
+    /// not set
+    #[arg(long, env, value_delimiter = ',')]
+    pub allow_validators: Vec<VotePubkey>,
+    /// Never delegate to these vote accounts, comma separated
+    #[arg(long, env, value_delimiter = ',')]
+    pub deny_validators: Vec<VotePubkey>,
+    /// Delegate to the validators of the recorded steward blacklist
+    #[arg(long, env, num_args = 0..=1, default_missing_value = "true")]
+    pub ignore_steward_blacklist: Option<bool>,
+}
+
+impl ValidatorListOptions {
+    /// Sets every option that is unset from the fallback
+    pub fn fill_unset_from(&mut self, fallback: &Self) {
+        if self.allow_validators.is_empty() {
+            self.allow_validators = fallback.allow_validators.clone();
+        }
+        if self.deny_validators.is_empty() {
+            self.deny_validators = fallback.deny_validators.clone();
+        }
+        self.ignore_steward_blacklist = self
+            .ignore_steward_blacklist
+            .or(fallback.ignore_steward_blacklist);
+    }
+
+    pub fn build(&self) -> ValidatorFilter {
+        ValidatorFilter {
+            allow: (!self.allow_validators.is_empty())
+                .then(|| self.allow_validators.iter().copied().collect()),
+            deny: self.deny_validators.iter().copied().collect(),
+            use_steward_blacklist: !self.ignore_steward_blacklist.unwrap_or(false),
+        }
+    }
+}
+
+/// Decides which validators the simulated steward may delegate to in an epoch. Excluded
+/// validators are never selected and are instantly unstaked when they were delegated to.
+#[derive(Clone, Debug)]
+pub struct ValidatorFilter {
+    /// Only these validators can be delegated to, every validator when unset
+    pub allow: Option<HashSet<VotePubkey>>,
+    pub deny: HashSet<VotePubkey>,
+    /// Exclude the validators of the steward blacklist recorded at or before the epoch
+    pub use_steward_blacklist: bool,
+}
+
+impl Default for ValidatorFilter {
+    fn default() -> Self {
+        Self {
+            allow: None,
+            deny: HashSet::new(),
+            use_steward_blacklist: true,
+        }
+    }
+}
+
+impl ValidatorFilter {
+    /// Returns true if the validator can't be delegated to in the epoch
+    pub fn is_excluded(
+        &self,
+        dataset: &BacktestDataset,
+        vote_account: &VotePubkey,
+        epoch: u16,
+    ) -> bool {
+        if self
+            .allow
+            .as_ref()
+            .is_some_and(|allow| !allow.contains(vote_account))
+        {
+            return true;
+        }
+        if self.deny.contains(vote_account) {
+            return true;
+        }
+        self.use_steward_blacklist
+            && dataset
+                .steward_blacklist_at(epoch)
+                .is_some_and(|blacklist| blacklist.vote_pubkeys.contains(vote_account))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtester::{SyntheticDataset, SyntheticDatasetConfig};
+    use stakenet_simulator_db::{domain::Epoch, steward_blacklist::StewardBlacklist};
+
+    #[tokio::test]
+    async fn test_validator_filter() {
+        let synthetic = SyntheticDataset::generate(&SyntheticDatasetConfig {
+            num_validators: 3,
+            start_epoch: 700,
+            end_epoch: 709,
+            seed: 1,
+            behaviors: Vec::new(),
+        });
+        let vote_accounts: Vec<VotePubkey> =
+            (0..3).map(|index| synthetic.vote_account(index)).collect();
+        let blacklist = |epoch: u64, validators: &[usize]| StewardBlacklist {
+            epoch: Epoch(epoch),
+            vote_pubkeys: validators
+                .iter()
+                .map(|index| synthetic.vote_account(*index))
+                .collect(),
+        };
+        let steward_blacklists = vec![blacklist(703, &[0]), blacklist(706, &[1])];
+        let store = synthetic.into_store().await.unwrap();
+        let mut dataset = BacktestDataset::load(&store, 702, 709, 2).await.unwrap();
+        dataset.steward_blacklists = steward_blacklists;
+
+        // The latest blacklist recorded at or before the epoch applies
+        let blacklist_epoch = |epoch| {
+            dataset
+                .steward_blacklist_at(epoch)
+                .map(|blacklist| blacklist.epoch)
+        };
+        assert_eq!(blacklist_epoch(702), None);
+        assert_eq!(blacklist_epoch(703), Some(Epoch(703)));
+        assert_eq!(blacklist_epoch(705), Some(Epoch(703)));
+        assert_eq!(blacklist_epoch(709), Some(Epoch(706)));
+
+        let filter = ValidatorListOptions::default().build();
+        assert!(!filter.is_excluded(&dataset, &vote_accounts[0], 702));
+        assert!(filter.is_excluded(&dataset, &vote_accounts[0], 704));
+        assert!(!filter.is_excluded(&dataset, &vote_accounts[0], 707));
+        assert!(filter.is_excluded(&dataset, &vote_accounts[1], 707));
+        assert!(!filter.is_excluded(&dataset, &vote_accounts[2], 707));
+
+        let filter = ValidatorListOptions {
+            ignore_steward_blacklist: Some(true),
+            ..Default::default()
+        }
+        .build();
+        assert!(!filter.is_excluded(&dataset, &vote_accounts[0], 704));
+
+        let filter = ValidatorListOptions {
+            allow_validators: vec![vote_accounts[0], vote_accounts[2]],
+            deny_validators: vec![vote_accounts[2]],
+            ignore_steward_blacklist: None,
+        }
+        .build();
+        assert!(!filter.is_excluded(&dataset, &vote_accounts[0], 702));
+        assert!(filter.is_excluded(&dataset, &vote_accounts[0], 704));
+        assert!(filter.is_excluded(&dataset, &vote_accounts[1], 702));
+        assert!(filter.is_excluded(&dataset, &vote_accounts[2], 702));
+    }
+}
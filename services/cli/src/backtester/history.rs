@@ -0,0 +1,142 @@
+use crate::{
+    backtester::{
+        BacktestReport, CoverageReport, RebalancingCycle, TurnoverReport, ValidatorResult,
+    },
+    error::CliError,
+};
+use sqlx::{
+    Pool, Postgres,
+    types::{Json, chrono::Utc},
+};
+use stakenet_simulator_db::{
+    backtest_cycle::BacktestCycle, backtest_run::BacktestRun,
+    backtest_validator_result::BacktestValidatorResult, bulk_insert::InsertMode,
+};
+use uuid::Uuid;
+
+/// Stores the report in the backtest history tables and returns the id of the run. The run, its
+/// cycles and its validator results are written in one transaction, so a failure leaves no
+/// partial run behind.
+pub async fn save_report(
+    db_connection: &Pool<Postgres>,
+    report: &BacktestReport,
+) -> Result<String, CliError> {
+    let mut transaction = db_connection.begin().await?;
+
+    let id = BacktestRun::insert(
+        &mut *transaction,
+        BacktestRun {
+            id: Uuid::new_v4().to_string(),
+            created_at: Utc::now(),
+            git_revision: report.git_revision.clone(),
+            scenario: report.scenario.clone(),
+            start_epoch: report.simulation_start_epoch.into(),
+            end_epoch: report.simulation_end_epoch.into(),
+            seed: report.seed,
+            number_of_validator_delegations: u32::try_from(report.number_of_validator_delegations)
+                .map_err(|_| CliError::ArithmeticError)?,
+            delegation_strategy: report.delegation_strategy.clone(),
+            scoring_model: report.scoring_model.clone(),
+            parameters: Json(serde_json::to_value(&report.parameters)?),
+            aggregated_apy: report.aggregated_apy,
+            stake_utilization_ratio: report.stake_utilization_ratio,
+            final_apy: report.final_apy,
+        },
+    )
+    .await?;
+
+    let cycles = report
+        .rebalancing_cycles
+        .iter()
+        .enumerate()
+        .map(|(cycle_index, cycle)| BacktestCycle {
+            run_id: id.clone(),
+            cycle_index: cycle_index as u32,
+            start_epoch: cycle.start_epoch.into(),
+            end_epoch: cycle.end_epoch.into(),
+            starting_total_lamports: cycle.starting_total_lamports.into(),
+            ending_total_lamports: cycle.ending_total_lamports.into(),
+        })
+        .collect();
+    BacktestCycle::bulk_insert(&mut *transaction, cycles, InsertMode::InsertIgnore).await?;
+
+    let validator_results = report
+        .validator_results
+        .iter()
+        .map(|result| BacktestValidatorResult {
+            run_id: id.clone(),
+            vote_pubkey: result.vote_account,
+            cycles_selected: result.cycles_selected,
+            last_score: result.last_score,
+            final_stake_lamports: result.final_stake_lamports.into(),
+        })
+        .collect();
+    BacktestValidatorResult::bulk_insert(
+        &mut *transaction,
+        validator_results,
+        InsertMode::InsertIgnore,
+    )
+    .await?;
+
+    transaction.commit().await?;
+    Ok(id)
+}
+
+/// Rebuilds the report of a saved run
+pub async fn load_report(
+    db_connection: &Pool<Postgres>,
+    id: &str,
+) -> Result<BacktestReport, CliError> {
+    let run = BacktestRun::fetch(db_connection, id)
+        .await?
+        .ok_or_else(|| CliError::BacktestRunNotFound(id.to_string()))?;
+    let cycles = BacktestCycle::fetch_for_run(db_connection, id).await?;
+    let validator_results = BacktestValidatorResult::fetch_for_run(db_connection, id).await?;
+
+    Ok(BacktestReport {
+        scenario: run.scenario,
+        git_revision: run.git_revision,
+        simulation_start_epoch: u16::try_from(run.start_epoch)
+            .map_err(|_| CliError::ArithmeticError)?,
+        simulation_end_epoch: u16::try_from(run.end_epoch)
+            .map_err(|_| CliError::ArithmeticError)?,
+        seed: run.seed,
+        number_of_validator_delegations: run.number_of_validator_delegations as usize,
+        delegation_strategy: run.delegation_strategy,
+        scoring_model: run.scoring_model,
+        parameters: serde_json::from_value(run.parameters.0)?,
+        rebalancing_cycles: cycles
+            .into_iter()
+            .map(|cycle| {
+                Ok(RebalancingCycle {
+                    start_epoch: u16::try_from(cycle.start_epoch)
+                        .map_err(|_| CliError::ArithmeticError)?,
+                    end_epoch: u16::try_from(cycle.end_epoch)
+                        .map_err(|_| CliError::ArithmeticError)?,
+                    starting_total_lamports: cycle.starting_total_lamports.into(),
+                    ending_total_lamports: cycle.ending_total_lamports.into(),
+                })
+            })
+            .collect::<Result<_, CliError>>()?,
+        validator_results: validator_results
+            .into_iter()
+            .map(|result| ValidatorResult {
+                vote_account: result.vote_pubkey,
+                cycles_selected: result.cycles_selected,
+                last_score: result.last_score,
+                final_stake_lamports: result.final_stake_lamports.into(),
+            })
+            .collect(),
+        directed_stake: None,
+        decentralization: Vec::new(),
+        turnover: TurnoverReport::default(),
+        phase_transitions: Vec::new(),
+        coverage: CoverageReport::default(),
+        scoring_summaries: Vec::new(),
+        score_breakdowns: Vec::new(),
+        epoch_traces: Vec::new(),
+        aggregated_apy: run.aggregated_apy,
+        stake_utilization_ratio: run.stake_utilization_ratio,
+        final_apy: run.final_apy,
+    })
+}
@@ -0,0 +1,44 @@
+pub mod audit;
+pub use audit::*;
+
+pub mod backtest;
+pub use backtest::*;
+
+pub mod dataset;
+pub use dataset::*;
+
+pub mod coverage;
+pub use coverage::*;
+
+pub mod decentralization;
+pub use decentralization::*;
+
+pub mod directed;
+pub use directed::*;
+
+pub mod eligibility;
+pub use eligibility::*;
+
+pub mod history;
+pub use history::*;
+
+pub mod phases;
+pub use phases::*;
+
+pub mod scoring;
+pub use scoring::*;
+
+pub mod strategy;
+pub use strategy::*;
+
+pub mod synthetic;
+pub use synthetic::*;
+
+pub mod timing;
+pub use timing::*;
+
+pub mod turnover;
+pub use turnover::*;
+
+pub mod validation;
+pub use validation::*;
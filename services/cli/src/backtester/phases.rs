@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+/// States of the on-chain steward program
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StewardPhase {
+    ComputeScores,
+    ComputeDelegations,
+    Idle,
+    ComputeInstantUnstake,
+    Rebalance,
+}
+
+/// The simulated steward entering a state. Transitions are a trace derived from the steps the
+/// simulator runs, placed at the slots the steward program would run them at, rather than the
+/// output of the program's state machine.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PhaseTransition {
+    pub epoch: u16,
+    pub slot: u64,
+    pub phase: StewardPhase,
+}
+
+/// Slots the simulated steward steps through an epoch at
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EpochSlots {
+    pub epoch_start: u64,
+    /// End of the `compute_score_slot_range` slots of scoring, where the delegations are computed
+    /// and the stake is rebalanced at the start of a cycle
+    pub delegations: u64,
+    /// Instant unstakes are computed once `instant_unstake_epoch_progress` of the epoch passed,
+    /// after the delegations
+    pub instant_unstake: u64,
+}
+
+impl EpochSlots {
+    pub fn new(
+        epoch_start_slot: u64,
+        epoch_last_slot: u64,
+        compute_score_slot_range: u64,
+        instant_unstake_slot: u64,
+    ) -> Self {
+        let delegations = epoch_start_slot
+            .saturating_add(compute_score_slot_range)
+            .min(epoch_last_slot);
+        Self {
+            epoch_start: epoch_start_slot,
+            delegations,
+            instant_unstake: instant_unstake_slot.max(delegations),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_epoch_slots() {
+        let slots = EpochSlots::new(1000, 1999, 100, 1900);
+        assert_eq!(slots.delegations, 1100);
+        assert_eq!(slots.instant_unstake, 1900);
+
+        // Scoring can't run past the epoch and instant unstakes wait for the delegations
+        let slots = EpochSlots::new(2000, 2999, 5000, 2900);
+        assert_eq!(slots.delegations, 2999);
+        assert_eq!(slots.instant_unstake, 2999);
+    }
+}
@@ -0,0 +1,710 @@
+use crate::backtester::validator_historical_start_offset;
+use clap::{Args, ValueEnum};
+use jito_steward::{Config, constants::TVC_ACTIVATION_EPOCH, score::validator_score};
+use serde::{Deserialize, Serialize};
+use stakenet_simulator_db::domain::VotePubkey;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+    sync::Arc,
+};
+use validator_history::{ClusterHistory as JitoClusterHistory, ValidatorHistory};
+
+/// Vote credits a validator earns for voting on a block with timely vote credits
+const VOTE_CREDITS_PER_BLOCK: u64 = 16;
+
+/// Score of a validator along with the components it was computed from
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ScoreBreakdown {
+    pub score: f64,
+    /// Value of every component of the score by name
+    pub components: BTreeMap<String, f64>,
+}
+
+/// Score breakdown of a validator in a scoring epoch, or the reason it couldn't be scored
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ValidatorScoreRecord {
+    pub epoch: u16,
+    pub vote_account: VotePubkey,
+    /// Score the validator was ranked with, 0 when scoring failed
+    pub score: f64,
+    pub components: BTreeMap<String, f64>,
+    pub error: Option<String>,
+}
+
+/// Outcome of the scoring of all validators in a scoring epoch
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ScoringEpochSummary {
+    pub epoch: u16,
+    pub validators_scored: u32,
+    pub zero_scores: u32,
+    /// Number of scored validators excluded by the allow/deny lists or the steward blacklist
+    #[serde(default)]
+    pub excluded: u32,
+    /// Number of validators with each component at 0, a component that is 0 for every validator
+    /// usually points at missing data
+    pub zero_components: BTreeMap<String, u32>,
+    /// Number of validators that failed to score, by error
+    pub errors: BTreeMap<String, u32>,
+}
+
+impl ScoringEpochSummary {
+    pub fn from_records(epoch: u16, records: &[ValidatorScoreRecord]) -> Self {
+        let mut summary = Self {
+            epoch,
+            ..Default::default()
+        };
+        for record in records {
+            summary.validators_scored += 1;
+            if record.score == 0.0 {
+                summary.zero_scores += 1;
+            }
+            for (name, value) in &record.components {
+                if *value == 0.0 {
+                    *summary.zero_components.entry(name.clone()).or_default() += 1;
+                }
+            }
+            if let Some(error) = &record.error {
+                *summary.errors.entry(error.clone()).or_default() += 1;
+            }
+        }
+        summary
+    }
+}
+
+/// Writes the score breakdowns as CSV, with a column for every component recorded in any of them
+pub fn write_score_breakdowns_csv(
+    writer: impl Write,
+    records: &[ValidatorScoreRecord],
+) -> Result<(), csv::Error> {
+    let component_names: Vec<&String> = records
+        .iter()
+        .flat_map(|record| record.components.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    let mut writer = csv::Writer::from_writer(writer);
+    let mut header = vec!["epoch", "vote_account", "score"];
+    header.extend(component_names.iter().map(|name| name.as_str()));
+    header.push("error");
+    writer.write_record(&header)?;
+
+    for record in records {
+        let mut row = vec![
+            record.epoch.to_string(),
+            record.vote_account.to_string(),
+            record.score.to_string(),
+        ];
+        row.extend(component_names.iter().map(|name| {
+            record
+                .components
+                .get(*name)
+                .map(|value| value.to_string())
+                .unwrap_or_default()
+        }));
+        row.push(record.error.clone().unwrap_or_default());
+        writer.write_record(&row)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Scores validators at the start of every steward cycle. Validators with a score of 0 are never
+/// delegated to.
+pub trait ScoringModel: Send + Sync {
+    /// Name of the model with its settings, recorded in the backtest report
+    fn name(&self) -> String;
+
+    /// Number of epochs of history before the scored epoch the model reads
+    fn lookback_epochs(&self, config: &Config) -> u16 {
+        validator_historical_start_offset(config)
+    }
+
+    /// Scores the validator, or returns the reason it can't be scored
+    fn score(
+        &self,
+        validator_history: &ValidatorHistory,
+        cluster_history: &JitoClusterHistory,
+        config: &Config,
+        current_epoch: u16,
+    ) -> Result<ScoreBreakdown, String>;
+}
+
+/// The scoring of the on-chain steward program
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OnChainScoringModel;
+
+impl ScoringModel for OnChainScoringModel {
+    fn name(&self) -> String {
+        "on-chain".to_string()
+    }
+
+    fn score(
+        &self,
+        validator_history: &ValidatorHistory,
+        cluster_history: &JitoClusterHistory,
+        config: &Config,
+        current_epoch: u16,
+    ) -> Result<ScoreBreakdown, String> {
+        let score = validator_score(
+            validator_history,
+            cluster_history,
+            config,
+            current_epoch,
+            TVC_ACTIVATION_EPOCH,
+        )
+        .map_err(|error| error.to_string())?;
+
+        let components = [
+            ("raw_score", score.raw_score),
+            ("mev_commission_score", score.mev_commission_score),
+            ("blacklisted_score", score.blacklisted_score),
+            ("superminority_score", score.superminority_score),
+            ("delinquency_score", score.delinquency_score),
+            ("running_jito_score", score.running_jito_score),
+            ("commission_score", score.commission_score),
+            (
+                "historical_commission_score",
+                score.historical_commission_score,
+            ),
+            ("vote_credits_ratio", score.vote_credits_ratio),
+            (
+                "merkle_root_upload_authority_score",
+                score.merkle_root_upload_authority_score,
+            ),
+            (
+                "priority_fee_commission_score",
+                score.priority_fee_commission_score,
+            ),
+            (
+                "priority_fee_merkle_root_upload_authority_score",
+                score.priority_fee_merkle_root_upload_authority_score,
+            ),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect();
+
+        Ok(ScoreBreakdown {
+            score: score.score,
+            components,
+        })
+    }
+}
+
+/// Weighted average of components between 0 and 1 over the last `lookback_epochs` epochs.
+/// A validator that exceeds any of the thresholds gets a score of 0.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct WeightedScoringModel {
+    pub lookback_epochs: u16,
+    pub mev_commission_weight: f64,
+    pub commission_weight: f64,
+    pub epoch_credits_weight: f64,
+    pub blocks_produced_weight: f64,
+    pub priority_fee_commission_weight: f64,
+    pub max_mev_commission_bps: u16,
+    pub max_commission: u8,
+    pub max_priority_fee_commission_bps: u16,
+    /// Minimum share of the possible vote credits the validator must have earned
+    pub min_epoch_credits_ratio: f64,
+}
+
+impl Default for WeightedScoringModel {
+    fn default() -> Self {
+        Self {
+            lookback_epochs: 30,
+            mev_commission_weight: 1.0,
+            commission_weight: 1.0,
+            epoch_credits_weight: 1.0,
+            blocks_produced_weight: 1.0,
+            priority_fee_commission_weight: 1.0,
+            max_mev_commission_bps: 1000,
+            max_commission: 5,
+            max_priority_fee_commission_bps: 5000,
+            min_epoch_credits_ratio: 0.9,
+        }
+    }
+}
+
+impl ScoringModel for WeightedScoringModel {
+    fn name(&self) -> String {
+        format!(
+            "weighted({})",
+            serde_json::to_string(self).unwrap_or_default()
+        )
+    }
+
+    fn lookback_epochs(&self, _config: &Config) -> u16 {
+        self.lookback_epochs
+    }
+
+    fn score(
+        &self,
+        validator_history: &ValidatorHistory,
+        cluster_history: &JitoClusterHistory,
+        _config: &Config,
+        current_epoch: u16,
+    ) -> Result<ScoreBreakdown, String> {
+        let start_epoch = current_epoch.saturating_sub(self.lookback_epochs);
+        let entries: Vec<_> = validator_history
+            .history
+            .epoch_range(start_epoch, current_epoch)
+            .into_iter()
+            .flatten()
+            .collect();
+        if entries.is_empty() {
+            return Err(format!(
+                "no validator history between epochs {} and {}",
+                start_epoch, current_epoch
+            ));
+        }
+
+        // Fields that were never recorded hold their max value
+        let mev_commissions: Vec<u16> = entries
+            .iter()
+            .map(|entry| entry.mev_commission)
+            .filter(|commission| *commission != u16::MAX)
+            .collect();
+        let commissions: Vec<u8> = entries
+            .iter()
+            .map(|entry| entry.commission)
+            .filter(|commission| *commission != u8::MAX)
+            .collect();
+        let priority_fee_commissions: Vec<u16> = entries
+            .iter()
+            .map(|entry| entry.priority_fee_commission)
+            .filter(|commission| *commission != u16::MAX)
+            .collect();
+
+        let within_commission_thresholds = !(mev_commissions
+            .iter()
+            .any(|commission| *commission > self.max_mev_commission_bps)
+            || commissions
+                .iter()
+                .any(|commission| *commission > self.max_commission)
+            || priority_fee_commissions
+                .iter()
+                .any(|commission| *commission > self.max_priority_fee_commission_bps));
+
+        let total_blocks: u64 = cluster_history
+            .history
+            .epoch_range(start_epoch, current_epoch)
+            .into_iter()
+            .flatten()
+            .filter(|entry| {
+                entry.total_blocks != u32::MAX
+                    && entries
+                        .iter()
+                        .any(|validator_entry| validator_entry.epoch == entry.epoch)
+            })
+            .map(|entry| u64::from(entry.total_blocks))
+            .sum();
+        let epoch_credits: u64 = entries
+            .iter()
+            .map(|entry| entry.epoch_credits)
+            .filter(|credits| *credits != u32::MAX)
+            .map(u64::from)
+            .sum();
+        let epoch_credits_ratio = if total_blocks == 0 {
+            0.0
+        } else {
+            (epoch_credits as f64 / (total_blocks * VOTE_CREDITS_PER_BLOCK) as f64).min(1.0)
+        };
+        let above_min_epoch_credits = epoch_credits_ratio >= self.min_epoch_credits_ratio;
+
+        let (blocks_produced, leader_slots) = entries
+            .iter()
+            .filter(|entry| {
+                entry.blocks_produced != u32::MAX && entry.total_leader_slots != u32::MAX
+            })
+            .fold((0u64, 0u64), |(blocks, slots), entry| {
+                (
+                    blocks + u64::from(entry.blocks_produced),
+                    slots + u64::from(entry.total_leader_slots),
+                )
+            });
+        let blocks_produced_ratio = if leader_slots == 0 {
+            1.0
+        } else {
+            (blocks_produced as f64 / leader_slots as f64).min(1.0)
+        };
+
+        let mean = |values: Vec<f64>| {
+            if values.is_empty() {
+                // A validator that never recorded the commission doesn't charge it
+                1.0
+            } else {
+                values.iter().sum::<f64>() / values.len() as f64
+            }
+        };
+        let weighted_components = [
+            (
+                "mev_commission_score",
+                self.mev_commission_weight,
+                mean(
+                    mev_commissions
+                        .iter()
+                        .map(|commission| 1.0 - f64::from(*commission) / 10_000.0)
+                        .collect(),
+                ),
+            ),
+            (
+                "commission_score",
+                self.commission_weight,
+                mean(
+                    commissions
+                        .iter()
+                        .map(|commission| 1.0 - f64::from(*commission) / 100.0)
+                        .collect(),
+                ),
+            ),
+            (
+                "epoch_credits_ratio",
+                self.epoch_credits_weight,
+                epoch_credits_ratio,
+            ),
+            (
+                "blocks_produced_ratio",
+                self.blocks_produced_weight,
+                blocks_produced_ratio,
+            ),
+            (
+                "priority_fee_commission_score",
+                self.priority_fee_commission_weight,
+                mean(
+                    priority_fee_commissions
+                        .iter()
+                        .map(|commission| 1.0 - f64::from(*commission) / 10_000.0)
+                        .collect(),
+                ),
+            ),
+        ];
+
+        let total_weight: f64 = weighted_components
+            .iter()
+            .map(|(_, weight, _)| weight)
+            .sum();
+        let weighted_score = if total_weight <= 0.0 {
+            0.0
+        } else {
+            weighted_components
+                .iter()
+                .map(|(_, weight, component)| weight * component)
+                .sum::<f64>()
+                / total_weight
+        };
+        let commission_threshold_score = if within_commission_thresholds {
+            1.0
+        } else {
+            0.0
+        };
+        let epoch_credits_threshold_score = if above_min_epoch_credits { 1.0 } else { 0.0 };
+
+        let mut components: BTreeMap<String, f64> = weighted_components
+            .iter()
+            .map(|(name, _, component)| (name.to_string(), *component))
+            .collect();
+        components.insert(
+            "commission_threshold_score".to_string(),
+            commission_threshold_score,
+        );
+        components.insert(
+            "epoch_credits_threshold_score".to_string(),
+            epoch_credits_threshold_score,
+        );
+        components.insert("weighted_score".to_string(), weighted_score);
+
+        Ok(ScoreBreakdown {
+            score: weighted_score * commission_threshold_score * epoch_credits_threshold_score,
+            components,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScoringModelKind {
+    #[default]
+    OnChain,
+    Weighted,
+}
+
+/// Selection of the scoring model, from the CLI or a scenario file. The weights and thresholds
+/// only apply to the `weighted` model, unset ones use the defaults of [`WeightedScoringModel`].
+#[derive(Args, Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScoringModelOptions {
+    /// Scoring model of the simulated steward, defaults to the on-chain scoring
+    #[arg(long, env, value_enum)]
+    pub scoring_model: Option<ScoringModelKind>,
+    #[arg(long, env)]
+    pub scoring_lookback_epochs: Option<u16>,
+    #[arg(long, env)]
+    pub mev_commission_weight: Option<f64>,
+    #[arg(long, env)]
+    pub commission_weight: Option<f64>,
+    #[arg(long, env)]
+    pub epoch_credits_weight: Option<f64>,
+    #[arg(long, env)]
+    pub blocks_produced_weight: Option<f64>,
+    #[arg(long, env)]
+    pub priority_fee_commission_weight: Option<f64>,
+    #[arg(long, env)]
+    pub max_mev_commission_bps: Option<u16>,
+    #[arg(long, env)]
+    pub max_commission: Option<u8>,
+    #[arg(long, env)]
+    pub max_priority_fee_commission_bps: Option<u16>,
+    #[arg(long, env)]
+    pub min_epoch_credits_ratio: Option<f64>,
+}
+
+impl ScoringModelOptions {
+    /// Sets every option that is unset from the fallback
+    pub fn fill_unset_from(&mut self, fallback: &Self) {
+        self.scoring_model = self.scoring_model.or(fallback.scoring_model);
+        self.scoring_lookback_epochs = self
+            .scoring_lookback_epochs
+            .or(fallback.scoring_lookback_epochs);
+        self.mev_commission_weight = self
+            .mev_commission_weight
+            .or(fallback.mev_commission_weight);
+        self.commission_weight = self.commission_weight.or(fallback.commission_weight);
+        self.epoch_credits_weight = self.epoch_credits_weight.or(fallback.epoch_credits_weight);
+        self.blocks_produced_weight = self
+            .blocks_produced_weight
+            .or(fallback.blocks_produced_weight);
+        self.priority_fee_commission_weight = self
+            .priority_fee_commission_weight
+            .or(fallback.priority_fee_commission_weight);
+        self.max_mev_commission_bps = self
+            .max_mev_commission_bps
+            .or(fallback.max_mev_commission_bps);
+        self.max_commission = self.max_commission.or(fallback.max_commission);
+        self.max_priority_fee_commission_bps = self
+            .max_priority_fee_commission_bps
+            .or(fallback.max_priority_fee_commission_bps);
+        self.min_epoch_credits_ratio = self
+            .min_epoch_credits_ratio
+            .or(fallback.min_epoch_credits_ratio);
+    }
+
+    pub fn build(&self) -> Arc<dyn ScoringModel> {
+        match self.scoring_model.unwrap_or_default() {
+            ScoringModelKind::OnChain => Arc::new(OnChainScoringModel),
+            ScoringModelKind::Weighted => {
+                let defaults = WeightedScoringModel::default();
+                Arc::new(WeightedScoringModel {
+                    lookback_epochs: self
+                        .scoring_lookback_epochs
+                        .unwrap_or(defaults.lookback_epochs),
+                    mev_commission_weight: self
+                        .mev_commission_weight
+                        .unwrap_or(defaults.mev_commission_weight),
+                    commission_weight: self.commission_weight.unwrap_or(defaults.commission_weight),
+                    epoch_credits_weight: self
+                        .epoch_credits_weight
+                        .unwrap_or(defaults.epoch_credits_weight),
+                    blocks_produced_weight: self
+                        .blocks_produced_weight
+                        .unwrap_or(defaults.blocks_produced_weight),
+                    priority_fee_commission_weight: self
+                        .priority_fee_commission_weight
+                        .unwrap_or(defaults.priority_fee_commission_weight),
+                    max_mev_commission_bps: self
+                        .max_mev_commission_bps
+                        .unwrap_or(defaults.max_mev_commission_bps),
+                    max_commission: self.max_commission.unwrap_or(defaults.max_commission),
+                    max_priority_fee_commission_bps: self
+                        .max_priority_fee_commission_bps
+                        .unwrap_or(defaults.max_priority_fee_commission_bps),
+                    min_epoch_credits_ratio: self
+                        .min_epoch_credits_ratio
+                        .unwrap_or(defaults.min_epoch_credits_ratio),
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+    use validator_history::{CircBuf, CircBufCluster, ClusterHistoryEntry, ValidatorHistoryEntry};
+
+    fn record(
+        vote_account: VotePubkey,
+        delinquency_score: f64,
+        error: Option<&str>,
+    ) -> ValidatorScoreRecord {
+        ValidatorScoreRecord {
+            epoch: 700,
+            vote_account,
+            score: if error.is_some() {
+                0.0
+            } else {
+                delinquency_score
+            },
+            components: if error.is_some() {
+                BTreeMap::new()
+            } else {
+                [("delinquency_score".to_string(), delinquency_score)].into()
+            },
+            error: error.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_score_breakdowns() {
+        let [a, b, c, d] = [(); 4].map(|_| VotePubkey(Pubkey::new_unique()));
+        let records = vec![
+            record(a, 1.0, None),
+            record(b, 0.0, None),
+            record(c, 0.0, Some("ArithmeticError")),
+            record(d, 0.0, Some("ArithmeticError")),
+        ];
+
+        let summary = ScoringEpochSummary::from_records(700, &records);
+        assert_eq!(summary.validators_scored, 4);
+        assert_eq!(summary.zero_scores, 3);
+        assert_eq!(summary.zero_components["delinquency_score"], 1);
+        assert_eq!(summary.errors["ArithmeticError"], 2);
+
+        let mut csv = Vec::new();
+        write_score_breakdowns_csv(&mut csv, &records).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("epoch,vote_account,score,delinquency_score,error")
+        );
+        assert_eq!(lines.next(), Some(format!("700,{a},1,1,").as_str()));
+        assert_eq!(
+            lines.nth(1),
+            Some(format!("700,{c},0,,ArithmeticError").as_str())
+        );
+    }
+
+    /// Validator entries of `(epoch, epoch_credits, commission)` and cluster entries of
+    /// `(epoch, total_blocks)`
+    fn histories(
+        validator_entries: &[(u16, u32, u8)],
+        cluster_entries: &[(u16, u32)],
+    ) -> (ValidatorHistory, JitoClusterHistory) {
+        let mut validator_history = ValidatorHistory {
+            struct_version: 0,
+            vote_account: Pubkey::default(),
+            index: 0,
+            bump: 0,
+            _padding0: [0; 7],
+            last_ip_timestamp: 0,
+            last_version_timestamp: 0,
+            _padding1: [0; 232],
+            history: CircBuf::default(),
+        };
+        for (epoch, epoch_credits, commission) in validator_entries {
+            validator_history.history.push(ValidatorHistoryEntry {
+                epoch: *epoch,
+                epoch_credits: *epoch_credits,
+                commission: *commission,
+                mev_commission: 500,
+                priority_fee_commission: 0,
+                blocks_produced: 9,
+                total_leader_slots: 10,
+                ..ValidatorHistoryEntry::default()
+            });
+        }
+
+        let mut cluster_history = JitoClusterHistory {
+            struct_version: 0,
+            bump: 0,
+            _padding0: [0; 7],
+            cluster_history_last_update_slot: 0,
+            _padding1: [0; 232],
+            history: CircBufCluster::default(),
+        };
+        for (epoch, total_blocks) in cluster_entries {
+            cluster_history.history.push(ClusterHistoryEntry {
+                total_blocks: *total_blocks,
+                epoch: *epoch,
+                padding0: [0; 2],
+                epoch_start_timestamp: 0,
+                padding: [0; 240],
+            });
+        }
+        (validator_history, cluster_history)
+    }
+
+    #[test]
+    fn test_weighted_scoring_model() {
+        let config: Config = bytemuck::Zeroable::zeroed();
+        // Epoch 699 is outside of a 2 epoch lookback from 702, and 702 produced no blocks
+        let (validator_history, cluster_history) = histories(
+            &[
+                (699, 16_000, 100),
+                (700, 1600, 0),
+                (701, 1200, 0),
+                (702, 0, 0),
+            ],
+            &[(699, 1000), (700, 100), (701, 100), (702, 0)],
+        );
+        let options = ScoringModelOptions {
+            scoring_model: Some(ScoringModelKind::Weighted),
+            scoring_lookback_epochs: Some(2),
+            mev_commission_weight: Some(2.0),
+            min_epoch_credits_ratio: Some(0.8),
+            ..ScoringModelOptions::default()
+        };
+        let model = options.build();
+        assert_eq!(model.lookback_epochs(&config), 2);
+
+        let breakdown = model
+            .score(&validator_history, &cluster_history, &config, 702)
+            .unwrap();
+        // 2800 credits over the 16 credits of each of the 200 blocks
+        let assert_close = |actual: f64, expected: f64| assert!((actual - expected).abs() < 1e-9);
+        assert_close(breakdown.components["epoch_credits_ratio"], 0.875);
+        assert_close(breakdown.components["blocks_produced_ratio"], 0.9);
+        assert_close(breakdown.components["mev_commission_score"], 0.95);
+        assert_eq!(breakdown.components["commission_threshold_score"], 1.0);
+        assert_close(
+            breakdown.score,
+            (2.0 * 0.95 + 1.0 + 0.875 + 0.9 + 1.0) / 6.0,
+        );
+
+        // Below the default minimum share of vote credits
+        let breakdown = ScoringModelOptions {
+            min_epoch_credits_ratio: None,
+            ..options.clone()
+        }
+        .build()
+        .score(&validator_history, &cluster_history, &config, 702)
+        .unwrap();
+        assert_eq!(breakdown.components["epoch_credits_threshold_score"], 0.0);
+        assert_eq!(breakdown.score, 0.0);
+
+        // The commission of epoch 699 is above the threshold once it is in the lookback
+        let breakdown = ScoringModelOptions {
+            scoring_lookback_epochs: Some(3),
+            ..options.clone()
+        }
+        .build()
+        .score(&validator_history, &cluster_history, &config, 702)
+        .unwrap();
+        assert_eq!(breakdown.components["commission_threshold_score"], 0.0);
+        assert_eq!(breakdown.score, 0.0);
+
+        // An epoch without blocks earns no share of vote credits
+        let breakdown = ScoringModelOptions {
+            scoring_lookback_epochs: Some(0),
+            ..options
+        }
+        .build()
+        .score(&validator_history, &cluster_history, &config, 702)
+        .unwrap();
+        assert_eq!(breakdown.components["epoch_credits_ratio"], 0.0);
+        assert_eq!(breakdown.score, 0.0);
+    }
+}
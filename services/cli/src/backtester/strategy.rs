@@ -0,0 +1,300 @@
+use crate::utils::ValidatorWithScore;
+use clap::{Args, ValueEnum};
+use serde::{Deserialize, Serialize};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use stakenet_simulator_db::domain::VotePubkey;
+use std::{collections::HashMap, sync::Arc};
+
+/// Default cap on the total stake of a validator for the `stake-capped` strategy
+pub const DEFAULT_MAX_VALIDATOR_STAKE_LAMPORTS: u64 = 1_000_000 * LAMPORTS_PER_SOL;
+/// Default weight of superminority validators for the `decentralization-weighted` strategy
+pub const DEFAULT_SUPERMINORITY_WEIGHT: f64 = 0.5;
+
+/// Decides which validators the simulated pool delegates to and how the stake is split between
+/// them at the start of every steward cycle
+pub trait DelegationStrategy: Send + Sync {
+    /// Name of the strategy with its settings, recorded in the backtest report
+    fn name(&self) -> String;
+
+    /// Picks the validators to delegate to. `candidates` holds every scored validator sorted by
+    /// descending score.
+    fn select(
+        &self,
+        candidates: &[ValidatorWithScore],
+        number_of_validator_delegations: usize,
+    ) -> Vec<ValidatorWithScore> {
+        candidates
+            .iter()
+            .filter(|candidate| candidate.score > 0.0)
+            .take(number_of_validator_delegations)
+            .cloned()
+            .collect()
+    }
+
+    /// Target stake of every selected validator, summing up to at most `total_lamports`
+    fn targets(
+        &self,
+        selected: &[ValidatorWithScore],
+        total_lamports: u64,
+    ) -> HashMap<VotePubkey, u64>;
+}
+
+/// Splits `total_lamports` between the validators proportionally to their weight
+fn weighted_targets(
+    selected: &[ValidatorWithScore],
+    total_lamports: u64,
+    weight: impl Fn(&ValidatorWithScore) -> f64,
+) -> HashMap<VotePubkey, u64> {
+    let total_weight: f64 = selected.iter().map(&weight).sum();
+    if total_weight <= 0.0 {
+        return HashMap::new();
+    }
+    selected
+        .iter()
+        .map(|validator| {
+            let target = (total_lamports as f64 * weight(validator) / total_weight) as u64;
+            (validator.vote_account, target.min(total_lamports))
+        })
+        .collect()
+}
+
+/// The on-chain steward: top validators by score, equal stake each
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StewardStrategy;
+
+impl DelegationStrategy for StewardStrategy {
+    fn name(&self) -> String {
+        "steward".to_string()
+    }
+
+    fn targets(
+        &self,
+        selected: &[ValidatorWithScore],
+        total_lamports: u64,
+    ) -> HashMap<VotePubkey, u64> {
+        if selected.is_empty() {
+            return HashMap::new();
+        }
+        let stake_per_validator = total_lamports / selected.len() as u64;
+        selected
+            .iter()
+            .map(|validator| (validator.vote_account, stake_per_validator))
+            .collect()
+    }
+}
+
+/// Top validators by score, stake proportional to the score
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ScoreProportionalStrategy;
+
+impl DelegationStrategy for ScoreProportionalStrategy {
+    fn name(&self) -> String {
+        "score-proportional".to_string()
+    }
+
+    fn targets(
+        &self,
+        selected: &[ValidatorWithScore],
+        total_lamports: u64,
+    ) -> HashMap<VotePubkey, u64> {
+        weighted_targets(selected, total_lamports, |validator| validator.score)
+    }
+}
+
+/// Top validators by score with equal stake, except that no validator is delegated past
+/// `max_validator_stake_lamports` of total stake on the network. The stake that doesn't fit is
+/// split between the other validators, and left undelegated when every validator is capped.
+#[derive(Clone, Copy, Debug)]
+pub struct StakeCappedStrategy {
+    pub max_validator_stake_lamports: u64,
+}
+
+impl DelegationStrategy for StakeCappedStrategy {
+    fn name(&self) -> String {
+        format!(
+            "stake-capped(max_validator_stake_lamports={})",
+            self.max_validator_stake_lamports
+        )
+    }
+
+    fn targets(
+        &self,
+        selected: &[ValidatorWithScore],
+        total_lamports: u64,
+    ) -> HashMap<VotePubkey, u64> {
+        let mut targets = HashMap::new();
+        let mut uncapped: Vec<(&ValidatorWithScore, u64)> = selected
+            .iter()
+            .map(|validator| {
+                let room = self
+                    .max_validator_stake_lamports
+                    .saturating_sub(validator.activated_stake_lamports);
+                (validator, room)
+            })
+            .collect();
+        let mut remaining = total_lamports;
+
+        while !uncapped.is_empty() {
+            let share = remaining / uncapped.len() as u64;
+            let (capped, rest): (Vec<_>, Vec<_>) =
+                uncapped.into_iter().partition(|(_, room)| *room < share);
+            if capped.is_empty() {
+                for (validator, _) in rest {
+                    targets.insert(validator.vote_account, share);
+                }
+                break;
+            }
+            for (validator, room) in capped {
+                targets.insert(validator.vote_account, room);
+                remaining -= room;
+            }
+            uncapped = rest;
+        }
+
+        targets
+    }
+}
+
+/// Top validators by score, with less stake on the validators in the superminority
+#[derive(Clone, Copy, Debug)]
+pub struct DecentralizationWeightedStrategy {
+    /// Weight of a superminority validator, where every other validator has a weight of 1
+    pub superminority_weight: f64,
+}
+
+impl DelegationStrategy for DecentralizationWeightedStrategy {
+    fn name(&self) -> String {
+        format!(
+            "decentralization-weighted(superminority_weight={})",
+            self.superminority_weight
+        )
+    }
+
+    fn targets(
+        &self,
+        selected: &[ValidatorWithScore],
+        total_lamports: u64,
+    ) -> HashMap<VotePubkey, u64> {
+        weighted_targets(selected, total_lamports, |validator| {
+            if validator.is_superminority {
+                self.superminority_weight
+            } else {
+                1.0
+            }
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum DelegationStrategyKind {
+    #[default]
+    Steward,
+    ScoreProportional,
+    StakeCapped,
+    DecentralizationWeighted,
+}
+
+/// Selection of one of the built-in delegation strategies, from the CLI or a scenario file
+#[derive(Args, Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DelegationStrategyOptions {
+    /// Delegation strategy of the simulated pool, defaults to the on-chain steward behavior
+    #[arg(long, env, value_enum)]
+    pub delegation_strategy: Option<DelegationStrategyKind>,
+    /// Total stake a validator can reach with the `stake-capped` strategy, defaults to 1M SOL
+    #[arg(long, env)]
+    pub max_validator_stake_lamports: Option<u64>,
+    /// Weight of superminority validators with the `decentralization-weighted` strategy, where
+    /// other validators have a weight of 1. Defaults to 0.5
+    #[arg(long, env)]
+    pub superminority_weight: Option<f64>,
+}
+
+impl DelegationStrategyOptions {
+    /// Sets every option that is unset from the fallback
+    pub fn fill_unset_from(&mut self, fallback: &Self) {
+        self.delegation_strategy = self.delegation_strategy.or(fallback.delegation_strategy);
+        self.max_validator_stake_lamports = self
+            .max_validator_stake_lamports
+            .or(fallback.max_validator_stake_lamports);
+        self.superminority_weight = self.superminority_weight.or(fallback.superminority_weight);
+    }
+
+    pub fn build(&self) -> Arc<dyn DelegationStrategy> {
+        match self.delegation_strategy.unwrap_or_default() {
+            DelegationStrategyKind::Steward => Arc::new(StewardStrategy),
+            DelegationStrategyKind::ScoreProportional => Arc::new(ScoreProportionalStrategy),
+            DelegationStrategyKind::StakeCapped => Arc::new(StakeCappedStrategy {
+                max_validator_stake_lamports: self
+                    .max_validator_stake_lamports
+                    .unwrap_or(DEFAULT_MAX_VALIDATOR_STAKE_LAMPORTS),
+            }),
+            DelegationStrategyKind::DecentralizationWeighted => {
+                Arc::new(DecentralizationWeightedStrategy {
+                    superminority_weight: self
+                        .superminority_weight
+                        .unwrap_or(DEFAULT_SUPERMINORITY_WEIGHT),
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+
+    fn validator(
+        vote_account: VotePubkey,
+        score: f64,
+        activated_stake_lamports: u64,
+        is_superminority: bool,
+    ) -> ValidatorWithScore {
+        ValidatorWithScore {
+            vote_account,
+            score,
+            activated_stake_lamports,
+            is_superminority,
+        }
+    }
+
+    #[test]
+    fn test_delegation_strategy_targets() {
+        let [a, b, c, d] = [(); 4].map(|_| VotePubkey(Pubkey::new_unique()));
+        let validators = vec![
+            validator(a, 0.9, 900, true),
+            validator(b, 0.6, 100, false),
+            validator(c, 0.3, 0, false),
+        ];
+
+        let targets = StewardStrategy.targets(&validators, 300);
+        assert_eq!(targets[&a], 100);
+        assert_eq!(targets[&c], 100);
+
+        let targets = ScoreProportionalStrategy.targets(&validators, 180);
+        assert_eq!(targets[&a], 90);
+        assert_eq!(targets[&b], 60);
+        assert_eq!(targets[&c], 30);
+
+        // `a` only has room for 100 more, the rest is split between `b` and `c`
+        let targets = StakeCappedStrategy {
+            max_validator_stake_lamports: 1000,
+        }
+        .targets(&validators, 600);
+        assert_eq!(targets[&a], 100);
+        assert_eq!(targets[&b], 250);
+        assert_eq!(targets[&c], 250);
+
+        let targets = DecentralizationWeightedStrategy {
+            superminority_weight: 0.5,
+        }
+        .targets(&validators, 500);
+        assert_eq!(targets[&a], 100);
+        assert_eq!(targets[&b], 200);
+
+        let selected = StewardStrategy.select(&[validator(d, 0.0, 0, false)], 10);
+        assert!(selected.is_empty());
+    }
+}
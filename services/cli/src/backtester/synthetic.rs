@@ -0,0 +1,521 @@
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::index::sample};
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey};
+use sqlx::types::BigDecimal;
+use stakenet_simulator_db::{
+    active_stake_jito_sol::ActiveStakeJitoSol,
+    bulk_insert::InsertMode,
+    cluster_history::ClusterHistory,
+    cluster_history_entry::ClusterHistoryEntry,
+    domain::{Bps, Epoch, Lamports, VotePubkey},
+    epoch_rewards::EpochRewards,
+    epoch_schedule::EpochSchedule,
+    inactive_stake_jito_sol::InactiveStakeJitoSol,
+    store::{SimulatorStore, SqliteStore},
+    validator_history::ValidatorHistory,
+    validator_history_entry::ValidatorHistoryEntry,
+    withdraw_and_deposit_sol::WithdrawAndDepositSol,
+    withdraw_and_deposits_stake::WithdrawsAndDepositStakes,
+};
+use std::{collections::HashMap, str::FromStr};
+use validator_history::{
+    ClientVersion, MerkleRootUploadAuthority, ValidatorHistoryEntry as JitoValidatorHistoryEntry,
+};
+
+const SLOTS_PER_EPOCH: u64 = 432_000;
+/// Vote credits earned for voting on every slot of an epoch
+const MAX_EPOCH_CREDITS: u64 = SLOTS_PER_EPOCH * 16;
+/// Unix timestamp of the start of epoch 0, epochs last 2 days
+const GENESIS_TIMESTAMP: u64 = 1_584_368_940;
+const EPOCH_DURATION_SECONDS: u64 = 2 * 24 * 60 * 60;
+const EPOCHS_PER_YEAR: f64 = 182.5;
+/// Yearly returns of the stake on a validator voting on every slot, before commissions
+const INFLATION_APY: f64 = 0.07;
+const MEV_APY: f64 = 0.006;
+const PRIORITY_FEE_APY: f64 = 0.002;
+/// `mev_earned` of the validator history is recorded in hundredths of a SOL
+const MEV_EARNED_UNIT: u64 = LAMPORTS_PER_SOL / 100;
+const JITO_LABS_CLIENT_TYPE: u8 = 1;
+
+/// Change in the behavior of a synthetic validator from an epoch on, parsed from
+/// `delinquent:<validator>@<epoch>`, `commission:<validator>@<epoch>=<percent>` or
+/// `mev-commission:<validator>@<epoch>=<bps>`, where validators are indexes in the dataset
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SyntheticBehavior {
+    /// The validator stops voting and producing blocks
+    Delinquent { validator: usize, epoch: u16 },
+    /// The validator raises its inflation commission
+    CommissionHike {
+        validator: usize,
+        epoch: u16,
+        commission: u8,
+    },
+    /// The validator changes its MEV commission
+    MevCommissionChange {
+        validator: usize,
+        epoch: u16,
+        mev_commission_bps: u16,
+    },
+}
+
+impl FromStr for SyntheticBehavior {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (kind, target) = value
+            .split_once(':')
+            .ok_or_else(|| format!("expected <behavior>:<validator>@<epoch>, got {}", value))?;
+        let (target, amount) = match target.split_once('=') {
+            Some((target, amount)) => (target, Some(amount)),
+            None => (target, None),
+        };
+        let (validator, epoch) = target
+            .split_once('@')
+            .ok_or_else(|| format!("expected <validator>@<epoch>, got {}", target))?;
+        let validator = validator
+            .parse()
+            .map_err(|_| format!("invalid validator index {}", validator))?;
+        let epoch = epoch
+            .parse()
+            .map_err(|_| format!("invalid epoch {}", epoch))?;
+        let amount = |name: &str| {
+            amount.ok_or_else(|| {
+                format!(
+                    "{} needs a value, e.g. {}:{}@{}=10",
+                    name, name, validator, epoch
+                )
+            })
+        };
+
+        match kind {
+            "delinquent" => Ok(Self::Delinquent { validator, epoch }),
+            "commission" => {
+                let commission = amount(kind)?;
+                Ok(Self::CommissionHike {
+                    validator,
+                    epoch,
+                    commission: commission
+                        .parse()
+                        .ok()
+                        .filter(|commission| *commission <= 100)
+                        .ok_or_else(|| format!("invalid commission {}", commission))?,
+                })
+            }
+            "mev-commission" => {
+                let mev_commission_bps = amount(kind)?;
+                Ok(Self::MevCommissionChange {
+                    validator,
+                    epoch,
+                    mev_commission_bps: mev_commission_bps
+                        .parse()
+                        .ok()
+                        .filter(|bps| *bps <= 10_000)
+                        .ok_or_else(|| format!("invalid MEV commission {}", mev_commission_bps))?,
+                })
+            }
+            _ => Err(format!("unknown behavior {}", kind)),
+        }
+    }
+}
+
+/// Settings of a generated dataset
+#[derive(Clone, Debug)]
+pub struct SyntheticDatasetConfig {
+    pub num_validators: usize,
+    /// Epochs `start_epoch..=end_epoch` to generate records for
+    pub start_epoch: u16,
+    pub end_epoch: u16,
+    pub seed: u64,
+    pub behaviors: Vec<SyntheticBehavior>,
+}
+
+impl Default for SyntheticDatasetConfig {
+    fn default() -> Self {
+        Self {
+            num_validators: 50,
+            start_epoch: 600,
+            end_epoch: 700,
+            seed: 0,
+            behaviors: Vec::new(),
+        }
+    }
+}
+
+/// State of a synthetic validator in an epoch
+struct SyntheticValidator {
+    vote_account: VotePubkey,
+    stake_lamports: u64,
+    commission: u8,
+    mev_commission_bps: u16,
+    priority_fee_commission_bps: u16,
+    /// Share of the vote credits the validator earns
+    vote_performance: f64,
+}
+
+/// Records of every table the simulator reads, generated for a number of validators over an
+/// epoch range. The records are coherent with each other and only depend on the config, so the
+/// same dataset can be generated again from its seed to test the simulator without a database.
+pub struct SyntheticDataset {
+    pub validator_histories: Vec<ValidatorHistory>,
+    pub validator_history_entries: Vec<ValidatorHistoryEntry>,
+    pub cluster_history: ClusterHistory,
+    pub cluster_history_entries: Vec<ClusterHistoryEntry>,
+    pub epoch_schedule: EpochSchedule,
+    pub epoch_rewards: Vec<EpochRewards>,
+    pub withdraw_and_deposit_sol: Vec<WithdrawAndDepositSol>,
+    pub withdraws_and_deposit_stakes: Vec<WithdrawsAndDepositStakes>,
+    pub active_stake: Vec<ActiveStakeJitoSol>,
+    pub inactive_stake: Vec<InactiveStakeJitoSol>,
+}
+
+impl SyntheticDataset {
+    pub fn generate(config: &SyntheticDatasetConfig) -> Self {
+        let mut rng = StdRng::seed_from_u64(config.seed);
+        let mut validators: Vec<SyntheticValidator> = (0..config.num_validators)
+            .map(|_| SyntheticValidator {
+                vote_account: Pubkey::new_from_array(rng.random()).into(),
+                stake_lamports: rng.random_range(20_000..2_000_000) * LAMPORTS_PER_SOL,
+                commission: [0, 0, 5, 5, 10][rng.random_range(0..5)],
+                mev_commission_bps: [0, 500, 800, 1000][rng.random_range(0..4)],
+                priority_fee_commission_bps: [5_000, 10_000][rng.random_range(0..2)],
+                vote_performance: rng.random_range(0.95..1.0),
+            })
+            .collect();
+
+        let validator_histories = validators
+            .iter()
+            .enumerate()
+            .map(|(index, validator)| ValidatorHistory {
+                struct_version: 0,
+                vote_account: validator.vote_account,
+                index: index as u32,
+                bump: 255,
+                last_ip_timestamp: epoch_start_timestamp(config.start_epoch),
+                last_version_timestamp: epoch_start_timestamp(config.start_epoch),
+            })
+            .collect();
+
+        let mut dataset = Self {
+            validator_histories,
+            validator_history_entries: Vec::new(),
+            cluster_history: ClusterHistory {
+                struct_version: 0,
+                bump: 255,
+                cluster_history_last_update_slot: (u64::from(config.end_epoch) + 1)
+                    * SLOTS_PER_EPOCH
+                    - 1,
+            },
+            cluster_history_entries: Vec::new(),
+            epoch_schedule: EpochSchedule {
+                slots_per_epoch: SLOTS_PER_EPOCH,
+                leader_schedule_slot_offset: SLOTS_PER_EPOCH,
+                warmup: false,
+                first_normal_epoch: Epoch(0),
+                first_normal_slot: 0,
+            },
+            epoch_rewards: Vec::new(),
+            withdraw_and_deposit_sol: Vec::new(),
+            withdraws_and_deposit_stakes: Vec::new(),
+            active_stake: Vec::new(),
+            inactive_stake: Vec::new(),
+        };
+
+        let mut pool_active_sol: u64 = 10_000_000;
+        for epoch in config.start_epoch..=config.end_epoch {
+            for validator in validators.iter_mut() {
+                // Stake drifts by up to 1% every epoch
+                let drift = rng.random_range(-0.01..0.01);
+                validator.stake_lamports = (validator.stake_lamports as f64 * (1.0 + drift)) as u64;
+            }
+            let mut delinquent = vec![false; validators.len()];
+            for behavior in &config.behaviors {
+                match *behavior {
+                    SyntheticBehavior::Delinquent {
+                        validator,
+                        epoch: from_epoch,
+                    } if epoch >= from_epoch && validator < validators.len() => {
+                        delinquent[validator] = true;
+                    }
+                    SyntheticBehavior::CommissionHike {
+                        validator,
+                        epoch: from_epoch,
+                        commission,
+                    } if epoch == from_epoch && validator < validators.len() => {
+                        validators[validator].commission = commission;
+                    }
+                    SyntheticBehavior::MevCommissionChange {
+                        validator,
+                        epoch: from_epoch,
+                        mev_commission_bps,
+                    } if epoch == from_epoch && validator < validators.len() => {
+                        validators[validator].mev_commission_bps = mev_commission_bps;
+                    }
+                    _ => {}
+                }
+            }
+            dataset.generate_epoch(epoch, &validators, &delinquent);
+            dataset.generate_pool_flows(epoch, &validators, &mut pool_active_sol, &mut rng);
+        }
+        dataset
+    }
+
+    /// Validator history entries, rewards and the cluster history entry of the epoch
+    fn generate_epoch(
+        &mut self,
+        epoch: u16,
+        validators: &[SyntheticValidator],
+        delinquent: &[bool],
+    ) {
+        let total_stake: u64 = validators.iter().map(|v| v.stake_lamports).sum();
+        let mut by_stake: Vec<usize> = (0..validators.len()).collect();
+        by_stake.sort_by_key(|index| std::cmp::Reverse(validators[*index].stake_lamports));
+        let mut ranks = HashMap::new();
+        let mut cumulative_stake = 0u64;
+        for (rank, index) in by_stake.into_iter().enumerate() {
+            // The superminority holds a third of the stake
+            let is_superminority = cumulative_stake < total_stake / 3;
+            cumulative_stake += validators[index].stake_lamports;
+            ranks.insert(index, (rank as u32, is_superminority));
+        }
+
+        let last_slot = (u64::from(epoch) + 1) * SLOTS_PER_EPOCH - 1;
+        let mut total_blocks = 0u32;
+        for (index, validator) in validators.iter().enumerate() {
+            let performance = if delinquent[index] {
+                0.0
+            } else {
+                validator.vote_performance
+            };
+            let stake_share = validator.stake_lamports as f64 / total_stake.max(1) as f64;
+            let total_leader_slots = (SLOTS_PER_EPOCH as f64 * stake_share) as u32;
+            let blocks_produced = (f64::from(total_leader_slots) * performance) as u32;
+            total_blocks += blocks_produced;
+
+            let epoch_return =
+                |apy: f64| (validator.stake_lamports as f64 * apy / EPOCHS_PER_YEAR) as u64;
+            let total_inflation_rewards = (epoch_return(INFLATION_APY) as f64 * performance) as u64;
+            let total_mev_rewards = (epoch_return(MEV_APY) as f64 * performance) as u64;
+            let total_priority_fee_rewards =
+                (epoch_return(PRIORITY_FEE_APY) as f64 * performance) as u64;
+            let (rank, is_superminority) = ranks[&index];
+
+            self.validator_history_entries
+                .push(ValidatorHistoryEntry::new(
+                    validator.vote_account,
+                    JitoValidatorHistoryEntry {
+                        activated_stake_lamports: validator.stake_lamports,
+                        epoch,
+                        mev_commission: validator.mev_commission_bps,
+                        epoch_credits: (MAX_EPOCH_CREDITS as f64 * performance) as u32,
+                        commission: validator.commission,
+                        client_type: JITO_LABS_CLIENT_TYPE,
+                        version: ClientVersion {
+                            major: 2,
+                            minor: 2,
+                            patch: 16,
+                        },
+                        ip: [10, 0, (index >> 8) as u8, index as u8],
+                        merkle_root_upload_authority: MerkleRootUploadAuthority::TipRouter,
+                        is_superminority: u8::from(is_superminority),
+                        rank,
+                        vote_account_last_update_slot: last_slot,
+                        mev_earned: (total_mev_rewards / MEV_EARNED_UNIT) as u32,
+                        priority_fee_commission: validator.priority_fee_commission_bps,
+                        priority_fee_tips: total_priority_fee_rewards,
+                        total_priority_fees: total_priority_fee_rewards,
+                        total_leader_slots,
+                        blocks_produced,
+                        block_data_updated_at_slot: last_slot,
+                        priority_fee_merkle_root_upload_authority:
+                            MerkleRootUploadAuthority::TipRouter,
+                        ..JitoValidatorHistoryEntry::default()
+                    },
+                ));
+            self.epoch_rewards.push(EpochRewards {
+                id: format!("{}-{}", validator.vote_account, epoch),
+                vote_pubkey: validator.vote_account,
+                epoch: epoch.into(),
+                inflation_commission_bps: Bps(u16::from(validator.commission) * 100),
+                total_inflation_rewards: Lamports(total_inflation_rewards),
+                mev_commission_bps: Bps(validator.mev_commission_bps),
+                total_mev_rewards: Lamports(total_mev_rewards),
+                priority_fee_commission_bps: Bps(validator.priority_fee_commission_bps),
+                total_priority_fee_rewards: Lamports(total_priority_fee_rewards),
+                active_stake: Lamports(validator.stake_lamports),
+            });
+        }
+
+        self.cluster_history_entries.push(ClusterHistoryEntry {
+            epoch: epoch.into(),
+            total_blocks,
+            epoch_start_timestamp: epoch_start_timestamp(epoch),
+        });
+    }
+
+    /// JitoSOL stake balances and the SOL and stake deposited to and withdrawn from the pool
+    fn generate_pool_flows(
+        &mut self,
+        epoch: u16,
+        validators: &[SyntheticValidator],
+        pool_active_sol: &mut u64,
+        rng: &mut StdRng,
+    ) {
+        let deposit_sol: u64 = rng.random_range(0..20_000);
+        let withdraw_sol: u64 = rng.random_range(0..20_000);
+        *pool_active_sol = (*pool_active_sol + deposit_sol).saturating_sub(withdraw_sol);
+        self.withdraw_and_deposit_sol.push(WithdrawAndDepositSol {
+            epoch: epoch.into(),
+            withdraw_sol: BigDecimal::from(withdraw_sol),
+            deposit_sol: BigDecimal::from(deposit_sol),
+        });
+        self.active_stake.push(ActiveStakeJitoSol {
+            epoch: epoch.into(),
+            balance: BigDecimal::from(*pool_active_sol),
+        });
+        self.inactive_stake.push(InactiveStakeJitoSol {
+            epoch: epoch.into(),
+            balance: BigDecimal::from(*pool_active_sol / 200),
+        });
+
+        if validators.is_empty() {
+            return;
+        }
+        // Rows are keyed by epoch and validator, so every validator is drawn at most once
+        let flows = rng.random_range(0..4).min(validators.len());
+        for index in sample(rng, validators.len(), flows) {
+            let validator = &validators[index];
+            let deposit_stake: u64 = rng.random_range(0..50_000);
+            let withdraw_stake: u64 = rng.random_range(0..10_000);
+            self.withdraws_and_deposit_stakes
+                .push(WithdrawsAndDepositStakes {
+                    id: format!("{}-{}", epoch, validator.vote_account),
+                    epoch: epoch.into(),
+                    vote_pubkey: validator.vote_account,
+                    withdraw_stake: BigDecimal::from(withdraw_stake),
+                    deposit_stake: BigDecimal::from(deposit_stake),
+                });
+        }
+    }
+
+    /// Vote account of the validator at `index` in the dataset
+    pub fn vote_account(&self, index: usize) -> VotePubkey {
+        self.validator_histories[index].vote_account
+    }
+
+    /// Writes the records to a store
+    pub async fn insert_into(self, store: &dyn SimulatorStore) -> Result<(), sqlx::Error> {
+        store
+            .insert_validator_histories(self.validator_histories, InsertMode::InsertIgnore)
+            .await?;
+        store
+            .insert_validator_history_entries(
+                self.validator_history_entries,
+                InsertMode::InsertIgnore,
+            )
+            .await?;
+        store.upsert_cluster_history(self.cluster_history).await?;
+        store
+            .insert_cluster_history_entries(self.cluster_history_entries, InsertMode::InsertIgnore)
+            .await?;
+        store.upsert_epoch_schedule(self.epoch_schedule).await?;
+        store
+            .insert_epoch_rewards(self.epoch_rewards, InsertMode::InsertIgnore)
+            .await?;
+        store
+            .insert_withdraw_and_deposit_sol(
+                self.withdraw_and_deposit_sol,
+                InsertMode::InsertIgnore,
+            )
+            .await?;
+        store
+            .insert_withdraw_and_deposit_stakes(
+                self.withdraws_and_deposit_stakes,
+                InsertMode::InsertIgnore,
+            )
+            .await?;
+        store.upsert_active_stake(self.active_stake).await?;
+        store.upsert_inactive_stake(self.inactive_stake).await?;
+        Ok(())
+    }
+
+    /// Writes the records to an in-memory SQLite store, to load a `BacktestDataset` from
+    pub async fn into_store(self) -> Result<SqliteStore, sqlx::Error> {
+        let store = SqliteStore::connect("sqlite::memory:").await?;
+        self.insert_into(&store).await?;
+        Ok(store)
+    }
+}
+
+fn epoch_start_timestamp(epoch: u16) -> u64 {
+    GENESIS_TIMESTAMP + u64::from(epoch) * EPOCH_DURATION_SECONDS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_synthetic_dataset() {
+        let config = SyntheticDatasetConfig {
+            num_validators: 4,
+            start_epoch: 700,
+            end_epoch: 709,
+            seed: 7,
+            behaviors: vec![
+                "delinquent:0@705".parse().unwrap(),
+                "commission:1@703=100".parse().unwrap(),
+                "mev-commission:2@704=10000".parse().unwrap(),
+            ],
+        };
+        let dataset = SyntheticDataset::generate(&config);
+        assert_eq!(dataset.validator_history_entries.len(), 40);
+        assert_eq!(dataset.epoch_rewards.len(), 40);
+        assert_eq!(dataset.cluster_history_entries.len(), 10);
+        assert_eq!(dataset.active_stake.len(), dataset.inactive_stake.len());
+        let flow_ids: HashSet<&str> = dataset
+            .withdraws_and_deposit_stakes
+            .iter()
+            .map(|flow| flow.id.as_str())
+            .collect();
+        assert_eq!(flow_ids.len(), dataset.withdraws_and_deposit_stakes.len());
+
+        let entry = |validator: usize, epoch: u16| {
+            dataset
+                .validator_history_entries
+                .iter()
+                .find(|entry| {
+                    entry.vote_pubkey == dataset.vote_account(validator)
+                        && entry.validator_history_entry.epoch == epoch
+                })
+                .map(|entry| entry.validator_history_entry)
+                .unwrap()
+        };
+        assert!(entry(0, 704).epoch_credits > 0);
+        assert_eq!(entry(0, 705).epoch_credits, 0);
+        assert_eq!(entry(1, 703).commission, 100);
+        assert_eq!(entry(2, 709).mev_commission, 10_000);
+        let rewards = dataset
+            .epoch_rewards
+            .iter()
+            .find(|reward| {
+                reward.vote_pubkey == dataset.vote_account(0) && reward.epoch == Epoch(706)
+            })
+            .unwrap();
+        assert_eq!(rewards.total_inflation_rewards, Lamports(0));
+
+        // The same seed generates the same dataset
+        let again = SyntheticDataset::generate(&config);
+        assert_eq!(again.vote_account(3), dataset.vote_account(3));
+        assert_eq!(
+            again.validator_history_entries[17]
+                .validator_history_entry
+                .activated_stake_lamports,
+            dataset.validator_history_entries[17]
+                .validator_history_entry
+                .activated_stake_lamports
+        );
+
+        assert!("commission:1@703".parse::<SyntheticBehavior>().is_err());
+        assert!("stake:1@703=5".parse::<SyntheticBehavior>().is_err());
+    }
+}
@@ -0,0 +1,126 @@
+use jito_steward::Config;
+use solana_sdk::epoch_schedule::EpochSchedule;
+use validator_history::{CircBufCluster, ClusterHistory, ValidatorHistory};
+
+/// Number of epochs in a steward cycle, `num_epochs_between_scoring` of the config unless
+/// overridden
+pub fn steward_cycle_length(config: &Config, steward_cycle_rate: Option<u16>) -> u16 {
+    steward_cycle_rate
+        .unwrap_or_else(|| {
+            u16::try_from(config.parameters.num_epochs_between_scoring).unwrap_or(u16::MAX)
+        })
+        .max(1)
+}
+
+/// First epoch of the cycle after the one `epoch` is in. Cycles start at multiples of the cycle
+/// length, following the absolute epoch numbering of the on-chain steward.
+pub fn next_cycle_start(epoch: u16, cycle_length: u16) -> u16 {
+    let cycle_length = cycle_length.max(1);
+    (epoch / cycle_length)
+        .saturating_add(1)
+        .saturating_mul(cycle_length)
+}
+
+/// Slot at which `epoch_progress` of the epoch has passed, e.g. the slot the steward computes
+/// instant unstakes at for `instant_unstake_epoch_progress`
+pub fn epoch_progress_slot(epoch_schedule: &EpochSchedule, epoch: u64, epoch_progress: f64) -> u64 {
+    let first_slot = epoch_schedule.get_first_slot_in_epoch(epoch);
+    let slots_in_epoch = epoch_schedule.get_slots_in_epoch(epoch);
+    first_slot + (slots_in_epoch as f64 * epoch_progress.clamp(0.0, 1.0)) as u64
+}
+
+/// Scales a count accumulated from `start_slot` to `recorded_slot` back to its value at `slot`,
+/// assuming it grew linearly. The history only holds the latest value of every epoch.
+pub fn value_at_slot(value: u32, start_slot: u64, recorded_slot: u64, slot: u64) -> u32 {
+    if recorded_slot <= slot || recorded_slot <= start_slot {
+        return value;
+    }
+    let elapsed = slot.saturating_sub(start_slot);
+    (u128::from(value) * u128::from(elapsed) / u128::from(recorded_slot - start_slot)) as u32
+}
+
+/// Cluster history as the steward sees it at `slot` of `epoch`. Later epochs are dropped and the
+/// blocks of `epoch` are scaled back to the slot.
+pub fn cluster_history_at_slot(
+    cluster_history: &ClusterHistory,
+    epoch_schedule: &EpochSchedule,
+    epoch: u16,
+    slot: u64,
+) -> ClusterHistory {
+    let epoch_start_slot = epoch_schedule.get_first_slot_in_epoch(epoch.into());
+    // Blocks of past epochs were last recorded at the end of the epoch at the latest
+    let recorded_slot = cluster_history
+        .cluster_history_last_update_slot
+        .min(epoch_schedule.get_last_slot_in_epoch(epoch.into()));
+
+    let mut entries: Vec<_> = cluster_history
+        .history
+        .arr
+        .iter()
+        .filter(|entry| entry.epoch <= epoch)
+        .copied()
+        .collect();
+    entries.sort_by_key(|entry| entry.epoch);
+
+    let mut cluster_history_at_slot = *cluster_history;
+    cluster_history_at_slot.history = CircBufCluster::default();
+    for mut entry in entries {
+        if entry.epoch == epoch && entry.total_blocks != u32::MAX {
+            entry.total_blocks =
+                value_at_slot(entry.total_blocks, epoch_start_slot, recorded_slot, slot);
+        }
+        cluster_history_at_slot.history.push(entry);
+    }
+    cluster_history_at_slot.cluster_history_last_update_slot = recorded_slot.min(slot);
+    cluster_history_at_slot
+}
+
+/// Scales the vote credits of the `epoch` entry of the validator history back to `slot`
+pub fn validator_history_at_slot(
+    validator_history: &mut ValidatorHistory,
+    epoch: u16,
+    epoch_start_slot: u64,
+    slot: u64,
+) {
+    if let Some(entry) = validator_history.history.last_mut()
+        && entry.epoch == epoch
+        && entry.vote_account_last_update_slot != u64::MAX
+        && entry.vote_account_last_update_slot > slot
+    {
+        if entry.epoch_credits != u32::MAX {
+            entry.epoch_credits = value_at_slot(
+                entry.epoch_credits,
+                epoch_start_slot,
+                entry.vote_account_last_update_slot,
+                slot,
+            );
+        }
+        entry.vote_account_last_update_slot = slot;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_epoch_timing() {
+        let without_warmup = EpochSchedule::without_warmup();
+        assert_eq!(
+            epoch_progress_slot(&without_warmup, 700, 0.9),
+            700 * 432_000 + 388_800
+        );
+
+        // With warmup the first epochs are shorter, epochs don't start at `epoch * 432_000`
+        let with_warmup = EpochSchedule::custom(432_000, 432_000, true);
+        let first_slot = with_warmup.get_first_slot_in_epoch(700);
+        assert_ne!(first_slot, 700 * 432_000);
+        assert_eq!(epoch_progress_slot(&with_warmup, 700, 0.0), first_slot);
+
+        assert_eq!(next_cycle_start(703, 10), 710);
+        assert_eq!(next_cycle_start(710, 10), 720);
+
+        assert_eq!(value_at_slot(1000, 100, 200, 150), 500);
+        assert_eq!(value_at_slot(1000, 100, 200, 250), 1000);
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{commands::DAYS_PER_YEAR, utils::calculate_apy};
+use serde::{Deserialize, Serialize};
+
+/// Stake moved by the simulated steward during a cycle
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct CycleTurnover {
+    pub start_epoch: u16,
+    /// Stake deactivated from validators that left the delegated set
+    pub scoring_unstake_lamports: u64,
+    pub instant_unstake_lamports: u64,
+    /// Stake deposits unstaked from validators, capped by `stake_deposit_unstake_cap_bps`
+    pub stake_deposit_unstake_lamports: u64,
+    /// Stake deactivated to move the directed validators to their targets
+    pub directed_unstake_lamports: u64,
+    /// Stake activated on validators, excluding the initial allocation of the pool
+    pub activated_lamports: u64,
+    /// Number of stake account activations and deactivations
+    pub transactions: u32,
+}
+
+impl CycleTurnover {
+    pub fn new(start_epoch: u16) -> Self {
+        Self {
+            start_epoch,
+            ..Self::default()
+        }
+    }
+
+    pub fn unstaked_lamports(&self) -> u64 {
+        self.scoring_unstake_lamports
+            + self.instant_unstake_lamports
+            + self.stake_deposit_unstake_lamports
+            + self.directed_unstake_lamports
+    }
+}
+
+/// Turnover of the pool over the simulation and what it cost
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct TurnoverReport {
+    pub cycles: Vec<CycleTurnover>,
+    pub total_unstaked_lamports: u64,
+    /// Stake unstaked over the simulation relative to the initial pool size
+    pub turnover_ratio: f64,
+    /// Rewards the pool would have earned on its activating stake at the pool's reward rate
+    pub forgone_rewards_lamports: u64,
+    pub transactions: u32,
+    /// Fees paid for the transactions, only set when a fee per transaction is given
+    pub transaction_fee_lamports: u64,
+    /// Final APY after paying the transaction fees
+    pub cost_adjusted_apy: f64,
+}
+
+impl TurnoverReport {
+    pub fn new(
+        cycles: Vec<CycleTurnover>,
+        forgone_rewards_lamports: u64,
+        fee_per_transaction_lamports: Option<u64>,
+        initial_pool_lamports: u64,
+    ) -> Self {
+        let total_unstaked_lamports = cycles.iter().map(CycleTurnover::unstaked_lamports).sum();
+        let transactions = cycles.iter().map(|cycle| cycle.transactions).sum();
+        let transaction_fee_lamports =
+            fee_per_transaction_lamports.unwrap_or(0) * u64::from(transactions);
+        let turnover_ratio = if initial_pool_lamports > 0 {
+            total_unstaked_lamports as f64 / initial_pool_lamports as f64
+        } else {
+            0.0
+        };
+
+        Self {
+            cycles,
+            total_unstaked_lamports,
+            turnover_ratio,
+            forgone_rewards_lamports,
+            transactions,
+            transaction_fee_lamports,
+            cost_adjusted_apy: 0.0,
+        }
+    }
+}
+
+/// APY of a pool growing from `starting_lamports` to `ending_lamports` over `epochs`, after
+/// paying `fee_lamports`. Assumes 2 days per epoch like the aggregated APY.
+pub fn cost_adjusted_apy(
+    starting_lamports: u64,
+    ending_lamports: u64,
+    fee_lamports: u64,
+    epochs: u16,
+) -> f64 {
+    if starting_lamports == 0 || epochs == 0 {
+        return 0.0;
+    }
+    let return_rate =
+        (ending_lamports as f64 - fee_lamports as f64) / starting_lamports as f64 - 1.0;
+    calculate_apy(return_rate, f64::from(epochs) * 2.0, DAYS_PER_YEAR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turnover_report() {
+        let cycles = vec![
+            CycleTurnover {
+                scoring_unstake_lamports: 100,
+                instant_unstake_lamports: 50,
+                activated_lamports: 150,
+                transactions: 4,
+                ..CycleTurnover::new(700)
+            },
+            CycleTurnover {
+                stake_deposit_unstake_lamports: 10,
+                directed_unstake_lamports: 40,
+                transactions: 2,
+                ..CycleTurnover::new(710)
+            },
+        ];
+        let report = TurnoverReport::new(cycles, 7, Some(5000), 1000);
+        assert_eq!(report.total_unstaked_lamports, 200);
+        assert_eq!(report.transactions, 6);
+        assert_eq!(report.transaction_fee_lamports, 30_000);
+        assert!((report.turnover_ratio - 0.2).abs() < 1e-12);
+
+        let apy = cost_adjusted_apy(1000, 1010, 0, 10);
+        assert!(cost_adjusted_apy(1000, 1010, 5, 10) < apy);
+        assert_eq!(cost_adjusted_apy(0, 1010, 5, 10), 0.0);
+    }
+}
@@ -0,0 +1,339 @@
+use crate::{
+    backtester::{Backtest, BacktestDataset, BacktestReport, GIT_REVISION, next_cycle_start},
+    error::CliError,
+};
+use anchor_lang::AccountDeserialize;
+use jito_steward::Config;
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+use stakenet_simulator_db::{
+    domain::VotePubkey, steward_config_snapshot::StewardConfigSnapshot,
+    steward_delegation::StewardDelegation,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+use tracing::warn;
+
+/// A steward cycle to compare, with the config that was live on chain and the delegations the
+/// steward recorded at its start
+pub struct ValidationCycle {
+    pub start_epoch: u16,
+    /// Epoch the cycle ends at (exclusive)
+    pub end_epoch: u16,
+    pub config: Config,
+    /// Epoch of the recorded steward state the cycle is compared against
+    pub actual_epoch: u64,
+    pub actual_delegations: Vec<StewardDelegation>,
+}
+
+/// Comparison of the simulated and actual delegations of a steward cycle
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CycleValidation {
+    pub start_epoch: u16,
+    pub end_epoch: u16,
+    pub actual_epoch: u64,
+    pub simulated_validators: usize,
+    pub actual_validators: usize,
+    /// Size of the intersection over the size of the union of both validator sets
+    pub jaccard_overlap: f64,
+    /// Spearman correlation between the simulated and actual scores of the validators selected
+    /// in both, unset when fewer than two validators are common
+    pub score_rank_correlation: Option<f64>,
+    /// Share of the pool that would have to move to turn the simulated delegation targets into
+    /// the actual ones, between 0 and 1
+    pub stake_weighted_divergence: f64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ValidationReport {
+    pub git_revision: String,
+    pub start_epoch: u16,
+    pub end_epoch: u16,
+    pub seed: u64,
+    pub cycles: Vec<CycleValidation>,
+    pub mean_jaccard_overlap: f64,
+    pub mean_score_rank_correlation: Option<f64>,
+    pub mean_stake_weighted_divergence: f64,
+}
+
+/// Loads the recorded steward state for every cycle of `start_epoch..end_epoch`, with cycles
+/// starting at multiples of `steward_cycle_length` like on-chain. Cycles without recorded
+/// delegations are skipped, and the fallback config is used when no config snapshot
+/// was recorded at or before the cycle.
+pub async fn load_validation_cycles(
+    db_connection: &Pool<Postgres>,
+    start_epoch: u16,
+    end_epoch: u16,
+    steward_cycle_length: u16,
+    fallback_config: &Config,
+) -> Result<Vec<ValidationCycle>, CliError> {
+    let mut cycles = Vec::new();
+
+    let cycle_starts = std::iter::successors(Some(start_epoch), |cycle_start| {
+        Some(next_cycle_start(*cycle_start, steward_cycle_length))
+            .filter(|next_start| *next_start < end_epoch)
+    });
+    for cycle_start in cycle_starts {
+        let cycle_end = next_cycle_start(cycle_start, steward_cycle_length).min(end_epoch);
+
+        let delegations = StewardDelegation::fetch_for_epoch_range(
+            db_connection,
+            cycle_start.into(),
+            cycle_end.into(),
+        )
+        .await?;
+        // Delegation targets are set when the cycle starts, compare against the earliest state
+        let Some(actual_epoch) = delegations.first().map(|delegation| delegation.epoch) else {
+            warn!(
+                "No steward delegations recorded for epochs {} to {}, skipping the cycle",
+                cycle_start, cycle_end
+            );
+            continue;
+        };
+        let actual_delegations = delegations
+            .into_iter()
+            .filter(|delegation| delegation.epoch == actual_epoch)
+            .collect();
+
+        let config = match StewardConfigSnapshot::fetch_at_or_before(
+            db_connection,
+            cycle_start.into(),
+        )
+        .await?
+        {
+            Some(snapshot) => Config::try_deserialize(&mut snapshot.config_data.as_slice())
+                .map_err(|_| CliError::AnchorDeserializeError)?,
+            None => {
+                warn!(
+                    "No steward config recorded at or before epoch {}, using the current config",
+                    cycle_start
+                );
+                *fallback_config
+            }
+        };
+
+        cycles.push(ValidationCycle {
+            start_epoch: cycle_start,
+            end_epoch: cycle_end,
+            config,
+            actual_epoch: actual_epoch.into(),
+            actual_delegations,
+        });
+    }
+
+    Ok(cycles)
+}
+
+/// Simulates every cycle on its own with the config that was live and compares the selected
+/// validators and their stake to what the steward actually delegated
+pub async fn validate_cycles(
+    dataset: Arc<BacktestDataset>,
+    cycles: &[ValidationCycle],
+    steward_cycle_length: u16,
+    seed: u64,
+) -> Result<ValidationReport, CliError> {
+    let mut results = Vec::with_capacity(cycles.len());
+
+    for cycle in cycles {
+        let report = Backtest::builder()
+            .dataset(Arc::clone(&dataset))
+            .config(cycle.config)
+            .window(cycle.start_epoch, cycle.end_epoch)
+            .seed(seed)
+            .steward_cycle_rate(steward_cycle_length)
+            .number_of_validator_delegations(
+                cycle.config.parameters.num_delegation_validators as usize,
+            )
+            .record_epoch_traces(true)
+            .run()
+            .await?;
+        results.push(compare_cycle(cycle, &report));
+    }
+
+    let mean = |values: Vec<f64>| {
+        if values.is_empty() {
+            None
+        } else {
+            Some(values.iter().sum::<f64>() / values.len() as f64)
+        }
+    };
+
+    Ok(ValidationReport {
+        git_revision: GIT_REVISION.to_string(),
+        start_epoch: cycles.first().map(|cycle| cycle.start_epoch).unwrap_or(0),
+        end_epoch: cycles.last().map(|cycle| cycle.end_epoch).unwrap_or(0),
+        seed,
+        mean_jaccard_overlap: mean(results.iter().map(|r| r.jaccard_overlap).collect())
+            .unwrap_or(0.0),
+        mean_score_rank_correlation: mean(
+            results
+                .iter()
+                .filter_map(|r| r.score_rank_correlation)
+                .collect(),
+        ),
+        mean_stake_weighted_divergence: mean(
+            results
+                .iter()
+                .map(|r| r.stake_weighted_divergence)
+                .collect(),
+        )
+        .unwrap_or(0.0),
+        cycles: results,
+    })
+}
+
+fn compare_cycle(cycle: &ValidationCycle, report: &BacktestReport) -> CycleValidation {
+    let simulated_scores: HashMap<VotePubkey, f64> = report
+        .validator_results
+        .iter()
+        .map(|result| (result.vote_account, result.last_score))
+        .collect();
+    // The actual shares are the targets the steward set at the start of the cycle, so they are
+    // compared with the targets of the simulated rebalance rather than the stake that moved since
+    let simulated_targets: HashMap<VotePubkey, f64> = report
+        .epoch_traces
+        .iter()
+        .find(|trace| trace.is_rebalancing_epoch)
+        .map(|trace| {
+            trace
+                .stake_states
+                .iter()
+                .filter(|(_, state)| state.target > 0)
+                .map(|(vote_account, state)| (*vote_account, state.target as f64))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let actual: Vec<&StewardDelegation> = cycle
+        .actual_delegations
+        .iter()
+        .filter(|delegation| delegation.delegation_numerator > 0)
+        .collect();
+    let actual_shares: HashMap<VotePubkey, f64> = actual
+        .iter()
+        .map(|delegation| (delegation.vote_pubkey, delegation.delegation_share()))
+        .collect();
+
+    let simulated_set: HashSet<VotePubkey> = simulated_scores.keys().copied().collect();
+    let actual_set: HashSet<VotePubkey> = actual_shares.keys().copied().collect();
+
+    let common_scores: Vec<(f64, f64)> = actual
+        .iter()
+        .filter_map(|delegation| {
+            simulated_scores
+                .get(&delegation.vote_pubkey)
+                .map(|score| (*score, delegation.score as f64))
+        })
+        .collect();
+
+    CycleValidation {
+        start_epoch: cycle.start_epoch,
+        end_epoch: cycle.end_epoch,
+        actual_epoch: cycle.actual_epoch,
+        simulated_validators: simulated_set.len(),
+        actual_validators: actual_set.len(),
+        jaccard_overlap: jaccard_overlap(&simulated_set, &actual_set),
+        score_rank_correlation: spearman_rank_correlation(&common_scores),
+        stake_weighted_divergence: stake_weighted_divergence(&simulated_targets, &actual_shares),
+    }
+}
+
+pub fn jaccard_overlap<T: Eq + std::hash::Hash>(a: &HashSet<T>, b: &HashSet<T>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+/// Ranks of the values, ties get the average of the ranks they span
+fn ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|a, b| values[*a].total_cmp(&values[*b]));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut i = 0;
+    while i < order.len() {
+        let mut j = i;
+        while j + 1 < order.len() && values[order[j + 1]] == values[order[i]] {
+            j += 1;
+        }
+        let rank = (i + j) as f64 / 2.0;
+        for index in &order[i..=j] {
+            ranks[*index] = rank;
+        }
+        i = j + 1;
+    }
+    ranks
+}
+
+/// Spearman rank correlation of the pairs, unset when it is undefined
+pub fn spearman_rank_correlation(pairs: &[(f64, f64)]) -> Option<f64> {
+    if pairs.len() < 2 {
+        return None;
+    }
+    let a = ranks(&pairs.iter().map(|pair| pair.0).collect::<Vec<_>>());
+    let b = ranks(&pairs.iter().map(|pair| pair.1).collect::<Vec<_>>());
+
+    let n = pairs.len() as f64;
+    let mean_a = a.iter().sum::<f64>() / n;
+    let mean_b = b.iter().sum::<f64>() / n;
+    let covariance: f64 = a
+        .iter()
+        .zip(&b)
+        .map(|(x, y)| (x - mean_a) * (y - mean_b))
+        .sum();
+    let variance_a: f64 = a.iter().map(|x| (x - mean_a).powi(2)).sum();
+    let variance_b: f64 = b.iter().map(|y| (y - mean_b).powi(2)).sum();
+    if variance_a == 0.0 || variance_b == 0.0 {
+        return None;
+    }
+    Some(covariance / (variance_a * variance_b).sqrt())
+}
+
+/// Total variation distance between the two stake distributions after normalizing each to 1
+pub fn stake_weighted_divergence<K: Eq + std::hash::Hash>(
+    a: &HashMap<K, f64>,
+    b: &HashMap<K, f64>,
+) -> f64 {
+    let total_a: f64 = a.values().sum();
+    let total_b: f64 = b.values().sum();
+    let share = |stakes: &HashMap<K, f64>, total: f64, key: &K| {
+        if total > 0.0 {
+            stakes.get(key).copied().unwrap_or(0.0) / total
+        } else {
+            0.0
+        }
+    };
+
+    let keys: HashSet<&K> = a.keys().chain(b.keys()).collect();
+    keys.into_iter()
+        .map(|key| (share(a, total_a, key) - share(b, total_b, key)).abs())
+        .sum::<f64>()
+        / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validation_metrics() {
+        let a: HashSet<&str> = ["a", "b", "c"].into();
+        let b: HashSet<&str> = ["b", "c", "d"].into();
+        assert_eq!(jaccard_overlap(&a, &b), 0.5);
+
+        let same_order = [(1.0, 10.0), (2.0, 20.0), (3.0, 40.0)];
+        assert_eq!(spearman_rank_correlation(&same_order), Some(1.0));
+        let reversed = [(1.0, 40.0), (2.0, 20.0), (3.0, 10.0)];
+        assert_eq!(spearman_rank_correlation(&reversed), Some(-1.0));
+        assert_eq!(spearman_rank_correlation(&[(1.0, 1.0)]), None);
+
+        let simulated: HashMap<&str, f64> = [("a", 1.0), ("b", 1.0)].into();
+        let actual: HashMap<&str, f64> = [("b", 0.5), ("c", 0.5)].into();
+        assert_eq!(stake_weighted_divergence(&simulated, &actual), 0.5);
+        assert_eq!(stake_weighted_divergence(&simulated, &simulated), 0.0);
+    }
+}
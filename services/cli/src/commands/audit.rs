@@ -0,0 +1,89 @@
+use crate::{
+    backtester::{BacktestDataset, audit_dataset},
+    error::CliError,
+};
+use clap::Parser;
+use stakenet_simulator_db::store::SimulatorStore;
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+#[derive(Clone, Debug, Parser)]
+pub struct AuditArgs {
+    /// First epoch to audit
+    #[arg(long, env)]
+    pub start_epoch: u16,
+    /// Epoch the audit stops at
+    #[arg(long, env)]
+    pub end_epoch: u16,
+    /// File to write the JSON audit report to, printed to stdout when not set
+    #[arg(long, env)]
+    pub output: Option<PathBuf>,
+    #[arg(long, env)]
+    pub pretty_output: bool,
+}
+
+/// Checks the data a backtest of the epochs would read for gaps and inconsistencies. Fails when
+/// any issue is found so backtests can be gated on complete data.
+pub async fn handle_audit(args: AuditArgs, store: &dyn SimulatorStore) -> Result<(), CliError> {
+    if args.start_epoch >= args.end_epoch {
+        return Err(CliError::InvalidEpochWindow {
+            start_epoch: args.start_epoch,
+            end_epoch: args.end_epoch,
+        });
+    }
+
+    let dataset = BacktestDataset::load(store, args.start_epoch, args.end_epoch, 0).await?;
+    let report = audit_dataset(&dataset, args.start_epoch, args.end_epoch);
+
+    for table in &report.tables {
+        info!(
+            "{}: {} records, {} missing epochs",
+            table.table,
+            table.records,
+            table.missing_epochs.len()
+        );
+    }
+    for sentinel in &report.sentinel_values {
+        warn!(
+            "{} holds the default value in {} records",
+            sentinel.field, sentinel.count
+        );
+    }
+    if !report.validators_without_rewards.is_empty() {
+        warn!(
+            "{} validators have history but no rewards",
+            report.validators_without_rewards.len()
+        );
+    }
+    if !report.zero_stake_rewards.is_empty() {
+        warn!(
+            "{} rewards have no active stake",
+            report.zero_stake_rewards.len()
+        );
+    }
+    if report.active_stake_records != report.inactive_stake_records {
+        warn!(
+            "Active stake has {} records, inactive stake has {} records",
+            report.active_stake_records, report.inactive_stake_records
+        );
+    }
+
+    let contents = if args.pretty_output {
+        serde_json::to_string_pretty(&report)?
+    } else {
+        serde_json::to_string(&report)?
+    };
+    match args.output {
+        Some(path) => {
+            std::fs::write(&path, contents)?;
+            info!("Audit report written to {}", path.display());
+        }
+        None => println!("{}", contents),
+    }
+
+    if !report.passed() {
+        return Err(CliError::AuditFailed(report.issues));
+    }
+    info!("Audit passed");
+    Ok(())
+}
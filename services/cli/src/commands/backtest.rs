@@ -1,208 +1,285 @@
-use crate::utils::{
-    RebalancingCycle, RebalancingSimulator, calculate_aggregated_apy,
-    calculate_stake_utilization_rate,
+use crate::{
+    backtester::{
+        Backtest, BacktestDataset, CoveragePolicy, DelegationStrategyOptions, DirectedStakeTarget,
+        ScoringModelOptions, ValidatorListOptions, save_report, write_score_breakdowns_csv,
+    },
+    commands::connect_store,
+    error::CliError,
+    scenario::Scenario,
+    steward_utils::{StewardParameterOverrides, fetch_config},
 };
-use crate::{error::CliError, modify_config_parameter_from_args, steward_utils::fetch_config};
 use clap::Parser;
 use jito_steward::Config;
 use solana_client::nonblocking::rpc_client::RpcClient;
-use sqlx::{Pool, Postgres};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use stakenet_simulator_db::store::{SimulatorStore, Snapshot};
+use std::path::PathBuf;
 use tracing::info;
 
 pub const DAYS_PER_YEAR: f64 = 365.0;
 
-#[derive(Clone, Debug, Parser)]
+#[derive(Clone, Debug, Default, Parser)]
 pub struct BacktestArgs {
+    /// Scenario to load the backtest settings from, as `<file.toml>:<name>`. Arguments passed
+    /// through the CLI take precedence over the scenario.
     #[arg(long, env)]
-    pub mev_commission_range: Option<u16>,
+    pub scenario: Option<String>,
+    #[command(flatten)]
+    pub parameters: StewardParameterOverrides,
+    #[command(flatten)]
+    pub strategy: DelegationStrategyOptions,
+    #[command(flatten)]
+    pub scoring: ScoringModelOptions,
+    #[command(flatten)]
+    pub validators: ValidatorListOptions,
+    /// Stake delegated regardless of the score before the steward distributes the rest of the
+    /// pool, as comma separated `<vote account>:<lamports>` or `<vote account>:<bps>bps`
+    #[arg(long, env, value_delimiter = ',')]
+    pub directed_stake: Vec<DirectedStakeTarget>,
     #[arg(long, env)]
-    pub epoch_credits_range: Option<u16>,
-    #[arg(long, env)]
-    pub commission_range: Option<u16>,
-    #[arg(long, env)]
-    pub scoring_delinquency_threshold_ratio: Option<f64>,
-    #[arg(long, env)]
-    pub instant_unstake_delinquency_threshold_ratio: Option<f64>,
-    #[arg(long, env)]
-    pub mev_commission_bps_threshold: Option<u16>,
-    #[arg(long, env)]
-    pub commission_threshold: Option<u8>,
-    #[arg(long, env)]
-    pub historical_commission_threshold: Option<u8>,
-    #[arg(long, env)]
-    pub priority_fee_lookback_epochs: Option<u8>,
-    #[arg(long, env)]
-    pub priority_fee_lookback_offset: Option<u8>,
+    target_epoch: Option<u64>,
+    /// Number of epochs in a steward cycle, overrides `num_epochs_between_scoring` of the
+    /// steward config
     #[arg(long, env)]
-    pub priority_fee_max_commission_bps: Option<u16>,
+    pub steward_cycle_rate: Option<u16>,
+    /// Step through the phases of the steward program every epoch and record the transitions
     #[arg(long, env)]
-    pub priority_fee_error_margin_bps: Option<u16>,
+    pub steward_phases: bool,
+    /// Unstake the stake deposited to the pool's validators, up to
+    /// `stake_deposit_unstake_cap_bps` of the pool per cycle
     #[arg(long, env)]
-    pub num_delegation_validators: Option<u32>,
+    pub stake_deposit_unstake: bool,
+    /// What to do when validators have stake but no recorded rewards in the window, defaults to
+    /// failing
+    #[arg(long, env, value_enum)]
+    pub coverage_policy: Option<CoveragePolicy>,
+    /// Snapshot file to load the backtest data from instead of the database
     #[arg(long, env)]
-    pub scoring_unstake_cap_bps: Option<u32>,
+    pub snapshot: Option<PathBuf>,
+    /// First epoch of the simulation
     #[arg(long, env)]
-    pub instant_unstake_cap_bps: Option<u32>,
+    pub start_epoch: Option<u16>,
+    /// Epoch the simulation stops at, defaults to the last epoch recorded in the data
     #[arg(long, env)]
-    pub stake_deposit_unstake_cap_bps: Option<u32>,
+    pub end_epoch: Option<u16>,
+    /// Seed used for the random parts of the simulation. A random seed is picked when not set
     #[arg(long, env)]
-    pub instant_unstake_epoch_progress: Option<f64>,
+    pub seed: Option<u64>,
     #[arg(long, env)]
-    pub compute_score_slot_range: Option<u64>,
+    pub number_of_validator_delegations: Option<usize>,
+    /// Size of the simulated pool at the start, defaults to 1 SOL per delegated validator
     #[arg(long, env)]
-    pub instant_unstake_inputs_epoch_progress: Option<f64>,
+    pub initial_pool_lamports: Option<u64>,
+    /// Fee paid for every stake activation and deactivation, deducted in the cost adjusted APY
     #[arg(long, env)]
-    pub num_epochs_between_scoring: Option<u64>,
+    pub transaction_fee_lamports: Option<u64>,
+    /// File to write the JSON backtest report to
     #[arg(long, env)]
-    pub minimum_stake_lamports: Option<u64>,
+    pub output: Option<PathBuf>,
     #[arg(long, env)]
-    pub minimum_voting_epochs: Option<u64>,
+    pub pretty_output: bool,
+    /// File to write the CSV score breakdown of every validator in every scoring epoch to
     #[arg(long, env)]
-    priority_fee_scoring_start_epoch: Option<u16>,
+    pub score_breakdown_output: Option<PathBuf>,
+    /// Save the run and its results in the backtest history tables
     #[arg(long, env)]
-    target_epoch: Option<u64>,
-    #[arg(long, env, default_value = "10")]
-    steward_cycle_rate: u16,
+    pub save: bool,
 }
 
 impl BacktestArgs {
     pub fn update_steward_config(&self, config: &mut Config) {
-        modify_config_parameter_from_args!(self, config, mev_commission_range);
-        modify_config_parameter_from_args!(self, config, epoch_credits_range);
-        modify_config_parameter_from_args!(self, config, commission_range);
-        modify_config_parameter_from_args!(self, config, scoring_delinquency_threshold_ratio);
-        modify_config_parameter_from_args!(
-            self,
-            config,
-            instant_unstake_delinquency_threshold_ratio
-        );
-        modify_config_parameter_from_args!(self, config, mev_commission_bps_threshold);
-        modify_config_parameter_from_args!(self, config, commission_threshold);
-        modify_config_parameter_from_args!(self, config, historical_commission_threshold);
-        modify_config_parameter_from_args!(self, config, priority_fee_lookback_epochs);
-        modify_config_parameter_from_args!(self, config, priority_fee_lookback_offset);
-        modify_config_parameter_from_args!(self, config, priority_fee_max_commission_bps);
-        modify_config_parameter_from_args!(self, config, priority_fee_error_margin_bps);
-        modify_config_parameter_from_args!(self, config, num_delegation_validators);
-        modify_config_parameter_from_args!(self, config, scoring_unstake_cap_bps);
-        modify_config_parameter_from_args!(self, config, instant_unstake_cap_bps);
-        modify_config_parameter_from_args!(self, config, stake_deposit_unstake_cap_bps);
-        modify_config_parameter_from_args!(self, config, compute_score_slot_range);
-        modify_config_parameter_from_args!(self, config, instant_unstake_epoch_progress);
-        modify_config_parameter_from_args!(self, config, instant_unstake_inputs_epoch_progress);
-        modify_config_parameter_from_args!(self, config, num_epochs_between_scoring);
-        modify_config_parameter_from_args!(self, config, minimum_stake_lamports);
-        modify_config_parameter_from_args!(self, config, minimum_voting_epochs);
-        modify_config_parameter_from_args!(self, config, priority_fee_scoring_start_epoch);
-    }
-}
-
-impl Default for BacktestArgs {
-    fn default() -> Self {
-        BacktestArgs {
-            mev_commission_range: None,
-            epoch_credits_range: None,
-            commission_range: None,
-            scoring_delinquency_threshold_ratio: None,
-            instant_unstake_delinquency_threshold_ratio: None,
-            mev_commission_bps_threshold: None,
-            commission_threshold: None,
-            historical_commission_threshold: None,
-            priority_fee_lookback_epochs: None,
-            priority_fee_lookback_offset: None,
-            priority_fee_max_commission_bps: None,
-            priority_fee_error_margin_bps: None,
-            num_delegation_validators: None,
-            scoring_unstake_cap_bps: None,
-            instant_unstake_cap_bps: None,
-            stake_deposit_unstake_cap_bps: None,
-            instant_unstake_epoch_progress: None,
-            compute_score_slot_range: None,
-            instant_unstake_inputs_epoch_progress: None,
-            num_epochs_between_scoring: None,
-            minimum_stake_lamports: None,
-            minimum_voting_epochs: None,
-            priority_fee_scoring_start_epoch: None,
-            target_epoch: None,
-            steward_cycle_rate: 10,
-        }
+        self.parameters.update_steward_config(config);
     }
 }
 
+/// Runs a backtest on the data of the snapshot, or of the configured database when no snapshot
+/// is given. The database is only connected to when the data or the saved run need it.
 pub async fn handle_backtest(
     args: BacktestArgs,
-    db_connection: &Pool<Postgres>,
+    db_connection_url: &str,
     rpc_client: &RpcClient,
-    current_epoch: u16,
     look_back_period: u16,
 ) -> Result<f64, CliError> {
-    // TODO: Determine if this should be an argument
-    let number_of_validator_delegations = 200;
+    let scenario = match &args.scenario {
+        Some(reference) => Scenario::load(reference)?,
+        None => Scenario::default(),
+    };
+    let snapshot = match &args.snapshot {
+        Some(path) => Some(Snapshot::open(path).await?),
+        None => None,
+    };
+    let store = if snapshot.is_none() || args.save {
+        Some(connect_store(db_connection_url).await?)
+    } else {
+        None
+    };
+    // Runs are saved in the backtest history tables, which only the Postgres database holds
+    let history_connection = match &store {
+        Some(store) if args.save => Some(
+            store
+                .postgres()
+                .ok_or(CliError::PostgresRequired("backtest --save"))?,
+        ),
+        _ => None,
+    };
 
-    // Load existing steward config and overwrite parameters based on CLI args
+    // Load existing steward config and overwrite parameters based on the scenario and CLI args
     let mut steward_config = fetch_config(rpc_client).await?;
+    scenario
+        .parameters
+        .update_steward_config(&mut steward_config);
     args.update_steward_config(&mut steward_config);
 
-    let simulation_start_epoch = current_epoch.saturating_sub(look_back_period);
+    let source = snapshot
+        .as_ref()
+        .map(|snapshot| snapshot.store() as &dyn SimulatorStore)
+        .or(store.as_deref())
+        .ok_or(CliError::MissingBacktestInput("backtest data"))?;
+
+    let simulation_end_epoch = match args.end_epoch.or(scenario.end_epoch) {
+        Some(end_epoch) => end_epoch,
+        // Defaults to the last epoch recorded in the data
+        None => source
+            .fetch_cluster_history_entries()
+            .await?
+            .into_iter()
+            .filter_map(|entry| u16::try_from(entry.epoch).ok())
+            .max()
+            .ok_or(CliError::MissingBacktestInput("end epoch"))?,
+    };
+    let simulation_start_epoch = args
+        .start_epoch
+        .or(scenario.start_epoch)
+        .unwrap_or(simulation_end_epoch.saturating_sub(look_back_period));
+    if simulation_start_epoch >= simulation_end_epoch {
+        return Err(CliError::InvalidEpochWindow {
+            start_epoch: simulation_start_epoch,
+            end_epoch: simulation_end_epoch,
+        });
+    }
+
+    let seed = args.seed.or(scenario.seed).unwrap_or_else(rand::random);
+    info!(
+        "Backtesting epochs {} to {} with seed {}",
+        simulation_start_epoch, simulation_end_epoch, seed
+    );
 
-    let rebalancing_cycles = rebalancing_simulation(
-        db_connection,
-        &steward_config,
+    let mut strategy = args.strategy.clone();
+    strategy.fill_unset_from(&scenario.strategy);
+    let mut scoring = args.scoring.clone();
+    scoring.fill_unset_from(&scenario.scoring);
+    let scoring_model = scoring.build();
+    let mut validators = args.validators.clone();
+    validators.fill_unset_from(&scenario.validators);
+
+    let dataset = BacktestDataset::load(
+        source,
         simulation_start_epoch,
-        current_epoch,
-        args.steward_cycle_rate,
-        number_of_validator_delegations,
-        steward_config.parameters.instant_unstake_cap_bps,
-        steward_config.parameters.scoring_unstake_cap_bps,
-        std::cmp::max(
-            steward_config.parameters.mev_commission_range,
-            std::cmp::max(
-                steward_config.parameters.epoch_credits_range,
-                steward_config.parameters.commission_range,
-            ),
-        ),
+        simulation_end_epoch,
+        scoring_model.lookback_epochs(&steward_config),
     )
     .await?;
 
-    let aggregated_apy = calculate_aggregated_apy(&rebalancing_cycles, look_back_period)?;
+    let mut backtest = Backtest::builder()
+        .dataset(dataset)
+        .config(steward_config)
+        .window(simulation_start_epoch, simulation_end_epoch)
+        .seed(seed)
+        .delegation_strategy(strategy.build())
+        .scoring_model(scoring_model)
+        .validator_filter(validators.build())
+        .directed_stake(if args.directed_stake.is_empty() {
+            scenario.directed_stake.clone()
+        } else {
+            args.directed_stake.clone()
+        })
+        .steward_phases(args.steward_phases || scenario.steward_phases.unwrap_or(false))
+        .stake_deposit_unstake(
+            args.stake_deposit_unstake || scenario.stake_deposit_unstake.unwrap_or(false),
+        )
+        .coverage_policy(
+            args.coverage_policy
+                .or(scenario.coverage_policy)
+                .unwrap_or_default(),
+        )
+        .record_score_breakdowns(args.score_breakdown_output.is_some());
+    if let Some(number_of_validator_delegations) = args
+        .number_of_validator_delegations
+        .or(scenario.number_of_validator_delegations)
+    {
+        backtest = backtest.number_of_validator_delegations(number_of_validator_delegations);
+    }
+    if let Some(steward_cycle_rate) = args.steward_cycle_rate.or(scenario.steward_cycle_rate) {
+        backtest = backtest.steward_cycle_rate(steward_cycle_rate);
+    }
+    if let Some(initial_pool_lamports) = args
+        .initial_pool_lamports
+        .or(scenario.initial_pool_lamports)
+    {
+        backtest = backtest.initial_pool_lamports(initial_pool_lamports);
+    }
+    if let Some(transaction_fee_lamports) = args
+        .transaction_fee_lamports
+        .or(scenario.transaction_fee_lamports)
+    {
+        backtest = backtest.transaction_fee_lamports(transaction_fee_lamports);
+    }
 
-    let stake_utilization_ratio =
-        calculate_stake_utilization_rate(db_connection, look_back_period, current_epoch).await?;
+    let mut report = backtest.run().await?;
+    report.scenario = args.scenario;
 
-    let final_apy = aggregated_apy * stake_utilization_ratio;
+    info!(
+        "Rebalancing cycles completed: {}",
+        report.rebalancing_cycles.len()
+    );
+    info!("Raw aggregated APY: {:.4}%", report.aggregated_apy * 100.0);
+    info!(
+        "Stake utilization ratio: {:.4}",
+        report.stake_utilization_ratio
+    );
+    info!("Final adjusted APY: {:.4}%", report.final_apy * 100.0);
+    info!(
+        "Turnover: {:.3} SOL unstaked ({:.2}x the initial pool), {:.6} SOL of forgone rewards, {} transactions",
+        report.turnover.total_unstaked_lamports as f64 / LAMPORTS_PER_SOL as f64,
+        report.turnover.turnover_ratio,
+        report.turnover.forgone_rewards_lamports as f64 / LAMPORTS_PER_SOL as f64,
+        report.turnover.transactions
+    );
+    info!(
+        "Cost adjusted APY: {:.4}%",
+        report.turnover.cost_adjusted_apy * 100.0
+    );
+    if let Some(directed_stake) = &report.directed_stake {
+        info!(
+            "Directed stake APY: {:.4}%, steward stake APY: {:.4}%",
+            directed_stake.directed_apy * 100.0,
+            directed_stake.steward_apy * 100.0
+        );
+    }
 
-    info!("Rebalancing cycles completed: {}", rebalancing_cycles.len());
-    info!("Raw aggregated APY: {:.4}%", aggregated_apy * 100.0);
-    info!("Stake utilization ratio: {:.4}", stake_utilization_ratio);
-    info!("Final adjusted APY: {:.4}%", final_apy * 100.0);
+    if let Some(path) = &args.score_breakdown_output {
+        let file = std::fs::File::create(path)?;
+        write_score_breakdowns_csv(file, &report.score_breakdowns)?;
+        info!("Score breakdowns written to {}", path.display());
+        // The breakdowns are too large for the report, they only go to the CSV file
+        report.score_breakdowns.clear();
+    }
 
-    Ok(final_apy)
-}
+    if let Some(db_connection) = history_connection {
+        let id = save_report(db_connection, &report).await?;
+        info!("Backtest run saved as {}", id);
+    }
 
-pub async fn rebalancing_simulation(
-    db_connection: &Pool<Postgres>,
-    steward_config: &Config,
-    simulation_start_epoch: u16,
-    simulation_end_epoch: u16,
-    steward_cycle_rate: u16,
-    number_of_validator_delegations: usize,
-    instant_unstake_cap_bps: u32,
-    scoring_unstake_cap_bps: u32,
-    validator_historical_start_offset: u16,
-) -> Result<Vec<RebalancingCycle>, CliError> {
-    let mut simulator = RebalancingSimulator::new(
-        db_connection,
-        *steward_config,
-        simulation_start_epoch,
-        simulation_end_epoch,
-        steward_cycle_rate,
-        number_of_validator_delegations,
-        instant_unstake_cap_bps,
-        scoring_unstake_cap_bps,
-        validator_historical_start_offset,
-    )
-    .await?;
+    if let Some(path) = args.output.or(scenario.output.path) {
+        let pretty = args.pretty_output || scenario.output.pretty.unwrap_or(false);
+        let contents = if pretty {
+            serde_json::to_string_pretty(&report)?
+        } else {
+            serde_json::to_string(&report)?
+        };
+        std::fs::write(&path, contents)?;
+        info!("Backtest report written to {}", path.display());
+    }
 
-    // Run the simulation
-    simulator.run_simulation(db_connection).await
+    Ok(report.final_apy)
 }
@@ -0,0 +1,46 @@
+use crate::error::CliError;
+use clap::Parser;
+use stakenet_simulator_db::store::{self, SimulatorStore, copy_simulator_data};
+use tracing::info;
+
+#[derive(Clone, Debug, Parser)]
+pub struct CopyDataArgs {
+    /// Store to copy the data to, e.g. `sqlite://backtest.db`. The SQLite file is created when
+    /// it does not exist.
+    #[arg(long, env)]
+    pub destination_url: String,
+    /// First epoch to copy, include the scoring lookback of the backtests to run
+    #[arg(long, env)]
+    pub start_epoch: u16,
+    /// Last epoch to copy
+    #[arg(long, env)]
+    pub end_epoch: u16,
+}
+
+/// Copies the data the simulator reads for an epoch range to another store, so backtests can run
+/// from a local SQLite file without the Postgres database
+pub async fn handle_copy_data(
+    args: CopyDataArgs,
+    store: &dyn SimulatorStore,
+) -> Result<(), CliError> {
+    if args.start_epoch >= args.end_epoch {
+        return Err(CliError::InvalidEpochWindow {
+            start_epoch: args.start_epoch,
+            end_epoch: args.end_epoch,
+        });
+    }
+
+    let destination = store::connect(&args.destination_url).await?;
+    copy_simulator_data(
+        store,
+        destination.as_ref(),
+        args.start_epoch.into(),
+        args.end_epoch.into(),
+    )
+    .await?;
+    info!(
+        "Copied epochs {} to {} to {}",
+        args.start_epoch, args.end_epoch, args.destination_url
+    );
+    Ok(())
+}
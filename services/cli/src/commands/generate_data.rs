@@ -0,0 +1,58 @@
+use crate::{
+    backtester::{SyntheticBehavior, SyntheticDataset, SyntheticDatasetConfig},
+    error::CliError,
+};
+use clap::Parser;
+use stakenet_simulator_db::store;
+use tracing::info;
+
+#[derive(Clone, Debug, Parser)]
+pub struct GenerateDataArgs {
+    /// Store to write the synthetic data to, e.g. `sqlite://what-if.db`. The SQLite file is
+    /// created when it does not exist.
+    #[arg(long, env)]
+    pub destination_url: String,
+    /// Number of validators to generate
+    #[arg(long, env, default_value_t = 50)]
+    pub num_validators: usize,
+    /// First epoch to generate, include the scoring lookback of the backtests to run
+    #[arg(long, env)]
+    pub start_epoch: u16,
+    /// Last epoch to generate
+    #[arg(long, env)]
+    pub end_epoch: u16,
+    /// Seed of the generated data, the same seed always generates the same data
+    #[arg(long, env, default_value_t = 0)]
+    pub seed: u64,
+    /// Changes in the behavior of validators, as comma separated
+    /// `delinquent:<validator>@<epoch>`, `commission:<validator>@<epoch>=<percent>` or
+    /// `mev-commission:<validator>@<epoch>=<bps>`, where validators are indexes from 0
+    #[arg(long, env, value_delimiter = ',')]
+    pub behavior: Vec<SyntheticBehavior>,
+}
+
+/// Writes a synthetic dataset to a store, to backtest what-if scenarios against data with known
+/// validator behaviors
+pub async fn handle_generate_data(args: GenerateDataArgs) -> Result<(), CliError> {
+    if args.start_epoch >= args.end_epoch {
+        return Err(CliError::InvalidEpochWindow {
+            start_epoch: args.start_epoch,
+            end_epoch: args.end_epoch,
+        });
+    }
+
+    let dataset = SyntheticDataset::generate(&SyntheticDatasetConfig {
+        num_validators: args.num_validators,
+        start_epoch: args.start_epoch,
+        end_epoch: args.end_epoch,
+        seed: args.seed,
+        behaviors: args.behavior,
+    });
+    let destination = store::connect(&args.destination_url).await?;
+    dataset.insert_into(destination.as_ref()).await?;
+    info!(
+        "Generated {} validators over epochs {} to {} in {}",
+        args.num_validators, args.start_epoch, args.end_epoch, args.destination_url
+    );
+    Ok(())
+}
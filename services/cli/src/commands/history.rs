@@ -0,0 +1,220 @@
+use crate::{
+    backtester::{BacktestReport, load_report},
+    error::CliError,
+};
+use clap::{Parser, Subcommand};
+use serde_json::Value;
+use sqlx::{Pool, Postgres};
+use stakenet_simulator_db::{backtest_run::BacktestRun, domain::VotePubkey};
+use std::collections::{BTreeSet, HashSet};
+
+#[derive(Clone, Debug, Parser)]
+pub struct HistoryArgs {
+    #[command(subcommand)]
+    pub command: HistoryCommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum HistoryCommand {
+    /// Lists the most recent backtest runs saved with `backtest --save`
+    List {
+        #[arg(long, default_value_t = 20)]
+        limit: i64,
+    },
+    /// Compares the settings and results of two saved backtest runs
+    Diff { run_a: String, run_b: String },
+}
+
+pub async fn handle_history(
+    args: HistoryArgs,
+    db_connection: &Pool<Postgres>,
+) -> Result<(), CliError> {
+    match args.command {
+        HistoryCommand::List { limit } => {
+            let runs = BacktestRun::fetch_recent(db_connection, limit).await?;
+            for run in runs {
+                println!(
+                    "{} {} rev {} epochs {}..{} seed {} APY {:.4}%{}",
+                    run.id,
+                    run.created_at.format("%Y-%m-%d %H:%M:%S"),
+                    run.git_revision,
+                    run.start_epoch,
+                    run.end_epoch,
+                    run.seed,
+                    run.final_apy * 100.0,
+                    run.scenario
+                        .map(|scenario| format!(" scenario {}", scenario))
+                        .unwrap_or_default(),
+                );
+            }
+        }
+        HistoryCommand::Diff { run_a, run_b } => {
+            let report_a = load_report(db_connection, &run_a).await?;
+            let report_b = load_report(db_connection, &run_b).await?;
+            println!("{} -> {}", run_a, run_b);
+            for line in diff_reports(&report_a, &report_b)? {
+                println!("{}", line);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Describes every difference in settings and results between two reports, one per line
+pub fn diff_reports(a: &BacktestReport, b: &BacktestReport) -> Result<Vec<String>, CliError> {
+    let mut lines = Vec::new();
+
+    if a.git_revision != b.git_revision {
+        lines.push(format!(
+            "git revision: {} -> {}",
+            a.git_revision, b.git_revision
+        ));
+    }
+    if (a.simulation_start_epoch, a.simulation_end_epoch)
+        != (b.simulation_start_epoch, b.simulation_end_epoch)
+    {
+        lines.push(format!(
+            "window: {}..{} -> {}..{}",
+            a.simulation_start_epoch,
+            a.simulation_end_epoch,
+            b.simulation_start_epoch,
+            b.simulation_end_epoch
+        ));
+    }
+    if a.seed != b.seed {
+        lines.push(format!("seed: {} -> {}", a.seed, b.seed));
+    }
+    if a.number_of_validator_delegations != b.number_of_validator_delegations {
+        lines.push(format!(
+            "number of validator delegations: {} -> {}",
+            a.number_of_validator_delegations, b.number_of_validator_delegations
+        ));
+    }
+
+    if a.delegation_strategy != b.delegation_strategy {
+        lines.push(format!(
+            "delegation strategy: {} -> {}",
+            a.delegation_strategy, b.delegation_strategy
+        ));
+    }
+    if a.scoring_model != b.scoring_model {
+        lines.push(format!(
+            "scoring model: {} -> {}",
+            a.scoring_model, b.scoring_model
+        ));
+    }
+
+    let parameters_a = serde_json::to_value(&a.parameters)?;
+    let parameters_b = serde_json::to_value(&b.parameters)?;
+    if let (Value::Object(parameters_a), Value::Object(parameters_b)) = (parameters_a, parameters_b)
+    {
+        let names: BTreeSet<&String> = parameters_a.keys().chain(parameters_b.keys()).collect();
+        for name in names {
+            let value_a = parameters_a.get(name).unwrap_or(&Value::Null);
+            let value_b = parameters_b.get(name).unwrap_or(&Value::Null);
+            if value_a != value_b {
+                lines.push(format!("{}: {} -> {}", name, value_a, value_b));
+            }
+        }
+    }
+
+    lines.push(format!(
+        "final APY: {:.4}% -> {:.4}% ({:+.4}%)",
+        a.final_apy * 100.0,
+        b.final_apy * 100.0,
+        (b.final_apy - a.final_apy) * 100.0
+    ));
+    lines.push(format!(
+        "aggregated APY: {:.4}% -> {:.4}% ({:+.4}%)",
+        a.aggregated_apy * 100.0,
+        b.aggregated_apy * 100.0,
+        (b.aggregated_apy - a.aggregated_apy) * 100.0
+    ));
+    lines.push(format!(
+        "stake utilization ratio: {:.4} -> {:.4}",
+        a.stake_utilization_ratio, b.stake_utilization_ratio
+    ));
+    lines.push(format!(
+        "rebalancing cycles: {} -> {}",
+        a.rebalancing_cycles.len(),
+        b.rebalancing_cycles.len()
+    ));
+
+    let validators_a: HashSet<&VotePubkey> = a
+        .validator_results
+        .iter()
+        .map(|result| &result.vote_account)
+        .collect();
+    let validators_b: HashSet<&VotePubkey> = b
+        .validator_results
+        .iter()
+        .map(|result| &result.vote_account)
+        .collect();
+    lines.push(format!(
+        "selected validators: {} common, {} only in the first run, {} only in the second run",
+        validators_a.intersection(&validators_b).count(),
+        validators_a.difference(&validators_b).count(),
+        validators_b.difference(&validators_a).count()
+    ));
+
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{backtester::ValidatorResult, steward_utils::StewardParameterOverrides};
+    use solana_sdk::pubkey::Pubkey;
+
+    fn report(seed: u64, validators: &[VotePubkey]) -> BacktestReport {
+        BacktestReport {
+            scenario: None,
+            git_revision: "abc1234".to_string(),
+            simulation_start_epoch: 600,
+            simulation_end_epoch: 700,
+            seed,
+            number_of_validator_delegations: 200,
+            delegation_strategy: "steward".to_string(),
+            scoring_model: "on-chain".to_string(),
+            parameters: StewardParameterOverrides::default(),
+            rebalancing_cycles: vec![],
+            validator_results: validators
+                .iter()
+                .map(|vote_account| ValidatorResult {
+                    vote_account: *vote_account,
+                    cycles_selected: 1,
+                    last_score: 1.0,
+                    final_stake_lamports: 1,
+                })
+                .collect(),
+            directed_stake: None,
+            decentralization: vec![],
+            turnover: Default::default(),
+            phase_transitions: vec![],
+            coverage: Default::default(),
+            scoring_summaries: vec![],
+            score_breakdowns: vec![],
+            epoch_traces: vec![],
+            aggregated_apy: 0.07,
+            stake_utilization_ratio: 1.0,
+            final_apy: 0.07,
+        }
+    }
+
+    #[test]
+    fn test_diff_reports() {
+        let [v1, v2, v3, v4] = [(); 4].map(|_| VotePubkey(Pubkey::new_unique()));
+        let a = report(1, &[v1, v2]);
+        let mut b = report(2, &[v2, v3, v4]);
+        b.parameters.mev_commission_bps_threshold = Some(1000);
+
+        let lines = diff_reports(&a, &b).unwrap();
+        assert!(lines.contains(&"seed: 1 -> 2".to_string()));
+        assert!(lines.contains(&"mev_commission_bps_threshold: null -> 1000".to_string()));
+        assert!(!lines.iter().any(|line| line.starts_with("git revision")));
+        assert!(lines.contains(
+            &"selected validators: 1 common, 1 only in the first run, 2 only in the second run"
+                .to_string()
+        ));
+    }
+}
@@ -1,3 +1,32 @@
+pub mod audit;
 pub mod backtest;
+pub mod copy_data;
+pub mod generate_data;
+pub mod history;
+pub mod snapshot;
+pub mod validate;
 
+pub use audit::*;
 pub use backtest::*;
+pub use copy_data::*;
+pub use generate_data::*;
+pub use history::*;
+pub use snapshot::*;
+pub use validate::*;
+
+use crate::error::CliError;
+use stakenet_simulator_db::{
+    migrations::check_schema_version,
+    store::{self, SimulatorStore},
+};
+
+/// Connects to the configured database, checking that a Postgres database has no pending
+/// migrations. Commands only connect when they read or write it, so the ones working on files
+/// and other stores run without the database.
+pub async fn connect_store(db_connection_url: &str) -> Result<Box<dyn SimulatorStore>, CliError> {
+    let store = store::connect(db_connection_url).await?;
+    if let Some(db_connection) = store.postgres() {
+        check_schema_version(db_connection).await?;
+    }
+    Ok(store)
+}
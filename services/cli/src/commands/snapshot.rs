@@ -0,0 +1,73 @@
+use crate::{commands::connect_store, error::CliError};
+use clap::Parser;
+use stakenet_simulator_db::store::{self, SimulatorStore, Snapshot};
+use std::path::PathBuf;
+use tracing::info;
+
+#[derive(Clone, Debug, Parser)]
+pub struct ExportSnapshotArgs {
+    /// File to write the snapshot to
+    #[arg(long, env)]
+    pub output: PathBuf,
+    /// First epoch to export, include the scoring lookback of the backtests to run
+    #[arg(long, env)]
+    pub start_epoch: u16,
+    /// Last epoch to export
+    #[arg(long, env)]
+    pub end_epoch: u16,
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct ImportSnapshotArgs {
+    /// Snapshot file to load
+    #[arg(long, env)]
+    pub input: PathBuf,
+    /// Store to load the snapshot into, defaults to the configured database
+    #[arg(long, env)]
+    pub destination_url: Option<String>,
+}
+
+/// Writes the data the simulator reads for an epoch range to a single snapshot file that can be
+/// shared and attached to bug reports
+pub async fn handle_export_snapshot(
+    args: ExportSnapshotArgs,
+    store: &dyn SimulatorStore,
+) -> Result<(), CliError> {
+    if args.start_epoch > args.end_epoch {
+        return Err(CliError::InvalidEpochWindow {
+            start_epoch: args.start_epoch,
+            end_epoch: args.end_epoch,
+        });
+    }
+
+    let header = Snapshot::export(
+        store,
+        &args.output,
+        args.start_epoch.into(),
+        args.end_epoch.into(),
+    )
+    .await?;
+    info!("Snapshot checksum {}", header.checksum);
+    Ok(())
+}
+
+/// Loads the records of a snapshot file into a store, the configured database is only connected
+/// to when no destination is given
+pub async fn handle_import_snapshot(
+    args: ImportSnapshotArgs,
+    db_connection_url: &str,
+) -> Result<(), CliError> {
+    let snapshot = Snapshot::open(&args.input).await?;
+    let destination = match &args.destination_url {
+        Some(destination_url) => store::connect(destination_url).await?,
+        None => connect_store(db_connection_url).await?,
+    };
+    snapshot.import(destination.as_ref()).await?;
+    info!(
+        "Imported epochs {} to {} from {}",
+        snapshot.header.start_epoch,
+        snapshot.header.end_epoch,
+        args.input.display()
+    );
+    Ok(())
+}
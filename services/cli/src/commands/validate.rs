@@ -0,0 +1,121 @@
+use crate::{
+    backtester::{
+        BacktestDataset, load_validation_cycles, steward_cycle_length, validate_cycles,
+        validator_historical_start_offset,
+    },
+    error::CliError,
+    steward_utils::fetch_config,
+};
+use clap::Parser;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use stakenet_simulator_db::store::SimulatorStore;
+use std::path::PathBuf;
+use tracing::info;
+
+#[derive(Clone, Debug, Parser)]
+pub struct ValidateArgs {
+    /// First epoch to compare
+    #[arg(long, env)]
+    pub start_epoch: u16,
+    /// Epoch the comparison stops at
+    #[arg(long, env)]
+    pub end_epoch: u16,
+    /// Number of epochs in a steward cycle, defaults to `num_epochs_between_scoring` of the
+    /// current steward config
+    #[arg(long, env)]
+    pub steward_cycle_rate: Option<u16>,
+    /// Seed used for the random parts of the simulation. A random seed is picked when not set
+    #[arg(long, env)]
+    pub seed: Option<u64>,
+    /// File to write the JSON validation report to
+    #[arg(long, env)]
+    pub output: Option<PathBuf>,
+    #[arg(long, env)]
+    pub pretty_output: bool,
+}
+
+/// Compares the delegations of the simulated steward to the ones recorded from the on-chain
+/// steward by the tracker
+pub async fn handle_validate(
+    args: ValidateArgs,
+    store: &dyn SimulatorStore,
+    rpc_client: &RpcClient,
+) -> Result<(), CliError> {
+    if args.start_epoch >= args.end_epoch {
+        return Err(CliError::InvalidEpochWindow {
+            start_epoch: args.start_epoch,
+            end_epoch: args.end_epoch,
+        });
+    }
+
+    // The recorded steward delegations and configs are only tracked in Postgres
+    let db_connection = store
+        .postgres()
+        .ok_or(CliError::PostgresRequired("validate"))?;
+
+    // Only used for the cycles that have no recorded config
+    let current_config = fetch_config(rpc_client).await?;
+    let cycle_length = steward_cycle_length(&current_config, args.steward_cycle_rate);
+    let cycles = load_validation_cycles(
+        db_connection,
+        args.start_epoch,
+        args.end_epoch,
+        cycle_length,
+        &current_config,
+    )
+    .await?;
+    let (Some(first_cycle), Some(last_cycle)) = (cycles.first(), cycles.last()) else {
+        info!("No recorded steward delegations to compare against");
+        return Ok(());
+    };
+
+    let historical_start_offset = cycles
+        .iter()
+        .map(|cycle| validator_historical_start_offset(&cycle.config))
+        .max()
+        .unwrap_or_default();
+    let dataset = BacktestDataset::load(
+        store,
+        first_cycle.start_epoch,
+        last_cycle.end_epoch,
+        historical_start_offset,
+    )
+    .await?;
+
+    let seed = args.seed.unwrap_or_else(rand::random);
+    let report = validate_cycles(dataset.into(), &cycles, cycle_length, seed).await?;
+
+    for cycle in &report.cycles {
+        info!(
+            "Epochs {} to {}: jaccard overlap {:.4}, score rank correlation {}, stake weighted divergence {:.4}",
+            cycle.start_epoch,
+            cycle.end_epoch,
+            cycle.jaccard_overlap,
+            cycle
+                .score_rank_correlation
+                .map(|correlation| format!("{:.4}", correlation))
+                .unwrap_or_else(|| "n/a".to_string()),
+            cycle.stake_weighted_divergence
+        );
+    }
+    info!("Mean jaccard overlap: {:.4}", report.mean_jaccard_overlap);
+    if let Some(correlation) = report.mean_score_rank_correlation {
+        info!("Mean score rank correlation: {:.4}", correlation);
+    }
+    info!(
+        "Mean stake weighted divergence: {:.4}",
+        report.mean_stake_weighted_divergence
+    );
+
+    if let Some(path) = args.output {
+        let contents = if args.pretty_output {
+            serde_json::to_string_pretty(&report)?
+        } else {
+            serde_json::to_string(&report)?
+        };
+        std::fs::write(&path, contents)?;
+        info!("Validation report written to {}", path.display());
+    }
+
+    Ok(())
+}
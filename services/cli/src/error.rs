@@ -1,5 +1,8 @@
+use crate::backtester::CoverageGaps;
 use solana_client::client_error::ClientError;
+use solana_sdk::pubkey::ParsePubkeyError;
 use sqlx::Error as SqlxError;
+use stakenet_simulator_db::error::StakenetSimulatorDbError;
 use thiserror::Error;
 use tokio::task::JoinError;
 
@@ -36,4 +39,52 @@ pub enum CliError {
 
     #[error("Error joining tokio task: {0}")]
     TaskJoinError(#[from] JoinError),
+
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Unable to parse scenario file: {0}")]
+    ScenarioParseError(#[from] toml::de::Error),
+
+    #[error("Scenario {0} not found")]
+    ScenarioNotFound(String),
+
+    #[error("Scenario file contains multiple scenarios, use <file>:<name> to select one")]
+    ScenarioNameRequired,
+
+    #[error("Scenario inheritance cycle detected at {0}")]
+    ScenarioInheritanceCycle(String),
+
+    #[error("Invalid epoch window: start epoch {start_epoch} is not before end epoch {end_epoch}")]
+    InvalidEpochWindow { start_epoch: u16, end_epoch: u16 },
+
+    #[error("Serde JSON error: {0}")]
+    SerdeJsonError(#[from] serde_json::Error),
+
+    #[error("Backtest {0} is required")]
+    MissingBacktestInput(&'static str),
+
+    #[error("Epochs {start_epoch} to {end_epoch} are not covered by the backtest dataset")]
+    EpochWindowNotInDataset { start_epoch: u16, end_epoch: u16 },
+
+    #[error("Backtest run {0} not found")]
+    BacktestRunNotFound(String),
+
+    #[error("CSV error: {0}")]
+    CsvError(#[from] csv::Error),
+
+    #[error("Data coverage gaps in the simulated window: {0}")]
+    DataCoverageGaps(Box<CoverageGaps>),
+
+    #[error("Data audit found {0} issues")]
+    AuditFailed(usize),
+
+    #[error("The {0} command needs a Postgres database")]
+    PostgresRequired(&'static str),
+
+    #[error("Database error: {0}")]
+    DbError(#[from] StakenetSimulatorDbError),
+
+    #[error("Invalid pubkey: {0}")]
+    ParsePubkeyError(#[from] ParsePubkeyError),
 }
@@ -1,5 +1,7 @@
+pub mod backtester;
 pub mod commands;
 pub mod error;
 pub mod macros;
+pub mod scenario;
 pub mod steward_utils;
 mod utils;
@@ -1,15 +1,19 @@
 use crate::error::CliError;
 use clap::{Parser, Subcommand};
-use commands::backtest::*;
+use commands::{
+    audit::*, backtest::*, connect_store, copy_data::*, generate_data::*, history::*, snapshot::*,
+    validate::*,
+};
 use solana_client::nonblocking::rpc_client::RpcClient;
-use sqlx::postgres::PgPoolOptions;
-use std::sync::Arc;
+use stakenet_simulator_db::{migrations::migrate, store};
 use tracing::Level;
 use tracing_subscriber::EnvFilter;
 
+pub mod backtester;
 pub mod commands;
 pub mod error;
 pub mod macros;
+pub mod scenario;
 pub mod steward_utils;
 mod utils;
 
@@ -19,6 +23,7 @@ struct Cli {
     #[arg(short, long, env)]
     pub rpc_url: Option<String>,
 
+    /// Postgres database, or a SQLite file as `sqlite://<path>` to run without the supabase stack
     #[arg(
         long,
         env,
@@ -34,8 +39,45 @@ struct Cli {
 pub enum Commands {
     Backtest {
         #[command(flatten)]
-        args: BacktestArgs,
+        args: Box<BacktestArgs>,
     },
+    /// List and compare saved backtest runs
+    History {
+        #[command(flatten)]
+        args: HistoryArgs,
+    },
+    /// Compare the simulated delegations with the recorded on-chain steward delegations
+    Validate {
+        #[command(flatten)]
+        args: ValidateArgs,
+    },
+    /// Check the data of an epoch range for gaps and inconsistencies
+    Audit {
+        #[command(flatten)]
+        args: AuditArgs,
+    },
+    /// Copy the data the simulator reads for an epoch range to another store, e.g. a SQLite file
+    CopyData {
+        #[command(flatten)]
+        args: CopyDataArgs,
+    },
+    /// Generate synthetic data for an epoch range, with validators changing behavior on the way
+    GenerateData {
+        #[command(flatten)]
+        args: GenerateDataArgs,
+    },
+    /// Write the data of an epoch range to a compressed snapshot file
+    ExportSnapshot {
+        #[command(flatten)]
+        args: ExportSnapshotArgs,
+    },
+    /// Load a snapshot file into a store
+    ImportSnapshot {
+        #[command(flatten)]
+        args: ImportSnapshotArgs,
+    },
+    /// Apply the pending database migrations
+    Migrate,
 }
 
 #[tokio::main]
@@ -56,33 +98,53 @@ async fn main() -> Result<(), CliError> {
         .init();
     let cli: Cli = Cli::parse();
 
-    let db_conn_pool = Arc::new(
-        PgPoolOptions::new()
-            .max_connections(10)
-            .connect(&cli.db_connection_url)
-            .await
-            .unwrap(),
-    );
-
     match cli.command {
         Commands::Backtest { args } => {
             let rpc_url = cli.rpc_url.as_ref().ok_or(CliError::InvalidRPCUrl)?;
             let rpc_client = RpcClient::new(rpc_url.to_string());
 
-            // TODO: Should we pull the current epoch from RPC or make it be a CLI argument?
-            let current_epoch: u16 = 700;
             // TODO: Determine how this should be passed. The number of epochs to look back
             let look_back_period = 100;
 
-            handle_backtest(
-                args,
-                &db_conn_pool,
-                &rpc_client,
-                current_epoch,
-                look_back_period,
-            )
-            .await?;
+            handle_backtest(*args, &cli.db_connection_url, &rpc_client, look_back_period).await?;
             Ok(())
         }
+        Commands::History { args } => {
+            let store = connect_store(&cli.db_connection_url).await?;
+            let db_connection = store
+                .postgres()
+                .ok_or(CliError::PostgresRequired("history"))?;
+            handle_history(args, db_connection).await
+        }
+        Commands::Validate { args } => {
+            let rpc_url = cli.rpc_url.as_ref().ok_or(CliError::InvalidRPCUrl)?;
+            let rpc_client = RpcClient::new(rpc_url.to_string());
+            let store = connect_store(&cli.db_connection_url).await?;
+            handle_validate(args, store.as_ref(), &rpc_client).await
+        }
+        Commands::Audit { args } => {
+            let store = connect_store(&cli.db_connection_url).await?;
+            handle_audit(args, store.as_ref()).await
+        }
+        Commands::CopyData { args } => {
+            let store = connect_store(&cli.db_connection_url).await?;
+            handle_copy_data(args, store.as_ref()).await
+        }
+        Commands::GenerateData { args } => handle_generate_data(args).await,
+        Commands::ExportSnapshot { args } => {
+            let store = connect_store(&cli.db_connection_url).await?;
+            handle_export_snapshot(args, store.as_ref()).await
+        }
+        Commands::ImportSnapshot { args } => {
+            handle_import_snapshot(args, &cli.db_connection_url).await
+        }
+        Commands::Migrate => {
+            // Connects without the schema check, the migrations bring the schema up to date
+            let store = store::connect(&cli.db_connection_url).await?;
+            let db_connection = store
+                .postgres()
+                .ok_or(CliError::PostgresRequired("migrate"))?;
+            Ok(migrate(db_connection).await?)
+        }
     }
 }
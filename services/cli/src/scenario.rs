@@ -0,0 +1,251 @@
+use crate::{
+    backtester::{
+        CoveragePolicy, DelegationStrategyOptions, DirectedStakeTarget, ScoringModelOptions,
+        ValidatorListOptions,
+    },
+    error::CliError,
+    steward_utils::StewardParameterOverrides,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+/// A named backtest scenario. Scenario files are TOML documents where every top level table is a
+/// scenario, e.g.
+///
+/// ```toml
+/// [base]
+/// start_epoch = 740
+/// end_epoch = 840
+/// seed = 42
+///
+/// [base.parameters]
+/// mev_commission_bps_threshold = 1000
+///
+/// [aggressive]
+/// extends = "base"
+///
+/// [aggressive.parameters]
+/// instant_unstake_cap_bps = 1000
+/// ```
+///
+/// A scenario that `extends` another one inherits every field it doesn't set itself.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Scenario {
+    /// Name of the scenario in the same file to inherit from
+    pub extends: Option<String>,
+    pub description: Option<String>,
+    /// First epoch of the simulation
+    pub start_epoch: Option<u16>,
+    /// Epoch the simulation stops at (exclusive)
+    pub end_epoch: Option<u16>,
+    /// Seed for the random selection of validators receiving manual stake deposits/withdraws
+    pub seed: Option<u64>,
+    pub number_of_validator_delegations: Option<usize>,
+    /// Size of the simulated pool at the start of the simulation, in lamports
+    pub initial_pool_lamports: Option<u64>,
+    /// Fee paid for every stake activation and deactivation, in lamports
+    pub transaction_fee_lamports: Option<u64>,
+    pub steward_cycle_rate: Option<u16>,
+    /// Step through the phases of the steward program every epoch
+    pub steward_phases: Option<bool>,
+    /// Unstake the stake deposited to the pool's validators
+    pub stake_deposit_unstake: Option<bool>,
+    /// What to do when validators have stake but no recorded rewards in the window
+    pub coverage_policy: Option<CoveragePolicy>,
+    pub parameters: StewardParameterOverrides,
+    pub strategy: DelegationStrategyOptions,
+    pub scoring: ScoringModelOptions,
+    pub validators: ValidatorListOptions,
+    /// Stake delegated to validators regardless of their score
+    pub directed_stake: Vec<DirectedStakeTarget>,
+    pub output: OutputOptions,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputOptions {
+    /// File the JSON backtest report is written to
+    pub path: Option<PathBuf>,
+    /// Pretty print the JSON report
+    pub pretty: Option<bool>,
+}
+
+impl Scenario {
+    /// Loads a scenario from a `<file>:<name>` reference. The name can be omitted when the file
+    /// contains a single scenario.
+    pub fn load(reference: &str) -> Result<Self, CliError> {
+        let (path, name) = Self::split_reference(reference);
+        let contents = std::fs::read_to_string(Path::new(path))?;
+        Self::from_toml(&contents, name)
+    }
+
+    /// Splits a `<file>:<name>` reference. The suffix after the last ':' is only treated as a
+    /// scenario name when the whole reference isn't an existing file and the suffix can't be part
+    /// of a path, so paths that contain ':' load as is.
+    fn split_reference(reference: &str) -> (&str, Option<&str>) {
+        if Path::new(reference).is_file() {
+            return (reference, None);
+        }
+        match reference.rsplit_once(':') {
+            Some((path, name)) if !name.is_empty() && !name.contains(['/', '\\']) => {
+                (path, Some(name))
+            }
+            _ => (reference, None),
+        }
+    }
+
+    /// Parses every scenario in `contents` and returns the requested one with its inheritance
+    /// chain resolved.
+    pub fn from_toml(contents: &str, name: Option<&str>) -> Result<Self, CliError> {
+        let scenarios: HashMap<String, Scenario> = toml::from_str(contents)?;
+        let name = match name {
+            Some(name) => name.to_string(),
+            None if scenarios.len() == 1 => scenarios.keys().next().cloned().unwrap_or_default(),
+            None => return Err(CliError::ScenarioNameRequired),
+        };
+        Self::resolve(&scenarios, &name)
+    }
+
+    fn resolve(scenarios: &HashMap<String, Scenario>, name: &str) -> Result<Self, CliError> {
+        let mut visited = HashSet::new();
+        let mut resolved = scenarios
+            .get(name)
+            .cloned()
+            .ok_or_else(|| CliError::ScenarioNotFound(name.to_string()))?;
+        visited.insert(name.to_string());
+
+        while let Some(parent_name) = resolved.extends.take() {
+            if !visited.insert(parent_name.clone()) {
+                return Err(CliError::ScenarioInheritanceCycle(parent_name));
+            }
+            let parent = scenarios
+                .get(&parent_name)
+                .ok_or_else(|| CliError::ScenarioNotFound(parent_name.clone()))?;
+            resolved.inherit_from(parent);
+        }
+
+        Ok(resolved)
+    }
+
+    /// Sets every field that is not set on this scenario to the value of `parent`, including the
+    /// parent's own `extends` so the chain can be followed.
+    fn inherit_from(&mut self, parent: &Scenario) {
+        self.extends = parent.extends.clone();
+        self.description = self.description.take().or(parent.description.clone());
+        self.start_epoch = self.start_epoch.or(parent.start_epoch);
+        self.end_epoch = self.end_epoch.or(parent.end_epoch);
+        self.seed = self.seed.or(parent.seed);
+        self.number_of_validator_delegations = self
+            .number_of_validator_delegations
+            .or(parent.number_of_validator_delegations);
+        self.initial_pool_lamports = self.initial_pool_lamports.or(parent.initial_pool_lamports);
+        self.transaction_fee_lamports = self
+            .transaction_fee_lamports
+            .or(parent.transaction_fee_lamports);
+        self.steward_cycle_rate = self.steward_cycle_rate.or(parent.steward_cycle_rate);
+        self.steward_phases = self.steward_phases.or(parent.steward_phases);
+        self.stake_deposit_unstake = self.stake_deposit_unstake.or(parent.stake_deposit_unstake);
+        self.coverage_policy = self.coverage_policy.or(parent.coverage_policy);
+        self.parameters.fill_unset_from(&parent.parameters);
+        self.strategy.fill_unset_from(&parent.strategy);
+        self.scoring.fill_unset_from(&parent.scoring);
+        self.validators.fill_unset_from(&parent.validators);
+        if self.directed_stake.is_empty() {
+            self.directed_stake = parent.directed_stake.clone();
+        }
+        self.output.path = self.output.path.take().or(parent.output.path.clone());
+        self.output.pretty = self.output.pretty.or(parent.output.pretty);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtester::DirectedStakeAmount;
+
+    const SCENARIOS: &str = r#"
+        [base]
+        start_epoch = 740
+        end_epoch = 840
+        seed = 42
+        directed_stake = [{ vote_account = "Vote111111111111111111111111111111111111111", bps = 500 }]
+
+        [base.parameters]
+        mev_commission_bps_threshold = 1000
+        instant_unstake_cap_bps = 500
+
+        [base.validators]
+        deny_validators = ["Vote111111111111111111111111111111111111111"]
+
+        [aggressive]
+        extends = "base"
+        end_epoch = 800
+
+        [aggressive.parameters]
+        instant_unstake_cap_bps = 1000
+
+        [loop_a]
+        extends = "loop_b"
+
+        [loop_b]
+        extends = "loop_a"
+    "#;
+
+    #[test]
+    fn test_scenario_inheritance() {
+        let scenario = Scenario::from_toml(SCENARIOS, Some("aggressive")).unwrap();
+        assert_eq!(scenario.start_epoch, Some(740));
+        assert_eq!(scenario.end_epoch, Some(800));
+        assert_eq!(scenario.seed, Some(42));
+        assert_eq!(scenario.parameters.mev_commission_bps_threshold, Some(1000));
+        assert_eq!(scenario.parameters.instant_unstake_cap_bps, Some(1000));
+        assert_eq!(scenario.validators.deny_validators.len(), 1);
+        assert_eq!(
+            scenario.directed_stake[0].amount,
+            DirectedStakeAmount::Bps(500)
+        );
+        assert!(scenario.validators.build().use_steward_blacklist);
+        assert!(scenario.extends.is_none());
+
+        assert!(matches!(
+            Scenario::from_toml(SCENARIOS, Some("loop_a")),
+            Err(CliError::ScenarioInheritanceCycle(_))
+        ));
+        assert!(matches!(
+            Scenario::from_toml(SCENARIOS, Some("missing")),
+            Err(CliError::ScenarioNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_scenario_reference() {
+        assert_eq!(
+            Scenario::split_reference("scenarios.toml:aggressive"),
+            ("scenarios.toml", Some("aggressive"))
+        );
+        assert_eq!(
+            Scenario::split_reference("scenarios.toml"),
+            ("scenarios.toml", None)
+        );
+        assert_eq!(
+            Scenario::split_reference("/data/run:1/scenarios.toml"),
+            ("/data/run:1/scenarios.toml", None)
+        );
+        assert_eq!(
+            Scenario::split_reference("C:\\scenarios.toml:base"),
+            ("C:\\scenarios.toml", Some("base"))
+        );
+
+        let dir = std::env::temp_dir().join(format!("scenario-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("run:base.toml");
+        std::fs::write(&path, "[only]\nseed = 7\n").unwrap();
+        let scenario = Scenario::load(path.to_str().unwrap()).unwrap();
+        assert_eq!(scenario.seed, Some(7));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,15 +1,178 @@
 use anchor_lang::AccountDeserialize;
+use clap::Args;
 use jito_steward::Config;
+use serde::{Deserialize, Serialize};
 use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_sdk::pubkey;
-use solana_sdk::pubkey::Pubkey;
+pub use stakenet_simulator_db::steward_config_snapshot::STEWARD_CONFIG_PUBKEY;
 
-use crate::error::CliError;
-
-pub const STEWARD_CONFIG_PUBKEY: Pubkey = pubkey!("jitoVjT9jRUyeXHzvCwzPgHj7yWNRhLcUoXtes4wtjv");
+use crate::{error::CliError, modify_config_parameter_from_args};
 
 pub async fn fetch_config(rpc_client: &RpcClient) -> Result<Config, CliError> {
     let account = rpc_client.get_account(&STEWARD_CONFIG_PUBKEY).await?;
     let mut data: &[u8] = &account.data;
     Config::try_deserialize(&mut data).map_err(|_| CliError::AnchorDeserializeError)
 }
+
+/// Optional overrides for the steward config parameters. Every field that is set replaces the
+/// value of the loaded steward config, both when passed through the CLI and in scenario files.
+#[derive(Args, Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StewardParameterOverrides {
+    #[arg(long, env)]
+    pub mev_commission_range: Option<u16>,
+    #[arg(long, env)]
+    pub epoch_credits_range: Option<u16>,
+    #[arg(long, env)]
+    pub commission_range: Option<u16>,
+    #[arg(long, env)]
+    pub scoring_delinquency_threshold_ratio: Option<f64>,
+    #[arg(long, env)]
+    pub instant_unstake_delinquency_threshold_ratio: Option<f64>,
+    #[arg(long, env)]
+    pub mev_commission_bps_threshold: Option<u16>,
+    #[arg(long, env)]
+    pub commission_threshold: Option<u8>,
+    #[arg(long, env)]
+    pub historical_commission_threshold: Option<u8>,
+    #[arg(long, env)]
+    pub priority_fee_lookback_epochs: Option<u8>,
+    #[arg(long, env)]
+    pub priority_fee_lookback_offset: Option<u8>,
+    #[arg(long, env)]
+    pub priority_fee_max_commission_bps: Option<u16>,
+    #[arg(long, env)]
+    pub priority_fee_error_margin_bps: Option<u16>,
+    #[arg(long, env)]
+    pub num_delegation_validators: Option<u32>,
+    #[arg(long, env)]
+    pub scoring_unstake_cap_bps: Option<u32>,
+    #[arg(long, env)]
+    pub instant_unstake_cap_bps: Option<u32>,
+    #[arg(long, env)]
+    pub stake_deposit_unstake_cap_bps: Option<u32>,
+    #[arg(long, env)]
+    pub instant_unstake_epoch_progress: Option<f64>,
+    #[arg(long, env)]
+    pub compute_score_slot_range: Option<u64>,
+    #[arg(long, env)]
+    pub instant_unstake_inputs_epoch_progress: Option<f64>,
+    #[arg(long, env)]
+    pub num_epochs_between_scoring: Option<u64>,
+    #[arg(long, env)]
+    pub minimum_stake_lamports: Option<u64>,
+    #[arg(long, env)]
+    pub minimum_voting_epochs: Option<u64>,
+    #[arg(long, env)]
+    pub priority_fee_scoring_start_epoch: Option<u16>,
+}
+
+/// Fills every unset field of `$target` with the value from `$fallback`
+macro_rules! fill_unset_fields {
+    ($target:expr, $fallback:expr, $($field:ident),+ $(,)?) => {
+        $(
+            if $target.$field.is_none() {
+                $target.$field = $fallback.$field;
+            }
+        )+
+    };
+}
+
+impl StewardParameterOverrides {
+    pub fn update_steward_config(&self, config: &mut Config) {
+        modify_config_parameter_from_args!(self, config, mev_commission_range);
+        modify_config_parameter_from_args!(self, config, epoch_credits_range);
+        modify_config_parameter_from_args!(self, config, commission_range);
+        modify_config_parameter_from_args!(self, config, scoring_delinquency_threshold_ratio);
+        modify_config_parameter_from_args!(
+            self,
+            config,
+            instant_unstake_delinquency_threshold_ratio
+        );
+        modify_config_parameter_from_args!(self, config, mev_commission_bps_threshold);
+        modify_config_parameter_from_args!(self, config, commission_threshold);
+        modify_config_parameter_from_args!(self, config, historical_commission_threshold);
+        modify_config_parameter_from_args!(self, config, priority_fee_lookback_epochs);
+        modify_config_parameter_from_args!(self, config, priority_fee_lookback_offset);
+        modify_config_parameter_from_args!(self, config, priority_fee_max_commission_bps);
+        modify_config_parameter_from_args!(self, config, priority_fee_error_margin_bps);
+        modify_config_parameter_from_args!(self, config, num_delegation_validators);
+        modify_config_parameter_from_args!(self, config, scoring_unstake_cap_bps);
+        modify_config_parameter_from_args!(self, config, instant_unstake_cap_bps);
+        modify_config_parameter_from_args!(self, config, stake_deposit_unstake_cap_bps);
+        modify_config_parameter_from_args!(self, config, compute_score_slot_range);
+        modify_config_parameter_from_args!(self, config, instant_unstake_epoch_progress);
+        modify_config_parameter_from_args!(self, config, instant_unstake_inputs_epoch_progress);
+        modify_config_parameter_from_args!(self, config, num_epochs_between_scoring);
+        modify_config_parameter_from_args!(self, config, minimum_stake_lamports);
+        modify_config_parameter_from_args!(self, config, minimum_voting_epochs);
+        modify_config_parameter_from_args!(self, config, priority_fee_scoring_start_epoch);
+    }
+
+    /// Sets every parameter that is not overridden in `self` to the value in `fallback`
+    pub fn fill_unset_from(&mut self, fallback: &Self) {
+        fill_unset_fields!(
+            self,
+            fallback,
+            mev_commission_range,
+            epoch_credits_range,
+            commission_range,
+            scoring_delinquency_threshold_ratio,
+            instant_unstake_delinquency_threshold_ratio,
+            mev_commission_bps_threshold,
+            commission_threshold,
+            historical_commission_threshold,
+            priority_fee_lookback_epochs,
+            priority_fee_lookback_offset,
+            priority_fee_max_commission_bps,
+            priority_fee_error_margin_bps,
+            num_delegation_validators,
+            scoring_unstake_cap_bps,
+            instant_unstake_cap_bps,
+            stake_deposit_unstake_cap_bps,
+            instant_unstake_epoch_progress,
+            compute_score_slot_range,
+            instant_unstake_inputs_epoch_progress,
+            num_epochs_between_scoring,
+            minimum_stake_lamports,
+            minimum_voting_epochs,
+            priority_fee_scoring_start_epoch,
+        );
+    }
+
+    /// Captures every parameter of the given steward config, used to record the effective
+    /// parameters of a backtest.
+    pub fn from_config(config: &Config) -> Self {
+        let parameters = &config.parameters;
+        Self {
+            mev_commission_range: Some(parameters.mev_commission_range),
+            epoch_credits_range: Some(parameters.epoch_credits_range),
+            commission_range: Some(parameters.commission_range),
+            scoring_delinquency_threshold_ratio: Some(
+                parameters.scoring_delinquency_threshold_ratio,
+            ),
+            instant_unstake_delinquency_threshold_ratio: Some(
+                parameters.instant_unstake_delinquency_threshold_ratio,
+            ),
+            mev_commission_bps_threshold: Some(parameters.mev_commission_bps_threshold),
+            commission_threshold: Some(parameters.commission_threshold),
+            historical_commission_threshold: Some(parameters.historical_commission_threshold),
+            priority_fee_lookback_epochs: Some(parameters.priority_fee_lookback_epochs),
+            priority_fee_lookback_offset: Some(parameters.priority_fee_lookback_offset),
+            priority_fee_max_commission_bps: Some(parameters.priority_fee_max_commission_bps),
+            priority_fee_error_margin_bps: Some(parameters.priority_fee_error_margin_bps),
+            num_delegation_validators: Some(parameters.num_delegation_validators),
+            scoring_unstake_cap_bps: Some(parameters.scoring_unstake_cap_bps),
+            instant_unstake_cap_bps: Some(parameters.instant_unstake_cap_bps),
+            stake_deposit_unstake_cap_bps: Some(parameters.stake_deposit_unstake_cap_bps),
+            instant_unstake_epoch_progress: Some(parameters.instant_unstake_epoch_progress),
+            compute_score_slot_range: Some(parameters.compute_score_slot_range),
+            instant_unstake_inputs_epoch_progress: Some(
+                parameters.instant_unstake_inputs_epoch_progress,
+            ),
+            num_epochs_between_scoring: Some(parameters.num_epochs_between_scoring),
+            minimum_stake_lamports: Some(parameters.minimum_stake_lamports),
+            minimum_voting_epochs: Some(parameters.minimum_voting_epochs),
+            priority_fee_scoring_start_epoch: Some(parameters.priority_fee_scoring_start_epoch),
+        }
+    }
+}
@@ -1,9 +1,9 @@
-use crate::{commands::DAYS_PER_YEAR, error::CliError, utils::RebalancingCycle};
-use num_traits::cast::ToPrimitive;
-use sqlx::{Pool, Postgres, types::BigDecimal};
-use stakenet_simulator_db::{
-    active_stake_jito_sol::ActiveStakeJitoSol, inactive_stake_jito_sol::InactiveStakeJitoSol,
+use crate::{
+    backtester::BacktestDataset, commands::DAYS_PER_YEAR, error::CliError, utils::RebalancingCycle,
 };
+use num_traits::cast::ToPrimitive;
+use sqlx::types::BigDecimal;
+use stakenet_simulator_db::domain::Epoch;
 
 pub fn calculate_apy(r: f64, t: f64, n: f64) -> f64 {
     // APY = (1 + r)^(n/t) - 1
@@ -69,8 +69,8 @@ fn calculate_stake_utilization(
     Ok(utilization_rate)
 }
 
-pub async fn calculate_stake_utilization_rate(
-    db_connection: &Pool<Postgres>,
+pub fn calculate_stake_utilization_rate(
+    dataset: &BacktestDataset,
     lookback_period: u16,
     current_epoch: u16,
 ) -> Result<f64, CliError> {
@@ -78,30 +78,30 @@ pub async fn calculate_stake_utilization_rate(
         return Err(CliError::LookBackPeriodTooBig);
     }
 
-    let (active_stake_data, inactive_stake_data) = futures::join!(
-        ActiveStakeJitoSol::fetch_balance_for_epoch_range(
-            db_connection,
-            current_epoch as u64,
-            lookback_period as u64,
-        ),
-        InactiveStakeJitoSol::fetch_balance_for_epoch_range(
-            db_connection,
-            current_epoch as u64,
-            lookback_period as u64,
-        )
-    );
-
-    let active_stake_data = active_stake_data?;
-    let inactive_stake_data = inactive_stake_data?;
-
-    if active_stake_data.count != inactive_stake_data.count {
+    let epochs = Epoch::from(current_epoch - lookback_period)..=Epoch::from(current_epoch);
+    let (active_balance, active_count) = dataset
+        .active_stake
+        .iter()
+        .filter(|stake| epochs.contains(&stake.epoch))
+        .fold((BigDecimal::from(0), 0i64), |(balance, count), stake| {
+            (balance + &stake.balance, count + 1)
+        });
+    let (inactive_balance, inactive_count) = dataset
+        .inactive_stake
+        .iter()
+        .filter(|stake| epochs.contains(&stake.epoch))
+        .fold((BigDecimal::from(0), 0i64), |(balance, count), stake| {
+            (balance + &stake.balance, count + 1)
+        });
+
+    if active_count != inactive_count {
         return Err(CliError::RecordCountMismatch {
-            active_count: active_stake_data.count,
-            inactive_count: inactive_stake_data.count,
+            active_count,
+            inactive_count,
         });
     }
 
-    calculate_stake_utilization(&active_stake_data.balance, &inactive_stake_data.balance)
+    calculate_stake_utilization(&active_balance, &inactive_balance)
 }
 
 #[cfg(test)]
@@ -1,33 +1,128 @@
-use crate::{error::CliError, utils::ValidatorStakeState};
-use futures::future::try_join_all;
-use jito_steward::{
-    Config,
-    constants::TVC_ACTIVATION_EPOCH,
-    score::{instant_unstake_validator, validator_score},
+use crate::{
+    backtester::{
+        BacktestDataset, CoverageGaps, CoveragePolicy, CoverageReport, CycleTurnover,
+        DecentralizationMetrics, DelegatedValidator, DelegationStrategy, DirectedStakeTarget,
+        EpochReturns, EpochSlots, ImputedReward, OnChainScoringModel, PhaseTransition,
+        ScoringEpochSummary, ScoringModel, StewardPhase, StewardStrategy, ValidatorFilter,
+        ValidatorScoreRecord, cluster_history_at_slot, directed_stake_targets, epoch_progress_slot,
+        median_epoch_return, next_cycle_start, validator_history_at_slot,
+    },
+    error::CliError,
+    utils::ValidatorStakeState,
 };
+use futures::future::try_join_all;
+use jito_steward::{Config, constants::TVC_ACTIVATION_EPOCH, score::instant_unstake_validator};
 use num_traits::ToPrimitive;
 use rand::prelude::IndexedRandom;
-use rand::rng;
+use rand::{SeedableRng, rngs::StdRng};
+use serde::{Deserialize, Serialize};
 use solana_sdk::native_token::LAMPORTS_PER_SOL;
-use sqlx::{Pool, Postgres};
 use stakenet_simulator_db::{
-    active_stake_jito_sol::ActiveStakeJitoSol, cluster_history::ClusterHistory,
-    cluster_history_entry::ClusterHistoryEntry, epoch_rewards::EpochRewards,
+    active_stake_jito_sol::ActiveStakeJitoSol, domain::VotePubkey,
     validator_history::ValidatorHistory, validator_history_entry::ValidatorHistoryEntry,
     withdraw_and_deposit_sol::WithdrawAndDepositSol,
     withdraw_and_deposits_stake::WithdrawsAndDepositStakes,
 };
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use validator_history::ClusterHistory as JitoClusterHistory;
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct RebalancingCycle {
+    pub start_epoch: u16,
+    /// Epoch the cycle ended at (exclusive)
+    pub end_epoch: u16,
     pub starting_total_lamports: u64,
     pub ending_total_lamports: u64,
 }
 
+/// Outcome of the simulation for a single validator
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ValidatorResult {
+    pub vote_account: VotePubkey,
+    /// Number of steward cycles the validator was selected for delegation
+    pub cycles_selected: u32,
+    /// Score of the last cycle the validator was selected in
+    pub last_score: f64,
+    /// Stake of the pool on the validator at the end of the simulation
+    pub final_stake_lamports: u64,
+}
+
+/// State of the simulated pool at the end of an epoch, recorded to compare simulations
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct EpochTrace {
+    pub epoch: u16,
+    pub is_rebalancing_epoch: bool,
+    pub total_lamports: u64,
+    /// Stake of the pool on every validator it is delegated to
+    pub stake_states: BTreeMap<VotePubkey, ValidatorStakeState>,
+    /// Stake moved so far in the current cycle
+    pub turnover: Option<CycleTurnover>,
+    /// Stake moved during the epoch, in the order the simulator moved it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<EpochEvent>,
+}
+
+/// Stake moved on a validator by the simulated pool
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EpochEvent {
+    /// Stake deposited to the pool, through a stake account or SOL, landing on the validator
+    Deposit {
+        vote_account: VotePubkey,
+        lamports: u64,
+    },
+    /// Stake withdrawn from the pool, through a stake account or SOL, off the validator
+    Withdraw {
+        vote_account: VotePubkey,
+        lamports: u64,
+    },
+    /// Stake deactivated from a validator flagged by the instant unstake checks
+    InstantUnstake {
+        vote_account: VotePubkey,
+        lamports: u64,
+    },
+    /// Stake deactivated from a validator that left the delegated set
+    ScoringUnstake {
+        vote_account: VotePubkey,
+        lamports: u64,
+    },
+    /// Stake deposit unstaked from a validator
+    StakeDepositUnstake {
+        vote_account: VotePubkey,
+        lamports: u64,
+    },
+    /// Stake deactivated to move the directed validators to their targets
+    DirectedUnstake {
+        vote_account: VotePubkey,
+        lamports: u64,
+    },
+    /// Stake activated on a validator when rebalancing
+    Activate {
+        vote_account: VotePubkey,
+        lamports: u64,
+    },
+}
+
+impl EpochEvent {
+    /// Deposit or withdraw that moved the active stake of a validator from `old_active` to
+    /// `new_active`
+    fn stake_change(vote_account: VotePubkey, old_active: u64, new_active: u64) -> Option<Self> {
+        match new_active.cmp(&old_active) {
+            std::cmp::Ordering::Greater => Some(Self::Deposit {
+                vote_account,
+                lamports: new_active - old_active,
+            }),
+            std::cmp::Ordering::Less => Some(Self::Withdraw {
+                vote_account,
+                lamports: old_active - new_active,
+            }),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+}
+
 pub struct EpochWithdrawDepositStakeData {
     pub withdraw_stake: f64,
     pub deposit_stake: f64,
@@ -40,40 +135,79 @@ pub struct EpochWithdrawDepositSOLData {
     pub withdraw_sol: f64,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct ValidatorWithScore {
-    pub vote_account: String,
+    pub vote_account: VotePubkey,
     pub score: f64,
+    /// Stake of the whole network on the validator in its latest entry
+    pub activated_stake_lamports: u64,
+    pub is_superminority: bool,
 }
 
 pub struct RebalancingSimulator {
     pub steward_config: Config,
     pub simulation_start_epoch: u16,
     pub simulation_end_epoch: u16,
+    /// Number of epochs in a steward cycle, cycles start at multiples of it
     pub steward_cycle_rate: u16,
     pub number_of_validator_delegations: usize,
     pub instant_unstake_cap_bps: u32,
     pub scoring_unstake_cap_bps: u32,
 
-    pub validator_stake_states: HashMap<String, ValidatorStakeState>,
-    pub validator_scores: HashMap<String, f64>,
+    pub validator_stake_states: HashMap<VotePubkey, ValidatorStakeState>,
+    pub validator_scores: HashMap<VotePubkey, f64>,
+    pub current_cycle_start: u16,
     pub current_cycle_end: u16,
     pub total_lamports_staked: u64,
     pub rebalancing_cycles: Vec<RebalancingCycle>,
     pub top_validators: Vec<ValidatorWithScore>,
+    pub validator_results: HashMap<VotePubkey, ValidatorResult>,
+    pub delegation_strategy: Arc<dyn DelegationStrategy>,
+    pub scoring_model: Arc<dyn ScoringModel>,
+    pub validator_filter: ValidatorFilter,
+    /// Stake targets honored before the steward distributes the rest of the pool
+    pub directed_stake: Vec<DirectedStakeTarget>,
+    pub directed_stake_states: HashMap<VotePubkey, ValidatorStakeState>,
+    pub directed_returns: EpochReturns,
+    pub steward_returns: EpochReturns,
+    pub decentralization: Vec<DecentralizationMetrics>,
+    /// Stake moved by the steward in every cycle
+    pub turnover: Vec<CycleTurnover>,
+    /// Unstake the stake deposited to the pool's validators, up to `stake_deposit_unstake_cap_bps`
+    /// of the pool per cycle
+    pub stake_deposit_unstake: bool,
+    /// Rewards missed on stake while it was activating
+    pub forgone_rewards_lamports: u64,
+    pub scoring_summaries: Vec<ScoringEpochSummary>,
+    /// Keep the score breakdown of every validator in every scoring epoch
+    pub record_score_breakdowns: bool,
+    pub score_breakdowns: Vec<ValidatorScoreRecord>,
+    /// Step through the phases of the steward program every epoch, with cycles of
+    /// `num_epochs_between_scoring` epochs
+    pub steward_phases: bool,
+    pub phase_transitions: Vec<PhaseTransition>,
+    pub coverage_policy: CoveragePolicy,
+    /// Rewards coverage of the window and the rewards imputed for its gaps
+    pub coverage: CoverageReport,
+    /// Keep the state of the pool at the end of every epoch in `epoch_traces`
+    pub record_epoch_traces: bool,
+    pub epoch_traces: Vec<EpochTrace>,
+    /// Stake moved so far in the current epoch
+    pub epoch_events: Vec<EpochEvent>,
 
     pub pending_deactivation: u64,
-    pub histories: Vec<ValidatorHistory>,
-    pub jito_cluster_history: Arc<JitoClusterHistory>,
-    pub entries_by_validator: Arc<HashMap<String, Vec<ValidatorHistoryEntry>>>,
+    pub dataset: Arc<BacktestDataset>,
     pub stake_epoch_map: HashMap<u64, Vec<EpochWithdrawDepositStakeData>>,
     pub sol_epoch_map: HashMap<u64, EpochWithdrawDepositSOLData>,
+    /// Source of randomness for the manual stake deposits/withdraws, seeded so that runs can be
+    /// reproduced
+    pub rng: StdRng,
 }
 
 impl RebalancingSimulator {
-    /// This function is responsible for querying all the data required for the simulation cycle.
-    pub async fn new(
-        db_connection: &Pool<Postgres>,
+    /// This function is responsible for preparing the data of the dataset for the simulation cycle.
+    pub fn new(
+        dataset: Arc<BacktestDataset>,
         steward_config: Config,
         simulation_start_epoch: u16,
         simulation_end_epoch: u16,
@@ -81,69 +215,47 @@ impl RebalancingSimulator {
         number_of_validator_delegations: usize,
         instant_unstake_cap_bps: u32,
         scoring_unstake_cap_bps: u32,
-        validator_historical_start_offset: u16,
+        seed: u64,
+        initial_pool_lamports: Option<u64>,
+        coverage_policy: CoveragePolicy,
     ) -> Result<Self, CliError> {
         info!("Initializing rebalancing simulator...");
+        // A cycle lasts at least one epoch, like `steward_cycle_length`
+        let steward_cycle_rate = steward_cycle_rate.max(1);
+
+        // Gaps in the data would silently drag the APY down, so they are checked up front
+        let coverage_gaps =
+            CoverageGaps::compute(&dataset, simulation_start_epoch, simulation_end_epoch);
+        if coverage_gaps.is_blocking(coverage_policy) {
+            return Err(CliError::DataCoverageGaps(Box::new(coverage_gaps)));
+        }
+        if !coverage_gaps.missing_rewards.is_empty() {
+            warn!(
+                "{} validator epochs have no rewards, imputing the median return of the cluster",
+                coverage_gaps.missing_rewards.len()
+            );
+        }
 
-        let histories = ValidatorHistory::fetch_all(db_connection).await?;
-        let cluster_history = ClusterHistory::fetch(db_connection).await?;
-        let cluster_history_entries = ClusterHistoryEntry::fetch_all(db_connection).await?;
-        let jito_cluster_history =
-            Arc::new(cluster_history.convert_to_jito_cluster_history(cluster_history_entries));
-
-        info!("Fetching all validator history entries...");
-        let all_entries = ValidatorHistoryEntry::fetch_all_records_between_epochs(
-            db_connection,
-            simulation_start_epoch
-                .saturating_sub(validator_historical_start_offset)
-                .into(),
-            simulation_end_epoch.into(),
-        )
-        .await?;
-
-        let withdraw_and_deposit_sol = WithdrawAndDepositSol::get_records_for_epoch_range(
-            db_connection,
-            simulation_start_epoch.into(),
-            simulation_end_epoch.into(),
-        )
-        .await?;
-
-        let withdraws_and_deposits_stakes = WithdrawsAndDepositStakes::get_records_for_epoch_range(
-            db_connection,
-            simulation_start_epoch.into(),
-            simulation_end_epoch.into(),
-        )
-        .await?;
-
-        let active_stake = ActiveStakeJitoSol::get_active_stakes_for_epoch_range(
-            db_connection,
-            simulation_start_epoch.into(),
-            simulation_end_epoch.into(),
-        )
-        .await?;
-
-        let manual_withdraw_deposit_stake_epoch_map =
-            Self::build_stake_epoch_map(withdraws_and_deposits_stakes, &active_stake);
-        let manual_withdraw_deposit_sol_epoch_map =
-            Self::build_sol_epoch_map(withdraw_and_deposit_sol, &active_stake);
-
-        let entries_by_validator = Self::build_entries_by_validator(all_entries);
-
-        info!(
-            "Grouped {} validators' history entries",
-            entries_by_validator.len()
+        let manual_withdraw_deposit_stake_epoch_map = Self::build_stake_epoch_map(
+            &dataset.withdraws_and_deposit_stakes,
+            &dataset.active_stake,
         );
-
-        // start with one sol per validator
-        let total_lamports_staked = LAMPORTS_PER_SOL
-            .checked_mul(number_of_validator_delegations as u64)
-            .ok_or(CliError::ArithmeticError)?;
+        let manual_withdraw_deposit_sol_epoch_map =
+            Self::build_sol_epoch_map(&dataset.withdraw_and_deposit_sol, &dataset.active_stake);
+
+        // start with one sol per validator unless an initial pool size is given
+        let total_lamports_staked = match initial_pool_lamports {
+            Some(lamports) => lamports,
+            None => LAMPORTS_PER_SOL
+                .checked_mul(number_of_validator_delegations as u64)
+                .ok_or(CliError::ArithmeticError)?,
+        };
 
         // Initialize validator stake states for all validators from the start
         let mut validator_stake_states = HashMap::new();
-        for validator_history in &histories {
+        for validator_history in &dataset.histories {
             validator_stake_states.insert(
-                validator_history.vote_account.clone(),
+                validator_history.vote_account,
                 ValidatorStakeState::default(),
             );
         }
@@ -158,26 +270,95 @@ impl RebalancingSimulator {
             scoring_unstake_cap_bps,
             validator_stake_states,
             validator_scores: HashMap::new(),
-            current_cycle_end: simulation_start_epoch
-                .checked_add(steward_cycle_rate)
-                .unwrap(),
+            current_cycle_start: simulation_start_epoch,
+            current_cycle_end: next_cycle_start(simulation_start_epoch, steward_cycle_rate)
+                .min(simulation_end_epoch),
             total_lamports_staked,
             rebalancing_cycles: Vec::new(),
             top_validators: Vec::new(),
+            validator_results: HashMap::new(),
+            delegation_strategy: Arc::new(StewardStrategy),
+            scoring_model: Arc::new(OnChainScoringModel),
+            validator_filter: ValidatorFilter::default(),
+            directed_stake: Vec::new(),
+            directed_stake_states: HashMap::new(),
+            directed_returns: EpochReturns::new(),
+            steward_returns: EpochReturns::new(),
+            decentralization: Vec::new(),
+            turnover: Vec::new(),
+            stake_deposit_unstake: false,
+            forgone_rewards_lamports: 0,
+            scoring_summaries: Vec::new(),
+            record_score_breakdowns: false,
+            score_breakdowns: Vec::new(),
+            steward_phases: false,
+            phase_transitions: Vec::new(),
+            coverage_policy,
+            coverage: CoverageReport::new(coverage_policy, &coverage_gaps),
+            record_epoch_traces: false,
+            epoch_traces: Vec::new(),
+            epoch_events: Vec::new(),
             pending_deactivation: 0,
-            histories,
-            jito_cluster_history,
-            entries_by_validator: Arc::new(entries_by_validator),
+            dataset,
             stake_epoch_map: manual_withdraw_deposit_stake_epoch_map,
             sol_epoch_map: manual_withdraw_deposit_sol_epoch_map,
+            rng: StdRng::seed_from_u64(seed),
         })
     }
 
+    /// Replaces the default steward delegation strategy
+    pub fn with_delegation_strategy(
+        mut self,
+        delegation_strategy: Arc<dyn DelegationStrategy>,
+    ) -> Self {
+        self.delegation_strategy = delegation_strategy;
+        self
+    }
+
+    /// Replaces the default on-chain scoring model
+    pub fn with_scoring_model(mut self, scoring_model: Arc<dyn ScoringModel>) -> Self {
+        self.scoring_model = scoring_model;
+        self
+    }
+
+    /// Replaces the default filter, which only excludes the recorded steward blacklist
+    pub fn with_validator_filter(mut self, validator_filter: ValidatorFilter) -> Self {
+        self.validator_filter = validator_filter;
+        self
+    }
+
+    /// Delegates part of the pool to the given validators regardless of their score
+    pub fn with_directed_stake(mut self, directed_stake: Vec<DirectedStakeTarget>) -> Self {
+        self.directed_stake = directed_stake;
+        self
+    }
+
+    /// Keeps the score breakdown of every validator in every scoring epoch in `score_breakdowns`
+    pub fn with_score_breakdowns(mut self, record_score_breakdowns: bool) -> Self {
+        self.record_score_breakdowns = record_score_breakdowns;
+        self
+    }
+
+    /// Steps through the phases of the steward program every epoch
+    pub fn with_steward_phases(mut self, steward_phases: bool) -> Self {
+        self.steward_phases = steward_phases;
+        self
+    }
+
+    /// Unstakes the stake deposited to the pool's validators like the steward program
+    pub fn with_stake_deposit_unstake(mut self, stake_deposit_unstake: bool) -> Self {
+        self.stake_deposit_unstake = stake_deposit_unstake;
+        self
+    }
+
+    /// Keeps the state of the pool at the end of every epoch in `epoch_traces`
+    pub fn with_epoch_traces(mut self, record_epoch_traces: bool) -> Self {
+        self.record_epoch_traces = record_epoch_traces;
+        self
+    }
+
     /// Main simulation entry point
-    pub async fn run_simulation(
-        &mut self,
-        db_connection: &Pool<Postgres>,
-    ) -> Result<Vec<RebalancingCycle>, CliError> {
+    pub async fn run_simulation(&mut self) -> Result<Vec<RebalancingCycle>, CliError> {
         let mut cycle_starting_lamports = 0u64;
 
         for current_epoch in self.simulation_start_epoch..self.simulation_end_epoch {
@@ -205,13 +386,19 @@ impl RebalancingSimulator {
             if !self.top_validators.is_empty() {
                 // process normal epoch cycle
                 self.process_epoch_cycle(
-                    db_connection,
                     &current_epoch_entries,
                     current_epoch,
                     is_rebalancing_epoch,
                 )
                 .await?;
+
+                self.record_decentralization(&current_epoch_entries, current_epoch);
             }
+
+            if self.record_epoch_traces {
+                self.record_epoch_trace(current_epoch, is_rebalancing_epoch);
+            }
+            self.epoch_events.clear();
         }
 
         self.finalize_simulation(cycle_starting_lamports);
@@ -221,22 +408,68 @@ impl RebalancingSimulator {
     /// Transitions each validator's stake state. Activating stake becomes active, deactivating is
     /// removed.
     fn transition_validator_stake_stake(&mut self) {
-        for stake_state in self.validator_stake_states.values_mut() {
+        for stake_state in self
+            .validator_stake_states
+            .values_mut()
+            .chain(self.directed_stake_states.values_mut())
+        {
             stake_state.process_epoch_transition();
         }
     }
 
-    /// checks if the current epoch is the start fo a new steward cycle
+    /// checks if the current epoch is the start fo a new steward cycle. Like the on-chain
+    /// steward, cycles start at multiples of the cycle length, the simulation starts with a
+    /// partial cycle when the start epoch isn't one.
     fn is_rebalancing_epoch(&self, current_epoch: u16) -> bool {
-        (current_epoch - self.simulation_start_epoch).is_multiple_of(self.steward_cycle_rate)
+        current_epoch == self.simulation_start_epoch
+            || current_epoch.is_multiple_of(self.steward_cycle_rate)
+    }
+
+    /// Slot the steward computes instant unstakes at in the epoch
+    fn instant_unstake_slot(&self, epoch: u16) -> u64 {
+        epoch_progress_slot(
+            &self.dataset.epoch_schedule,
+            epoch.into(),
+            self.steward_config
+                .parameters
+                .instant_unstake_epoch_progress,
+        )
+    }
+
+    /// Records the steward entering a phase as the simulator runs its step, when stepping through
+    /// the phases. Rebalancing and idling happen at the slot of the step before them.
+    fn record_phase(&mut self, epoch: u16, phase: StewardPhase) {
+        if !self.steward_phases {
+            return;
+        }
+        let epoch_schedule = &self.dataset.epoch_schedule;
+        let slots = EpochSlots::new(
+            epoch_schedule.get_first_slot_in_epoch(epoch.into()),
+            epoch_schedule.get_last_slot_in_epoch(epoch.into()),
+            self.steward_config.parameters.compute_score_slot_range,
+            self.instant_unstake_slot(epoch),
+        );
+        let slot = match phase {
+            StewardPhase::ComputeScores => slots.epoch_start,
+            StewardPhase::ComputeDelegations => slots.delegations,
+            StewardPhase::ComputeInstantUnstake => slots.instant_unstake,
+            StewardPhase::Rebalance | StewardPhase::Idle => self
+                .phase_transitions
+                .last()
+                .filter(|transition| transition.epoch == epoch)
+                .map_or(slots.epoch_start, |transition| transition.slot),
+        };
+        self.phase_transitions
+            .push(PhaseTransition { epoch, slot, phase });
     }
 
     /// From all the validator entries, filter only the entires that are before the current epoch
     fn get_current_epoch_entries(
         &self,
         current_epoch: u16,
-    ) -> Arc<HashMap<String, Vec<ValidatorHistoryEntry>>> {
-        let current_epoch_entries: HashMap<String, Vec<ValidatorHistoryEntry>> = self
+    ) -> Arc<HashMap<VotePubkey, Vec<ValidatorHistoryEntry>>> {
+        let current_epoch_entries: HashMap<VotePubkey, Vec<ValidatorHistoryEntry>> = self
+            .dataset
             .entries_by_validator
             .iter()
             .map(|(vote_pubkey, entries)| {
@@ -252,7 +485,7 @@ impl RebalancingSimulator {
                         .cmp(&a.validator_history_entry.epoch)
                 });
 
-                (vote_pubkey.clone(), filtered_entries)
+                (*vote_pubkey, filtered_entries)
             })
             .collect();
 
@@ -262,7 +495,7 @@ impl RebalancingSimulator {
     /// Starts a new steward cycle, called when a epoch is `rebalancing_epoch`
     async fn process_steward_cycle(
         &mut self,
-        current_epoch_entries: &Arc<HashMap<String, Vec<ValidatorHistoryEntry>>>,
+        current_epoch_entries: &Arc<HashMap<VotePubkey, Vec<ValidatorHistoryEntry>>>,
         current_epoch: u16,
         cycle_starting_lamports: u64,
     ) -> Result<u64, CliError> {
@@ -270,24 +503,43 @@ impl RebalancingSimulator {
             "Starting steward cycle at epoch {} (cycle {} of estimated {})",
             current_epoch,
             self.rebalancing_cycles.len() + 1,
-            (self.simulation_end_epoch - self.simulation_start_epoch) / self.steward_cycle_rate
+            (self.simulation_end_epoch - self.simulation_start_epoch)
+                .div_ceil(self.steward_cycle_rate)
         );
 
         // Complete the previous cycle if this isn't the very first rebalancing epoch
         if !self.rebalancing_cycles.is_empty() || cycle_starting_lamports > 0 {
-            self.complete_cycle(cycle_starting_lamports);
+            self.complete_cycle(cycle_starting_lamports, current_epoch);
         }
+        self.current_cycle_start = current_epoch;
+        self.turnover.push(CycleTurnover::new(current_epoch));
 
+        self.record_phase(current_epoch, StewardPhase::ComputeScores);
         self.top_validators = self
             .select_top_validators(current_epoch_entries, current_epoch)
             .await?;
+        self.record_phase(current_epoch, StewardPhase::ComputeDelegations);
+
+        for validator in &self.top_validators {
+            let result = self
+                .validator_results
+                .entry(validator.vote_account)
+                .or_insert_with(|| ValidatorResult {
+                    vote_account: validator.vote_account,
+                    cycles_selected: 0,
+                    last_score: 0.0,
+                    final_stake_lamports: 0,
+                });
+            result.cycles_selected += 1;
+            result.last_score = validator.score;
+        }
 
+        self.record_phase(current_epoch, StewardPhase::Rebalance);
         let new_cycle_starting_lamports = self.rebalance_stakes();
+        self.record_phase(current_epoch, StewardPhase::Idle);
 
         self.current_cycle_end = std::cmp::min(
-            current_epoch
-                .checked_add(self.steward_cycle_rate)
-                .unwrap_or(self.simulation_end_epoch),
+            next_cycle_start(current_epoch, self.steward_cycle_rate),
             self.simulation_end_epoch,
         );
 
@@ -297,8 +549,7 @@ impl RebalancingSimulator {
     /// process normal epoch cycle
     async fn process_epoch_cycle(
         &mut self,
-        db_connection: &Pool<Postgres>,
-        current_epoch_entries: &Arc<HashMap<String, Vec<ValidatorHistoryEntry>>>,
+        current_epoch_entries: &Arc<HashMap<VotePubkey, Vec<ValidatorHistoryEntry>>>,
         current_epoch: u16,
         is_rebalancing_epoch: bool,
     ) -> Result<(), CliError> {
@@ -306,31 +557,41 @@ impl RebalancingSimulator {
         self.apply_epoch_stake_changes(current_epoch)?;
         self.apply_epoch_sol_changes(current_epoch)?;
 
-        if !self.top_validators.is_empty() && !is_rebalancing_epoch {
-            self.check_previous_cycle_stake();
-        }
-
-        // We won't calculate instant unstakes in the epoch that steward cycle starts
-        if !is_rebalancing_epoch {
+        if self.steward_phases {
+            // Like the steward program, instant unstakes are computed every epoch, including the
+            // first epoch of a cycle, where the stake was rebalanced with the new delegations
+            self.record_phase(current_epoch, StewardPhase::ComputeInstantUnstake);
             self.handle_epoch_instant_unstaking(current_epoch_entries, current_epoch)
                 .await?;
+            if !self.top_validators.is_empty() && !is_rebalancing_epoch {
+                self.record_phase(current_epoch, StewardPhase::Rebalance);
+                self.check_previous_cycle_stake();
+            }
+            self.record_phase(current_epoch, StewardPhase::Idle);
+        } else {
+            if !self.top_validators.is_empty() && !is_rebalancing_epoch {
+                self.check_previous_cycle_stake();
+            }
+
+            // We won't calculate instant unstakes in the epoch that steward cycle starts
+            if !is_rebalancing_epoch {
+                self.handle_epoch_instant_unstaking(current_epoch_entries, current_epoch)
+                    .await?;
+            }
         }
 
-        self.simulate_epoch_returns(db_connection, current_epoch)
-            .await?;
+        self.simulate_epoch_returns(current_epoch);
 
         Ok(())
     }
 
     /// stores the result of the last steward cycle in the struct and updates the total lamports staked
-    fn complete_cycle(&mut self, cycle_starting_lamports: u64) {
-        let cycle_ending_lamports = self
-            .validator_stake_states
-            .values()
-            .map(|state| state.total())
-            .sum::<u64>();
+    fn complete_cycle(&mut self, cycle_starting_lamports: u64, end_epoch: u16) {
+        let cycle_ending_lamports = self.pool_total_lamports();
 
         let cycle_result = RebalancingCycle {
+            start_epoch: self.current_cycle_start,
+            end_epoch,
             starting_total_lamports: cycle_starting_lamports,
             ending_total_lamports: cycle_ending_lamports,
         };
@@ -347,22 +608,24 @@ impl RebalancingSimulator {
     }
 
     /// spawns new `tokio::task` for all the validators, calculates their score
-    /// and finds the top `self.number_of_validator_delegations` validators
+    /// and lets the delegation strategy pick the validators to delegate to
     async fn select_top_validators(
-        &self,
-        current_epoch_entries: &Arc<HashMap<String, Vec<ValidatorHistoryEntry>>>,
+        &mut self,
+        current_epoch_entries: &Arc<HashMap<VotePubkey, Vec<ValidatorHistoryEntry>>>,
         current_epoch: u16,
     ) -> Result<Vec<ValidatorWithScore>, CliError> {
         info!("Scoring validators for epoch {}", current_epoch);
 
         let scoring_tasks: Vec<_> = self
+            .dataset
             .histories
             .iter()
             .map(|validator_history| {
                 let validator_history = validator_history.clone();
                 let entries_by_validator = Arc::clone(current_epoch_entries);
-                let jito_cluster_history = Arc::clone(&self.jito_cluster_history);
+                let jito_cluster_history = Arc::clone(&self.dataset.jito_cluster_history);
                 let steward_config = self.steward_config;
+                let scoring_model = Arc::clone(&self.scoring_model);
 
                 tokio::task::spawn_blocking(move || {
                     Self::score_validator(
@@ -370,6 +633,7 @@ impl RebalancingSimulator {
                         &entries_by_validator,
                         &jito_cluster_history,
                         &steward_config,
+                        scoring_model.as_ref(),
                         current_epoch,
                     )
                 })
@@ -380,39 +644,69 @@ impl RebalancingSimulator {
             .await
             .map_err(CliError::TaskJoinError)?;
 
-        let mut scored_validators: Vec<(String, f64)> = scoring_results
+        let records: Vec<ValidatorScoreRecord> = scoring_results
             .into_iter()
             .filter_map(|result| result.ok())
             .collect();
 
-        scored_validators.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let mut summary = ScoringEpochSummary::from_records(current_epoch, &records);
+        for (reason, count) in &summary.errors {
+            warn!(
+                "{} validators failed to score in epoch {}: {}",
+                count, current_epoch, reason
+            );
+        }
 
-        let top_validators: Vec<ValidatorWithScore> = scored_validators
+        let mut scored_validators: Vec<(VotePubkey, f64)> = records
+            .iter()
+            .filter(|record| {
+                !self.validator_filter.is_excluded(
+                    &self.dataset,
+                    &record.vote_account,
+                    current_epoch,
+                )
+            })
+            .map(|record| (record.vote_account, record.score))
+            .collect();
+        summary.excluded = (records.len() - scored_validators.len()) as u32;
+        self.scoring_summaries.push(summary);
+        if self.record_score_breakdowns {
+            self.score_breakdowns.extend(records);
+        }
+
+        scored_validators.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        let candidates: Vec<ValidatorWithScore> = scored_validators
             .into_iter()
-            .filter(|(_, score)| *score > 0.0)
-            .take(self.number_of_validator_delegations)
-            .map(|(vote_account, score)| ValidatorWithScore {
-                vote_account,
-                score,
+            .map(|(vote_account, score)| {
+                // Entries are sorted by descending epoch, skip the fields that were never set
+                let latest_entry = current_epoch_entries
+                    .get(&vote_account)
+                    .and_then(|entries| entries.first())
+                    .map(|entry| entry.validator_history_entry);
+                ValidatorWithScore {
+                    activated_stake_lamports: latest_entry
+                        .map(|entry| entry.activated_stake_lamports)
+                        .filter(|stake| *stake != u64::MAX)
+                        .unwrap_or(0),
+                    is_superminority: latest_entry.is_some_and(|entry| entry.is_superminority == 1),
+                    vote_account,
+                    score,
+                }
             })
             .collect();
 
-        Ok(top_validators)
+        Ok(self
+            .delegation_strategy
+            .select(&candidates, self.number_of_validator_delegations))
     }
 
     /// rebalance the stakes from the validators
     fn rebalance_stakes(&mut self) -> u64 {
-        let current_total_stake = self
-            .validator_stake_states
-            .values()
-            .map(|state| state.total())
-            .sum::<u64>();
+        let current_total_stake = self.pool_total_lamports();
 
-        let new_validator_set: HashSet<String> = self
-            .top_validators
-            .iter()
-            .map(|v| v.vote_account.clone())
-            .collect();
+        let new_validator_set: HashSet<VotePubkey> =
+            self.top_validators.iter().map(|v| v.vote_account).collect();
 
         self.adjust_validator_targets_for_scoring_change(&new_validator_set);
 
@@ -422,21 +716,194 @@ impl RebalancingSimulator {
             self.total_lamports_staked
         };
 
-        self.redistribute_stakes(target_total);
+        let directed_total = self.rebalance_directed_stake(target_total, current_total_stake == 0);
+        self.redistribute_stakes(target_total - directed_total);
 
         target_total
     }
 
+    /// Stake of the whole pool, including the directed stake
+    fn pool_total_lamports(&self) -> u64 {
+        self.validator_stake_states
+            .values()
+            .chain(self.directed_stake_states.values())
+            .map(|state| state.total())
+            .sum()
+    }
+
+    /// Sum of the targets of the directed validators
+    fn directed_stake_total(&self) -> u64 {
+        self.directed_stake_states
+            .values()
+            .map(|state| state.target)
+            .sum()
+    }
+
+    /// Turnover of the current steward cycle, started if no cycle has recorded any yet
+    fn cycle_turnover(&mut self) -> &mut CycleTurnover {
+        if self.turnover.is_empty() {
+            self.turnover
+                .push(CycleTurnover::new(self.current_cycle_start));
+        }
+        let last = self.turnover.len() - 1;
+        &mut self.turnover[last]
+    }
+
+    /// Moves the directed validators to their targets for a pool of `pool_lamports` and returns
+    /// the sum of the targets. Directed stake is funded first by the stake released from directed
+    /// validators and deactivated by the steward, then by taking stake from the steward
+    /// validators. What isn't needed is left for the steward to redistribute.
+    fn rebalance_directed_stake(&mut self, pool_lamports: u64, initial_allocation: bool) -> u64 {
+        if self.directed_stake.is_empty() {
+            return 0;
+        }
+        let targets = directed_stake_targets(&self.directed_stake, pool_lamports);
+
+        let mut vote_accounts: Vec<&VotePubkey> = targets.keys().collect();
+        vote_accounts.sort();
+        let mut needed = Vec::new();
+        let mut released = 0u64;
+        let mut released_from = 0u32;
+        for vote_account in vote_accounts {
+            let target = targets[vote_account];
+            let stake_state = self.directed_stake_states.entry(*vote_account).or_default();
+            stake_state.target = target;
+
+            let total = stake_state.total();
+            if total < target {
+                needed.push((*vote_account, target - total));
+            } else if total > target {
+                let excess = total - target;
+                let from_activating = excess.min(stake_state.activating);
+                let from_active = (excess - from_activating).min(stake_state.active);
+                stake_state.activating -= from_activating;
+                stake_state.active -= from_active;
+                stake_state.deactivating += from_activating + from_active;
+                released += from_activating + from_active;
+                released_from += 1;
+                self.epoch_events.push(EpochEvent::DirectedUnstake {
+                    vote_account: *vote_account,
+                    lamports: from_activating + from_active,
+                });
+            }
+        }
+        let turnover = self.cycle_turnover();
+        turnover.directed_unstake_lamports += released;
+        turnover.transactions += released_from;
+
+        let total_needed: u64 = needed.iter().map(|(_, amount)| amount).sum();
+        let mut available = if initial_allocation {
+            total_needed
+        } else {
+            let available = released + self.pending_deactivation;
+            available + self.take_steward_stake(total_needed.saturating_sub(available))
+        };
+
+        for (vote_account, amount) in needed {
+            let allocation = amount.min(available);
+            if let Some(stake_state) = self.directed_stake_states.get_mut(&vote_account) {
+                stake_state.add_activating_stake(allocation);
+                available -= allocation;
+                if allocation > 0 {
+                    self.epoch_events.push(EpochEvent::Activate {
+                        vote_account,
+                        lamports: allocation,
+                    });
+                }
+                if !initial_allocation && allocation > 0 {
+                    let turnover = self.cycle_turnover();
+                    turnover.activated_lamports += allocation;
+                    turnover.transactions += 1;
+                }
+                info!(
+                    "Directing {:.3} SOL to validator {}",
+                    allocation as f64 / LAMPORTS_PER_SOL as f64,
+                    vote_account
+                );
+            }
+        }
+        if !initial_allocation {
+            self.pending_deactivation = available;
+        }
+
+        targets.values().sum()
+    }
+
+    /// Deactivates up to `amount` from the steward validators proportionally to their active
+    /// stake, returns the amount deactivated
+    fn take_steward_stake(&mut self, amount: u64) -> u64 {
+        let total_active: u64 = self
+            .validator_stake_states
+            .values()
+            .map(|state| state.active)
+            .sum();
+        if amount == 0 || total_active == 0 {
+            return 0;
+        }
+        let amount = amount.min(total_active);
+
+        let mut vote_accounts: Vec<VotePubkey> = self
+            .validator_stake_states
+            .iter()
+            .filter(|(_, state)| state.active > 0)
+            .map(|(vote_account, _)| *vote_account)
+            .collect();
+        vote_accounts.sort();
+
+        let mut taken = 0u64;
+        let mut taken_from = HashSet::new();
+        for vote_account in &vote_accounts {
+            if let Some(stake_state) = self.validator_stake_states.get_mut(vote_account) {
+                let share = (u128::from(amount) * u128::from(stake_state.active)
+                    / u128::from(total_active)) as u64;
+                let share = share.min(amount - taken).min(stake_state.active);
+                stake_state.active -= share;
+                stake_state.deactivating += share;
+                taken += share;
+                if share > 0 {
+                    taken_from.insert(vote_account);
+                    self.epoch_events.push(EpochEvent::DirectedUnstake {
+                        vote_account: *vote_account,
+                        lamports: share,
+                    });
+                }
+            }
+        }
+        // Rounding leftovers
+        for vote_account in &vote_accounts {
+            if taken == amount {
+                break;
+            }
+            if let Some(stake_state) = self.validator_stake_states.get_mut(vote_account) {
+                let share = (amount - taken).min(stake_state.active);
+                stake_state.active -= share;
+                stake_state.deactivating += share;
+                taken += share;
+                if share > 0 {
+                    taken_from.insert(vote_account);
+                    self.epoch_events.push(EpochEvent::DirectedUnstake {
+                        vote_account: *vote_account,
+                        lamports: share,
+                    });
+                }
+            }
+        }
+
+        let transactions = taken_from.len() as u32;
+        let turnover = self.cycle_turnover();
+        turnover.directed_unstake_lamports += taken;
+        turnover.transactions += transactions;
+
+        taken
+    }
+
     /// This function checks if there is still stake present in validators from the previous set
     /// that must still be deactivated.
     /// if yes, then we deactivate the previous amount by `self.scoring_unstake_cap_bps` and then
     /// distribute it to the highest score validator that has not reached the `target`
     fn check_previous_cycle_stake(&mut self) {
-        let new_validator_set: HashSet<String> = self
-            .top_validators
-            .iter()
-            .map(|v| v.vote_account.clone())
-            .collect();
+        let new_validator_set: HashSet<VotePubkey> =
+            self.top_validators.iter().map(|v| v.vote_account).collect();
 
         // Check if there are still old validators with stake
         let has_old_validators_with_stake =
@@ -453,18 +920,23 @@ impl RebalancingSimulator {
             self.adjust_validator_targets_for_scoring_change(&new_validator_set);
 
             // Redistribute to new validators
-            let target_total = self.total_lamports_staked;
+            let target_total = self
+                .total_lamports_staked
+                .saturating_sub(self.directed_stake_total());
             self.redistribute_stakes(target_total);
         }
     }
 
     /// Adjusts the validator targets based on a new set of validators.
-    fn adjust_validator_targets_for_scoring_change(&mut self, new_validator_set: &HashSet<String>) {
+    fn adjust_validator_targets_for_scoring_change(
+        &mut self,
+        new_validator_set: &HashSet<VotePubkey>,
+    ) {
         let max_deactivation_amount =
             (self.total_lamports_staked as u128 * self.scoring_unstake_cap_bps as u128 / 10000)
                 .min(u64::MAX as u128) as u64;
 
-        let mut validators_to_deactivate: Vec<(String, f64, u64)> = Vec::new();
+        let mut validators_to_deactivate: Vec<(VotePubkey, f64, u64)> = Vec::new();
 
         for (vote_account, stake_state) in self.validator_stake_states.iter() {
             if !new_validator_set.contains(vote_account) && stake_state.total() > 0 {
@@ -473,15 +945,15 @@ impl RebalancingSimulator {
                     .get(vote_account)
                     .copied()
                     .unwrap_or(0.0);
-                validators_to_deactivate.push((vote_account.clone(), score, stake_state.total()));
+                validators_to_deactivate.push((*vote_account, score, stake_state.total()));
             }
         }
 
-        validators_to_deactivate
-            .sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        validators_to_deactivate.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
 
         let mut total_deactivated = 0u64;
         let mut actual_deactivated_stake = 0u64;
+        let mut deactivated_validators = 0u32;
 
         for (vote_account, _score, total_stake) in validators_to_deactivate {
             // If we don't exceed the deactivation cap, then deactivate all
@@ -496,6 +968,11 @@ impl RebalancingSimulator {
                     stake_state.activating = 0;
 
                     actual_deactivated_stake += active_to_deactivate + activating_to_deactivate;
+                    deactivated_validators += 1;
+                    self.epoch_events.push(EpochEvent::ScoringUnstake {
+                        vote_account,
+                        lamports: active_to_deactivate + activating_to_deactivate,
+                    });
 
                     info!(
                         "Deactivating entire validator {} ({:.3} SOL) - Score: {:.4}",
@@ -528,6 +1005,13 @@ impl RebalancingSimulator {
                     let total_deactivated_this_validator =
                         remaining_capacity - amount_to_deactivate;
                     actual_deactivated_stake += total_deactivated_this_validator;
+                    if total_deactivated_this_validator > 0 {
+                        deactivated_validators += 1;
+                        self.epoch_events.push(EpochEvent::ScoringUnstake {
+                            vote_account,
+                            lamports: total_deactivated_this_validator,
+                        });
+                    }
 
                     let remaining_stake = stake_state.total();
                     stake_state.target = remaining_stake;
@@ -549,6 +1033,9 @@ impl RebalancingSimulator {
         }
 
         self.pending_deactivation = actual_deactivated_stake;
+        let turnover = self.cycle_turnover();
+        turnover.scoring_unstake_lamports += actual_deactivated_stake;
+        turnover.transactions += deactivated_validators;
 
         info!(
             "Gradual migration: Deactivated {:.3} SOL ({:.2}% of total) from lowest-scored validators",
@@ -569,16 +1056,18 @@ impl RebalancingSimulator {
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
-        let stake_per_validator: u64 = target_total / sorted_validators.len() as u64;
+        let targets = self
+            .delegation_strategy
+            .targets(&sorted_validators, target_total);
 
         self.validator_scores.clear();
         for validator in &sorted_validators {
             self.validator_scores
-                .insert(validator.vote_account.clone(), validator.score);
+                .insert(validator.vote_account, validator.score);
 
             if let Some(stake_state) = self.validator_stake_states.get_mut(&validator.vote_account)
             {
-                stake_state.target = stake_per_validator;
+                stake_state.target = targets.get(&validator.vote_account).copied().unwrap_or(0);
             }
         }
 
@@ -606,6 +1095,7 @@ impl RebalancingSimulator {
 
         // Distribute available stake prioritizing high-scored validators to reach their target first
         let mut remaining_stake = available_for_redistribution;
+        let mut allocations = 0u32;
 
         for validator in &sorted_validators {
             if remaining_stake == 0 {
@@ -630,6 +1120,11 @@ impl RebalancingSimulator {
                     stake_state.target = current_total + allocation;
                     stake_state.add_activating_stake(allocation);
                     remaining_stake -= allocation;
+                    allocations += 1;
+                    self.epoch_events.push(EpochEvent::Activate {
+                        vote_account: validator.vote_account,
+                        lamports: allocation,
+                    });
 
                     info!(
                         "Allocating {:.3} SOL to validator {} (Score: {:.4}) - Progress: {:.1}% of desired target",
@@ -650,11 +1145,19 @@ impl RebalancingSimulator {
         }
 
         self.pending_deactivation = 0;
+        // The initial allocation funds the pool, it isn't turnover
+        if total_existing_stake > 0 {
+            let turnover = self.cycle_turnover();
+            turnover.activated_lamports += available_for_redistribution - remaining_stake;
+            turnover.transactions += allocations;
+        }
 
         info!(
-            "Redistributed {:.3} SOL to validators (target: {:.3} SOL each, remaining unfulfilled: {:.3} SOL)",
+            "Redistributed {:.3} SOL to validators (average target: {:.3} SOL, remaining unfulfilled: {:.3} SOL)",
             (available_for_redistribution - remaining_stake) as f64 / LAMPORTS_PER_SOL as f64,
-            stake_per_validator as f64 / LAMPORTS_PER_SOL as f64,
+            targets.values().sum::<u64>() as f64
+                / targets.len().max(1) as f64
+                / LAMPORTS_PER_SOL as f64,
             remaining_stake as f64 / LAMPORTS_PER_SOL as f64
         );
     }
@@ -664,6 +1167,25 @@ impl RebalancingSimulator {
     fn apply_epoch_stake_changes(&mut self, current_epoch: u16) -> Result<(), CliError> {
         let current_epoch_u64 = current_epoch as u64;
 
+        // When enabled, the steward unstakes the stake deposited to its validators, up to a cap
+        // per cycle
+        let mut deposit_unstake_available = if self.stake_deposit_unstake {
+            let deposit_unstake_cap = (self.total_lamports_staked as u128
+                * self.steward_config.parameters.stake_deposit_unstake_cap_bps as u128
+                / 10000)
+                .min(u64::MAX as u128) as u64;
+            deposit_unstake_cap.saturating_sub(
+                self.turnover
+                    .last()
+                    .map(|turnover| turnover.stake_deposit_unstake_lamports)
+                    .unwrap_or(0),
+            )
+        } else {
+            0
+        };
+        let mut deposit_unstaked_amount = 0u64;
+        let mut deposit_unstaked_validators = Vec::new();
+
         if let Some(epoch_data_vec) = self.stake_epoch_map.get(&current_epoch_u64) {
             let num_records = epoch_data_vec.len();
             if num_records == 0 {
@@ -672,27 +1194,25 @@ impl RebalancingSimulator {
 
             // Only select from top_validators for manual withdraw/deposit stake operations
             // also filter the ones that have a target 0
-            let top_validator_accounts: Vec<String> = self
+            let top_validator_accounts: Vec<VotePubkey> = self
                 .top_validators
                 .iter()
                 .filter(|v| {
                     self.validator_stake_states.contains_key(&v.vote_account)
                         && self.validator_stake_states[&v.vote_account].target != 0
                 })
-                .map(|v| v.vote_account.clone())
+                .map(|v| v.vote_account)
                 .collect();
 
             if top_validator_accounts.is_empty() {
                 return Ok(());
             }
 
-            let mut rng = rng();
-            let selected_validators: Vec<String> = (0..num_records)
+            let selected_validators: Vec<VotePubkey> = (0..num_records)
                 .map(|_| {
-                    top_validator_accounts
-                        .choose(&mut rng)
+                    *top_validator_accounts
+                        .choose(&mut self.rng)
                         .unwrap_or(&top_validator_accounts[0])
-                        .clone()
                 })
                 .collect();
 
@@ -719,6 +1239,11 @@ impl RebalancingSimulator {
 
                     stake_state.increase_activating_stake(stake_change_ratio)?;
                     let new_active = stake_state.active;
+                    if let Some(event) =
+                        EpochEvent::stake_change(*validator_account, old_active, new_active)
+                    {
+                        self.epoch_events.push(event);
+                    }
 
                     info!(
                         "Epoch {}: Adjusted validator {} active stake by {:.6} SOL ({:.2}% change) - Active: {:.6} -> {:.6} SOL",
@@ -729,16 +1254,42 @@ impl RebalancingSimulator {
                         old_active as f64 / LAMPORTS_PER_SOL as f64,
                         new_active as f64 / LAMPORTS_PER_SOL as f64
                     );
+
+                    let unstake = new_active
+                        .saturating_sub(old_active)
+                        .min(deposit_unstake_available);
+                    if unstake > 0 {
+                        stake_state.add_deactivating_stake(unstake)?;
+                        deposit_unstake_available -= unstake;
+                        deposit_unstaked_amount += unstake;
+                        deposit_unstaked_validators.push(*validator_account);
+                        self.epoch_events.push(EpochEvent::StakeDepositUnstake {
+                            vote_account: *validator_account,
+                            lamports: unstake,
+                        });
+                    }
                 }
             }
         }
 
+        if deposit_unstaked_amount > 0 {
+            info!(
+                "Epoch {}: Stake deposit unstaking: moved {:.6} SOL to deactivating from {} validators",
+                current_epoch,
+                deposit_unstaked_amount as f64 / LAMPORTS_PER_SOL as f64,
+                deposit_unstaked_validators.len()
+            );
+            let turnover = self.cycle_turnover();
+            turnover.stake_deposit_unstake_lamports += deposit_unstaked_amount;
+            turnover.transactions += deposit_unstaked_validators.len() as u32;
+            self.redistribute_unstaked_amount(
+                deposit_unstaked_amount,
+                &deposit_unstaked_validators,
+            );
+        }
+
         // updating the total lamports staked
-        self.total_lamports_staked = self
-            .validator_stake_states
-            .values()
-            .map(|state| state.total())
-            .sum::<u64>();
+        self.total_lamports_staked = self.pool_total_lamports();
 
         Ok(())
     }
@@ -754,7 +1305,7 @@ impl RebalancingSimulator {
             }
 
             let net_sol_change = epoch_sol_data.deposit_sol - epoch_sol_data.withdraw_sol;
-            let active_top_validators: Vec<String> = self
+            let active_top_validators: Vec<VotePubkey> = self
                 .top_validators
                 .iter()
                 .filter(|v| {
@@ -763,7 +1314,7 @@ impl RebalancingSimulator {
                         .map(|state| state.target != 0)
                         .unwrap_or(false)
                 })
-                .map(|v| v.vote_account.clone())
+                .map(|v| v.vote_account)
                 .collect();
 
             if active_top_validators.is_empty() {
@@ -785,15 +1336,17 @@ impl RebalancingSimulator {
                         0.0
                     };
 
+                    let old_active = stake_state.active;
                     stake_state.increase_activating_stake(validator_ratio)?;
+                    if let Some(event) =
+                        EpochEvent::stake_change(*validator_account, old_active, stake_state.active)
+                    {
+                        self.epoch_events.push(event);
+                    }
                 }
             }
 
-            self.total_lamports_staked = self
-                .validator_stake_states
-                .values()
-                .map(|state| state.total())
-                .sum::<u64>();
+            self.total_lamports_staked = self.pool_total_lamports();
         }
         Ok(())
     }
@@ -801,14 +1354,11 @@ impl RebalancingSimulator {
     /// Calculate the validators that need to be unstaked in an epoch and then unstakes them
     async fn handle_epoch_instant_unstaking(
         &mut self,
-        current_epoch_entries: &Arc<HashMap<String, Vec<ValidatorHistoryEntry>>>,
+        current_epoch_entries: &Arc<HashMap<VotePubkey, Vec<ValidatorHistoryEntry>>>,
         current_epoch: u16,
     ) -> Result<(), CliError> {
-        let current_validator_list: Vec<String> = self
-            .top_validators
-            .iter()
-            .map(|v| v.vote_account.clone())
-            .collect();
+        let current_validator_list: Vec<VotePubkey> =
+            self.top_validators.iter().map(|v| v.vote_account).collect();
 
         let validators_to_unstake = self
             .calculate_unstake_per_epoch(
@@ -829,23 +1379,42 @@ impl RebalancingSimulator {
     /// returns a array of pubkeys of all the unstaked validators
     async fn calculate_unstake_per_epoch(
         &self,
-        selected_validators: &[String],
-        entries_by_validator: &Arc<HashMap<String, Vec<ValidatorHistoryEntry>>>,
+        selected_validators: &[VotePubkey],
+        entries_by_validator: &Arc<HashMap<VotePubkey, Vec<ValidatorHistoryEntry>>>,
         epoch: u16,
-    ) -> Result<Vec<String>, CliError> {
-        let epoch_start_slot = epoch as u64 * 432_000;
+    ) -> Result<Vec<VotePubkey>, CliError> {
+        let epoch_schedule = &self.dataset.epoch_schedule;
+        let epoch_start_slot = epoch_schedule.get_first_slot_in_epoch(epoch.into());
+        // The steward computes instant unstakes once `instant_unstake_epoch_progress` of the
+        // epoch passed, with the data recorded up to that slot
+        let instant_unstake_slot = self.instant_unstake_slot(epoch);
+        let cluster_history_at_slot = Arc::new(cluster_history_at_slot(
+            &self.dataset.jito_cluster_history,
+            epoch_schedule,
+            epoch,
+            instant_unstake_slot,
+        ));
+
+        // Validators excluded since they were selected are unstaked like blacklisted validators
+        let (excluded_validators, selected_validators): (Vec<&VotePubkey>, Vec<&VotePubkey>) =
+            selected_validators.iter().partition(|vote_account| {
+                self.validator_filter
+                    .is_excluded(&self.dataset, vote_account, epoch)
+            });
+
         let unstake_tasks: Vec<_> = selected_validators
-            .iter()
+            .into_iter()
             .filter_map(|validator_vote_account| {
-                self.histories
+                self.dataset
+                    .histories
                     .iter()
                     .find(|vh| vh.vote_account == *validator_vote_account)
                     .map(|validator_history| {
                         let validator_history = validator_history.clone();
                         let entries_by_validator = Arc::clone(entries_by_validator);
-                        let jito_cluster_history = Arc::clone(&self.jito_cluster_history);
+                        let jito_cluster_history = Arc::clone(&cluster_history_at_slot);
                         let steward_config = self.steward_config;
-                        let vote_account = validator_vote_account.clone();
+                        let vote_account = *validator_vote_account;
 
                         tokio::task::spawn_blocking(move || {
                             let unstake_result = Self::calculate_instant_unstake(
@@ -854,6 +1423,7 @@ impl RebalancingSimulator {
                                 &jito_cluster_history,
                                 &steward_config,
                                 epoch_start_slot,
+                                instant_unstake_slot,
                                 epoch,
                             );
                             (vote_account, unstake_result)
@@ -866,7 +1436,8 @@ impl RebalancingSimulator {
             .await
             .map_err(CliError::TaskJoinError)?;
 
-        let mut validators_to_unstake = Vec::new();
+        let mut validators_to_unstake: Vec<VotePubkey> =
+            excluded_validators.into_iter().cloned().collect();
         for (vote_account, result) in unstake_results {
             match result {
                 Ok(should_unstake) => {
@@ -892,13 +1463,13 @@ impl RebalancingSimulator {
     /// Stake is put in deactivating and isn't instantly removed. Will be removed in the next epoch
     fn handle_instant_unstaking(
         &mut self,
-        validators_to_unstake: &[String],
+        validators_to_unstake: &[VotePubkey],
     ) -> Result<(), CliError> {
         let max_unstake_amount =
             (self.total_lamports_staked as u128 * self.instant_unstake_cap_bps as u128 / 10000)
                 .min(u64::MAX as u128) as u64;
 
-        let mut validators_with_scores: Vec<(String, f64, u64)> = validators_to_unstake
+        let mut validators_with_scores: Vec<(VotePubkey, f64, u64)> = validators_to_unstake
             .iter()
             .filter_map(|vote_account| {
                 let score = self
@@ -910,13 +1481,12 @@ impl RebalancingSimulator {
 
                 self.validator_stake_states
                     .get(vote_account)
-                    .map(|state| (vote_account.clone(), score, state.total()))
+                    .map(|state| (*vote_account, score, state.total()))
             })
             .collect();
 
         // sorting all the validators to be unstaked by scores
-        validators_with_scores
-            .sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        validators_with_scores.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
 
         let mut actual_validators_to_unstake = Vec::new();
         let mut total_unstaked_amount = 0u64;
@@ -935,6 +1505,10 @@ impl RebalancingSimulator {
         for validator in &actual_validators_to_unstake {
             if let Some(stake_state) = self.validator_stake_states.get_mut(validator) {
                 let total_stake = stake_state.total();
+                self.epoch_events.push(EpochEvent::InstantUnstake {
+                    vote_account: *validator,
+                    lamports: stake_state.active + stake_state.activating,
+                });
                 stake_state.add_deactivating_stake(stake_state.active)?;
                 stake_state.deactivating += stake_state.activating;
                 stake_state.activating = 0;
@@ -949,6 +1523,10 @@ impl RebalancingSimulator {
             }
         }
 
+        let turnover = self.cycle_turnover();
+        turnover.instant_unstake_lamports += total_unstaked_amount;
+        turnover.transactions += actual_validators_to_unstake.len() as u32;
+
         // Redistribute to remaining validators
         if total_unstaked_amount > 0 {
             self.redistribute_unstaked_amount(total_unstaked_amount, &actual_validators_to_unstake);
@@ -962,7 +1540,7 @@ impl RebalancingSimulator {
     fn redistribute_unstaked_amount(
         &mut self,
         total_unstaked_amount: u64,
-        unstaked_validators: &[String],
+        unstaked_validators: &[VotePubkey],
     ) {
         let remaining_validators: Vec<_> = self
             .top_validators
@@ -981,6 +1559,12 @@ impl RebalancingSimulator {
                     stake_state.add_activating_stake(stake_per_remaining_validator);
                     // Update target to reflect the additional stake
                     stake_state.target += stake_per_remaining_validator;
+                    if stake_per_remaining_validator > 0 {
+                        self.epoch_events.push(EpochEvent::Activate {
+                            vote_account: validator.vote_account,
+                            lamports: stake_per_remaining_validator,
+                        });
+                    }
                 }
             }
 
@@ -990,50 +1574,94 @@ impl RebalancingSimulator {
                 remaining_validators.len(),
                 stake_per_remaining_validator as f64 / LAMPORTS_PER_SOL as f64
             );
+
+            let remaining = remaining_validators.len();
+            let turnover = self.cycle_turnover();
+            turnover.activated_lamports += stake_per_remaining_validator * remaining as u64;
+            turnover.transactions += remaining as u32;
         }
     }
 
     /// This function calculates the total returns before and after a epoch, and update the total lamports staked
     /// based on the rewards of the validators
-    async fn simulate_epoch_returns(
-        &mut self,
-        db_connection: &Pool<Postgres>,
-        current_epoch: u16,
-    ) -> Result<(), CliError> {
-        let total_before_rewards = self
+    fn simulate_epoch_returns(&mut self, current_epoch: u16) {
+        let total_before_rewards = self.pool_total_lamports();
+
+        let rewards = self
+            .dataset
+            .epoch_rewards
+            .get(&u64::from(current_epoch))
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        let steward_active: u64 = self
             .validator_stake_states
             .values()
-            .map(|state| state.total())
-            .sum::<u64>();
-
-        let validator_list: Vec<String> = self.validator_stake_states.keys().cloned().collect();
-        let rewards = EpochRewards::fetch_for_single_epoch(
-            db_connection,
-            &validator_list,
-            current_epoch.into(),
-        )
-        .await?;
+            .map(|state| state.active)
+            .sum();
+        let directed_active: u64 = self
+            .directed_stake_states
+            .values()
+            .map(|state| state.active)
+            .sum();
+        let activating: u64 = self
+            .validator_stake_states
+            .values()
+            .chain(self.directed_stake_states.values())
+            .map(|state| state.activating)
+            .sum();
+        let mut steward_rewards = 0u64;
+        let mut directed_rewards = 0u64;
 
         for reward in rewards {
             if let Some(stake_state) = self.validator_stake_states.get_mut(&reward.vote_pubkey)
-                && stake_state.active > 0 {
-                    let reward_amount =
-                        reward.stake_after_epoch(stake_state.active) - stake_state.active;
-                    stake_state.apply_rewards(reward_amount);
-                }
+                && stake_state.active > 0
+            {
+                let reward_amount =
+                    reward.stake_after_epoch(stake_state.active) - stake_state.active;
+                stake_state.apply_rewards(reward_amount);
+                steward_rewards += reward_amount;
+            }
+            if let Some(stake_state) = self.directed_stake_states.get_mut(&reward.vote_pubkey)
+                && stake_state.active > 0
+            {
+                let reward_amount =
+                    reward.stake_after_epoch(stake_state.active) - stake_state.active;
+                stake_state.apply_rewards(reward_amount);
+                directed_rewards += reward_amount;
+            }
         }
 
-        let total_after_rewards = self
-            .validator_stake_states
-            .values()
-            .map(|state| state.total())
-            .sum::<u64>();
+        if self.coverage_policy == CoveragePolicy::ImputeMedian {
+            let (steward_imputed, directed_imputed) = self.impute_missing_rewards(current_epoch);
+            steward_rewards += steward_imputed;
+            directed_rewards += directed_imputed;
+        }
+
+        // Stake that is moved is deactivated from one validator and activated on another in the
+        // same epoch, so it is only counted once, while activating
+        let pool_active = steward_active + directed_active;
+        if pool_active > 0 {
+            self.forgone_rewards_lamports += (u128::from(activating)
+                * u128::from(steward_rewards + directed_rewards)
+                / u128::from(pool_active)) as u64;
+        }
+
+        self.steward_returns
+            .record_epoch(steward_active, steward_rewards);
+        if !self.directed_stake.is_empty() {
+            self.directed_returns
+                .record_epoch(directed_active, directed_rewards);
+        }
+
+        let total_after_rewards = self.pool_total_lamports();
 
         self.total_lamports_staked = total_after_rewards;
 
         let active_stake_total = self
             .validator_stake_states
             .values()
+            .chain(self.directed_stake_states.values())
             .map(|state| state.active)
             .sum::<u64>();
 
@@ -1045,15 +1673,144 @@ impl RebalancingSimulator {
             (total_after_rewards - total_before_rewards) as f64 / LAMPORTS_PER_SOL as f64,
             active_stake_total as f64 / LAMPORTS_PER_SOL as f64
         );
+    }
 
-        Ok(())
+    /// Gives the active stake on validators without recorded rewards for the epoch the median
+    /// return of the cluster, returning the steward and directed rewards imputed
+    fn impute_missing_rewards(&mut self, current_epoch: u16) -> (u64, u64) {
+        let rewards = self
+            .dataset
+            .epoch_rewards
+            .get(&u64::from(current_epoch))
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let Some(median_return) = median_epoch_return(rewards) else {
+            return (0, 0);
+        };
+        let rewarded: HashSet<VotePubkey> =
+            rewards.iter().map(|reward| reward.vote_pubkey).collect();
+
+        let mut imputed_rewards = Vec::new();
+        let mut impute = |stake_states: &mut HashMap<VotePubkey, ValidatorStakeState>| {
+            let mut total = 0u64;
+            for (vote_account, stake_state) in stake_states.iter_mut() {
+                if stake_state.active == 0 || rewarded.contains(vote_account) {
+                    continue;
+                }
+                let rewards_lamports = (stake_state.active as f64 * median_return) as u64;
+                stake_state.apply_rewards(rewards_lamports);
+                total += rewards_lamports;
+                imputed_rewards.push(ImputedReward {
+                    vote_account: *vote_account,
+                    epoch: current_epoch,
+                    rewards_lamports,
+                });
+            }
+            total
+        };
+        let steward_imputed = impute(&mut self.validator_stake_states);
+        let directed_imputed = impute(&mut self.directed_stake_states);
+
+        imputed_rewards.sort_by_key(|a| a.vote_account);
+        for imputed in imputed_rewards {
+            self.coverage.record_imputed(imputed);
+        }
+        (steward_imputed, directed_imputed)
+    }
+
+    fn record_epoch_trace(&mut self, current_epoch: u16, is_rebalancing_epoch: bool) {
+        let stake_states = self
+            .validator_stake_states
+            .iter()
+            .filter(|(_, state)| state.total() > 0 || state.target > 0)
+            .map(|(vote_account, state)| (*vote_account, state.clone()))
+            .collect();
+        self.epoch_traces.push(EpochTrace {
+            epoch: current_epoch,
+            is_rebalancing_epoch,
+            total_lamports: self.pool_total_lamports(),
+            stake_states,
+            turnover: self.turnover.last().cloned(),
+            events: std::mem::take(&mut self.epoch_events),
+        });
+    }
+
+    /// Records how concentrated the stake of the pool is at the end of the epoch
+    fn record_decentralization(
+        &mut self,
+        current_epoch_entries: &HashMap<VotePubkey, Vec<ValidatorHistoryEntry>>,
+        current_epoch: u16,
+    ) {
+        let mut network_stakes: Vec<(&VotePubkey, u64)> = current_epoch_entries
+            .iter()
+            .filter_map(|(vote_account, entries)| {
+                entries
+                    .first()
+                    .map(|entry| entry.validator_history_entry.activated_stake_lamports)
+                    .filter(|stake| *stake != u64::MAX)
+                    .map(|stake| (vote_account, stake))
+            })
+            .collect();
+        network_stakes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        let network_total: u128 = network_stakes
+            .iter()
+            .map(|(_, stake)| u128::from(*stake))
+            .sum();
+        let mut nakamoto_set = HashSet::new();
+        let mut held = 0u128;
+        for (vote_account, stake) in network_stakes {
+            if held * 3 > network_total {
+                break;
+            }
+            held += u128::from(stake);
+            nakamoto_set.insert(vote_account);
+        }
+
+        let mut pool_stakes: HashMap<&VotePubkey, u64> = HashMap::new();
+        for (vote_account, state) in self
+            .validator_stake_states
+            .iter()
+            .chain(self.directed_stake_states.iter())
+        {
+            *pool_stakes.entry(vote_account).or_default() += state.total();
+        }
+
+        let validators: Vec<DelegatedValidator> = pool_stakes
+            .into_iter()
+            .filter(|(_, stake)| *stake > 0)
+            .map(|(vote_account, pool_stake_lamports)| {
+                // Entries are sorted by descending epoch, use the latest value that was set
+                let entries = current_epoch_entries
+                    .get(vote_account)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                let latest = |field: fn(&ValidatorHistoryEntry) -> u8| {
+                    entries.iter().map(field).find(|value| *value != u8::MAX)
+                };
+                DelegatedValidator {
+                    pool_stake_lamports,
+                    is_superminority: latest(|entry| {
+                        entry.validator_history_entry.is_superminority
+                    }) == Some(1),
+                    client_type: latest(|entry| entry.validator_history_entry.client_type),
+                    ip: entries
+                        .iter()
+                        .map(|entry| entry.validator_history_entry.ip)
+                        .find(|ip| *ip != [0; 4] && *ip != [u8::MAX; 4]),
+                    in_nakamoto_set: nakamoto_set.contains(vote_account),
+                }
+            })
+            .collect();
+
+        self.decentralization
+            .push(DecentralizationMetrics::new(current_epoch, &validators));
     }
 
     /// Pushes the final rebalancing cycle
     fn finalize_simulation(&mut self, cycle_starting_lamports: u64) {
         // Always complete the final cycle if we have validator states
         if !self.validator_stake_states.is_empty() && cycle_starting_lamports > 0 {
-            self.complete_cycle(cycle_starting_lamports);
+            self.complete_cycle(cycle_starting_lamports, self.simulation_end_epoch);
         }
 
         info!(
@@ -1062,43 +1819,53 @@ impl RebalancingSimulator {
         );
     }
 
-    /// This returns a hashmap of validator votekey to it's entries in the db
-    fn build_entries_by_validator(
-        all_entries: Vec<ValidatorHistoryEntry>,
-    ) -> HashMap<String, Vec<ValidatorHistoryEntry>> {
-        let mut entries_by_validator: HashMap<String, Vec<ValidatorHistoryEntry>> = HashMap::new();
-        for entry in all_entries {
-            entries_by_validator
-                .entry(entry.vote_pubkey.clone())
-                .or_default()
-                .push(entry);
-        }
-        entries_by_validator
+    /// Returns the result of every validator that was selected at least once, ordered by vote
+    /// account
+    pub fn validator_results(&self) -> Vec<ValidatorResult> {
+        let mut results: Vec<ValidatorResult> = self
+            .validator_results
+            .values()
+            .cloned()
+            .map(|mut result| {
+                result.final_stake_lamports = self
+                    .validator_stake_states
+                    .get(&result.vote_account)
+                    .map(|state| state.total())
+                    .unwrap_or(0);
+                result
+            })
+            .collect();
+        results.sort_by_key(|a| a.vote_account);
+        results
     }
 
     /// This returns the hashap of manual withdraws and deposits of stakes epochwise
     fn build_stake_epoch_map(
-        withdraws_and_deposits: Vec<WithdrawsAndDepositStakes>,
-        active_stake: &Vec<ActiveStakeJitoSol>,
+        withdraws_and_deposits: &[WithdrawsAndDepositStakes],
+        active_stake: &[ActiveStakeJitoSol],
     ) -> HashMap<u64, Vec<EpochWithdrawDepositStakeData>> {
         let mut epoch_map: HashMap<u64, Vec<EpochWithdrawDepositStakeData>> = HashMap::new();
         let mut active_by_epoch: HashMap<u64, f64> = HashMap::new();
 
         for stake in active_stake {
             let balance = stake.balance.to_f64().unwrap_or(0.0);
-            *active_by_epoch.entry(stake.epoch).or_insert(0.0) += balance;
+            *active_by_epoch.entry(stake.epoch.into()).or_insert(0.0) += balance;
         }
 
         for wd in withdraws_and_deposits {
-            let active_balance = active_by_epoch.get(&wd.epoch).cloned().unwrap_or(0.0);
+            let active_balance = active_by_epoch
+                .get(&u64::from(wd.epoch))
+                .cloned()
+                .unwrap_or(0.0);
 
-            epoch_map.entry(wd.epoch).or_default().push(
-                EpochWithdrawDepositStakeData {
+            epoch_map
+                .entry(wd.epoch.into())
+                .or_default()
+                .push(EpochWithdrawDepositStakeData {
                     withdraw_stake: wd.withdraw_stake.to_f64().unwrap_or(0.0),
                     deposit_stake: wd.deposit_stake.to_f64().unwrap_or(0.0),
                     active_balance,
-                },
-            );
+                });
         }
 
         epoch_map
@@ -1106,21 +1873,24 @@ impl RebalancingSimulator {
 
     /// This returns the hashmap of epoch to `WithdrawAndDepositSol` consisting of withdraw/deposit SOL and total `active_stake``
     fn build_sol_epoch_map(
-        withdraw_and_deposit_sol: Vec<WithdrawAndDepositSol>,
-        active_stake: &Vec<ActiveStakeJitoSol>,
+        withdraw_and_deposit_sol: &[WithdrawAndDepositSol],
+        active_stake: &[ActiveStakeJitoSol],
     ) -> HashMap<u64, EpochWithdrawDepositSOLData> {
         let mut active_by_epoch: HashMap<u64, f64> = HashMap::new();
         for stake in active_stake {
             let balance = stake.balance.to_f64().unwrap_or(0.0);
-            *active_by_epoch.entry(stake.epoch).or_insert(0.0) += balance;
+            *active_by_epoch.entry(stake.epoch.into()).or_insert(0.0) += balance;
         }
 
         let mut epoch_map: HashMap<u64, EpochWithdrawDepositSOLData> = HashMap::new();
         for sol_data in withdraw_and_deposit_sol {
-            let active_balance = active_by_epoch.get(&sol_data.epoch).cloned().unwrap_or(0.0);
+            let active_balance = active_by_epoch
+                .get(&u64::from(sol_data.epoch))
+                .cloned()
+                .unwrap_or(0.0);
 
             epoch_map.insert(
-                sol_data.epoch,
+                sol_data.epoch.into(),
                 EpochWithdrawDepositSOLData {
                     active_stake: active_balance,
                     deposit_sol: sol_data.deposit_sol.to_f64().unwrap_or(0.0),
@@ -1134,12 +1904,13 @@ impl RebalancingSimulator {
 
     fn score_validator(
         validator_history: ValidatorHistory,
-        entries_by_validator: &HashMap<String, Vec<ValidatorHistoryEntry>>,
+        entries_by_validator: &HashMap<VotePubkey, Vec<ValidatorHistoryEntry>>,
         jito_cluster_history: &JitoClusterHistory,
         steward_config: &Config,
+        scoring_model: &dyn ScoringModel,
         current_epoch: u16,
-    ) -> Result<(String, f64), CliError> {
-        let vote_account = validator_history.vote_account.clone();
+    ) -> Result<ValidatorScoreRecord, CliError> {
+        let vote_account = validator_history.vote_account;
 
         let mut entries = entries_by_validator
             .get(&vote_account)
@@ -1149,36 +1920,55 @@ impl RebalancingSimulator {
         let jito_validator_history =
             validator_history.convert_to_jito_validator_history(&mut entries);
 
-        let score_result = validator_score(
+        let record = match scoring_model.score(
             &jito_validator_history,
             jito_cluster_history,
             steward_config,
             current_epoch,
-            TVC_ACTIVATION_EPOCH,
-        );
+        ) {
+            Ok(breakdown) => ValidatorScoreRecord {
+                epoch: current_epoch,
+                vote_account,
+                score: breakdown.score,
+                components: breakdown.components,
+                error: None,
+            },
+            // Validators that can't be scored are never delegated to
+            Err(error) => ValidatorScoreRecord {
+                epoch: current_epoch,
+                vote_account,
+                score: 0.0,
+                components: Default::default(),
+                error: Some(error),
+            },
+        };
 
-        match score_result {
-            Ok(score) => Ok((vote_account, score.score)),
-            Err(_) => Ok((vote_account, 0.0)),
-        }
+        Ok(record)
     }
 
     fn calculate_instant_unstake(
         validator_history: ValidatorHistory,
-        entries_by_validator: &HashMap<String, Vec<ValidatorHistoryEntry>>,
+        entries_by_validator: &HashMap<VotePubkey, Vec<ValidatorHistoryEntry>>,
         jito_cluster_history: &JitoClusterHistory,
         config: &Config,
         epoch_start_slot: u64,
+        instant_unstake_slot: u64,
         current_epoch: u16,
     ) -> Result<bool, CliError> {
-        let vote_account = validator_history.vote_account.clone();
+        let vote_account = validator_history.vote_account;
         let mut entries = entries_by_validator
             .get(&vote_account)
             .cloned()
             .unwrap_or_default();
 
-        let jito_validator_history =
+        let mut jito_validator_history =
             validator_history.convert_to_jito_validator_history(&mut entries);
+        validator_history_at_slot(
+            &mut jito_validator_history,
+            current_epoch,
+            epoch_start_slot,
+            instant_unstake_slot,
+        );
 
         let unstake_result = instant_unstake_validator(
             &jito_validator_history,
@@ -1201,3 +1991,342 @@ impl RebalancingSimulator {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        backtester::{
+            Backtest, DEFAULT_NUMBER_OF_VALIDATOR_DELEGATIONS, SyntheticDataset,
+            SyntheticDatasetConfig, WeightedScoringModel,
+        },
+        scenario::Scenario,
+        steward_utils::StewardParameterOverrides,
+    };
+    use stakenet_simulator_db::{domain::Epoch, store::Snapshot};
+    use std::path::{Path, PathBuf};
+
+    /// A snapshot of a small synthetic dataset and the scenario simulated on it. The datasets are
+    /// committed rather than generated again, so that the goldens don't change with the
+    /// generator.
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct GoldenFixture {
+        /// Snapshot file, relative to the fixture
+        dataset: PathBuf,
+        scenario: Scenario,
+    }
+
+    #[derive(Serialize)]
+    struct GoldenOutput {
+        rebalancing_cycles: Vec<RebalancingCycle>,
+        epochs: Vec<EpochTrace>,
+    }
+
+    /// Steward parameters of the fixtures, unless the scenario overrides them
+    fn golden_parameters() -> StewardParameterOverrides {
+        StewardParameterOverrides {
+            mev_commission_range: Some(10),
+            epoch_credits_range: Some(30),
+            commission_range: Some(30),
+            scoring_delinquency_threshold_ratio: Some(0.85),
+            instant_unstake_delinquency_threshold_ratio: Some(0.7),
+            mev_commission_bps_threshold: Some(1000),
+            commission_threshold: Some(5),
+            historical_commission_threshold: Some(50),
+            priority_fee_lookback_epochs: Some(10),
+            priority_fee_lookback_offset: Some(2),
+            priority_fee_max_commission_bps: Some(5000),
+            priority_fee_error_margin_bps: Some(10),
+            num_delegation_validators: Some(200),
+            scoring_unstake_cap_bps: Some(750),
+            instant_unstake_cap_bps: Some(1000),
+            stake_deposit_unstake_cap_bps: Some(1000),
+            instant_unstake_epoch_progress: Some(0.9),
+            compute_score_slot_range: Some(1000),
+            instant_unstake_inputs_epoch_progress: Some(0.5),
+            num_epochs_between_scoring: Some(10),
+            minimum_stake_lamports: Some(5_000 * LAMPORTS_PER_SOL),
+            minimum_voting_epochs: Some(5),
+            priority_fee_scoring_start_epoch: Some(0),
+        }
+    }
+
+    async fn run_fixture(fixture: GoldenFixture, fixtures_dir: &Path) -> GoldenOutput {
+        let scenario = fixture.scenario;
+        let mut parameters = scenario.parameters.clone();
+        parameters.fill_unset_from(&golden_parameters());
+        let mut config: Config = bytemuck::Zeroable::zeroed();
+        parameters.update_steward_config(&mut config);
+        let scoring_model = scenario.scoring.build();
+
+        let start_epoch = scenario.start_epoch.unwrap();
+        let end_epoch = scenario.end_epoch.unwrap();
+        let snapshot = Snapshot::open(&fixtures_dir.join(&fixture.dataset))
+            .await
+            .unwrap();
+        let dataset = BacktestDataset::load(
+            snapshot.store(),
+            start_epoch,
+            end_epoch,
+            scoring_model.lookback_epochs(&config),
+        )
+        .await
+        .unwrap();
+        let mut builder = Backtest::builder()
+            .dataset(dataset)
+            .config(config)
+            .scoring_model(scoring_model)
+            .window(start_epoch, end_epoch)
+            .seed(scenario.seed.unwrap_or_default())
+            .number_of_validator_delegations(
+                scenario
+                    .number_of_validator_delegations
+                    .unwrap_or(DEFAULT_NUMBER_OF_VALIDATOR_DELEGATIONS),
+            )
+            .steward_phases(scenario.steward_phases.unwrap_or_default())
+            .stake_deposit_unstake(scenario.stake_deposit_unstake.unwrap_or_default())
+            .coverage_policy(scenario.coverage_policy.unwrap_or_default())
+            .record_epoch_traces(true);
+        if let Some(steward_cycle_rate) = scenario.steward_cycle_rate {
+            builder = builder.steward_cycle_rate(steward_cycle_rate);
+        }
+        if let Some(initial_pool_lamports) = scenario.initial_pool_lamports {
+            builder = builder.initial_pool_lamports(initial_pool_lamports);
+        }
+        let report = builder.run().await.unwrap();
+        GoldenOutput {
+            rebalancing_cycles: report.rebalancing_cycles,
+            epochs: report.epoch_traces,
+        }
+    }
+
+    /// Runs the fixtures of `tests/fixtures/simulator` and compares the state of the pool at the
+    /// end of every epoch with the goldens of `tests/goldens/simulator`. A missing golden fails
+    /// the test, and `BLESS_GOLDENS=1` writes all of them after an intended change of results.
+    #[tokio::test]
+    async fn test_simulator_goldens() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests");
+        let bless = std::env::var("BLESS_GOLDENS").is_ok_and(|value| value == "1");
+        let fixtures_dir = root.join("fixtures/simulator");
+        let mut fixtures: Vec<PathBuf> = std::fs::read_dir(&fixtures_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "toml")
+            })
+            .collect();
+        fixtures.sort();
+
+        let mut mismatches = Vec::new();
+        for fixture_path in fixtures {
+            let name = fixture_path.file_stem().unwrap().to_string_lossy();
+            let fixture: GoldenFixture =
+                toml::from_str(&std::fs::read_to_string(&fixture_path).unwrap()).unwrap();
+            let output = run_fixture(fixture, &fixtures_dir).await;
+            let output = serde_json::to_string_pretty(&output).unwrap() + "\n";
+
+            let golden_path = root.join("goldens/simulator").join(format!("{name}.json"));
+            if bless {
+                std::fs::create_dir_all(golden_path.parent().unwrap()).unwrap();
+                std::fs::write(&golden_path, output).unwrap();
+                eprintln!("Wrote {}", golden_path.display());
+                continue;
+            }
+            match std::fs::read_to_string(&golden_path) {
+                Ok(golden) => {
+                    let difference = golden
+                        .lines()
+                        .zip(output.lines())
+                        .position(|(expected, actual)| expected != actual)
+                        .or((golden.lines().count() != output.lines().count())
+                            .then(|| golden.lines().count().min(output.lines().count())));
+                    if let Some(line) = difference {
+                        mismatches.push(format!(
+                            "{} differs at line {}: expected {:?}, got {:?}",
+                            golden_path.display(),
+                            line + 1,
+                            golden.lines().nth(line).unwrap_or_default(),
+                            output.lines().nth(line).unwrap_or_default()
+                        ));
+                    }
+                }
+                Err(_) => {
+                    mismatches.push(format!("{} is missing", golden_path.display()));
+                }
+            }
+        }
+        assert!(
+            mismatches.is_empty(),
+            "{}\nRun `BLESS_GOLDENS=1 cargo test -p steward-simulator-cli simulator_goldens` if \
+             the changes are intended",
+            mismatches.join("\n")
+        );
+    }
+
+    const CONFIG: SyntheticDatasetConfig = SyntheticDatasetConfig {
+        num_validators: 10,
+        start_epoch: 700,
+        end_epoch: 730,
+        seed: 1,
+        behaviors: Vec::new(),
+    };
+
+    async fn load_simulator(dataset: SyntheticDataset) -> Result<RebalancingSimulator, CliError> {
+        let store = dataset.into_store().await.unwrap();
+        let dataset = BacktestDataset::load(&store, 710, 730, 10).await.unwrap();
+        RebalancingSimulator::new(
+            Arc::new(dataset),
+            bytemuck::Zeroable::zeroed(),
+            710,
+            730,
+            10,
+            5,
+            0,
+            0,
+            1,
+            None,
+            CoveragePolicy::Fail,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_simulator_on_synthetic_data() {
+        let simulator = load_simulator(SyntheticDataset::generate(&CONFIG))
+            .await
+            .unwrap();
+        assert_eq!(simulator.validator_stake_states.len(), 10);
+        assert_eq!(simulator.total_lamports_staked, 5 * LAMPORTS_PER_SOL);
+        assert_eq!(simulator.sol_epoch_map.len(), 21);
+        assert!(
+            simulator
+                .stake_epoch_map
+                .values()
+                .flatten()
+                .all(|data| data.active_balance > 0.0)
+        );
+
+        // Missing rewards are caught before simulating
+        let mut dataset = SyntheticDataset::generate(&CONFIG);
+        let vote_account = dataset.vote_account(3);
+        dataset
+            .epoch_rewards
+            .retain(|reward| !(reward.vote_pubkey == vote_account && reward.epoch == Epoch(720)));
+        match load_simulator(dataset).await {
+            Err(CliError::DataCoverageGaps(gaps)) => {
+                assert_eq!(gaps.missing_rewards, vec![(vote_account, 720)]);
+            }
+            _ => panic!("expected coverage gaps"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_simulation() {
+        // Only 4 of the validators are within the commission thresholds of the model, and the
+        // first one stops voting before the second cycle is scored. The unstake caps are 0, so
+        // the delinquent validator keeps its stake and the instant unstake checks move none.
+        let dataset = SyntheticDataset::generate(&SyntheticDatasetConfig {
+            behaviors: vec!["delinquent:0@712".parse().unwrap()],
+            ..CONFIG
+        });
+        let delinquent = dataset.vote_account(0);
+        let eligible: Vec<VotePubkey> = [1, 3, 7]
+            .into_iter()
+            .map(|index| dataset.vote_account(index))
+            .collect();
+        let mut simulator = load_simulator(dataset)
+            .await
+            .unwrap()
+            .with_scoring_model(Arc::new(WeightedScoringModel {
+                lookback_epochs: 10,
+                ..WeightedScoringModel::default()
+            }));
+
+        let cycles = simulator.run_simulation().await.unwrap();
+        let cycles: Vec<_> = cycles
+            .iter()
+            .map(|cycle| {
+                (
+                    cycle.start_epoch,
+                    cycle.end_epoch,
+                    cycle.starting_total_lamports,
+                    cycle.ending_total_lamports,
+                )
+            })
+            .collect();
+        assert_eq!(
+            cycles,
+            vec![
+                (710, 720, 5_000_000_000, 5_033_381_256),
+                (720, 730, 5_033_381_256, 5_089_308_308),
+            ]
+        );
+
+        assert_eq!(simulator.validator_results.len(), 4);
+        assert_eq!(simulator.validator_results[&delinquent].cycles_selected, 1);
+        for vote_account in &eligible {
+            assert_eq!(simulator.validator_results[vote_account].cycles_selected, 2);
+        }
+        let mut top_validators: Vec<VotePubkey> = simulator
+            .top_validators
+            .iter()
+            .map(|validator| validator.vote_account)
+            .collect();
+        top_validators.sort();
+        let mut expected = eligible.clone();
+        expected.sort();
+        assert_eq!(top_validators, expected);
+    }
+
+    #[tokio::test]
+    async fn test_steward_phases() {
+        let mut simulator = load_simulator(SyntheticDataset::generate(&CONFIG))
+            .await
+            .unwrap()
+            .with_scoring_model(Arc::new(WeightedScoringModel {
+                lookback_epochs: 10,
+                ..WeightedScoringModel::default()
+            }))
+            .with_steward_phases(true);
+        simulator.steward_config.parameters.compute_score_slot_range = 1000;
+        simulator
+            .steward_config
+            .parameters
+            .instant_unstake_epoch_progress = 0.9;
+        simulator.run_simulation().await.unwrap();
+
+        // The stake is rebalanced when the delegations are computed at the start of a cycle, and
+        // after the instant unstakes in the other epochs
+        let epoch_schedule = simulator.dataset.epoch_schedule.clone();
+        let phases = |epoch: u16| -> Vec<(u64, StewardPhase)> {
+            let first_slot = epoch_schedule.get_first_slot_in_epoch(epoch.into());
+            simulator
+                .phase_transitions
+                .iter()
+                .filter(|transition| transition.epoch == epoch)
+                .map(|transition| (transition.slot - first_slot, transition.phase))
+                .collect()
+        };
+        let instant_unstake_slot = epoch_schedule.get_slots_in_epoch(710) * 9 / 10;
+        assert_eq!(
+            phases(710),
+            vec![
+                (0, StewardPhase::ComputeScores),
+                (1000, StewardPhase::ComputeDelegations),
+                (1000, StewardPhase::Rebalance),
+                (1000, StewardPhase::Idle),
+                (instant_unstake_slot, StewardPhase::ComputeInstantUnstake),
+                (instant_unstake_slot, StewardPhase::Idle),
+            ]
+        );
+        assert_eq!(
+            phases(711),
+            vec![
+                (instant_unstake_slot, StewardPhase::ComputeInstantUnstake),
+                (instant_unstake_slot, StewardPhase::Rebalance),
+                (instant_unstake_slot, StewardPhase::Idle),
+            ]
+        );
+    }
+}
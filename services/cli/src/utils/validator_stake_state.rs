@@ -1,6 +1,7 @@
 use crate::error::CliError;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 pub struct ValidatorStakeState {
     pub active: u64,
     pub activating: u64,
@@ -3,16 +3,16 @@ use anchor_lang::AccountDeserialize;
 use solana_account_decoder_client_types::UiAccountEncoding;
 use solana_client::{nonblocking::rpc_client::RpcClient, rpc_config::RpcAccountInfoConfig};
 use solana_sdk::pubkey::Pubkey;
-use sqlx::{Pool, Postgres};
 use stakenet_simulator_db::{
-    cluster_history::ClusterHistory, cluster_history_entry::ClusterHistoryEntry,
+    bulk_insert::InsertMode, cluster_history_entry::ClusterHistoryEntry, store::SimulatorStore,
 };
 use tracing::info;
 use validator_history::ClusterHistory as JitoClusterHistory;
 
 pub async fn load_and_record_cluster_history(
-    db_connection: &Pool<Postgres>,
+    store: &dyn SimulatorStore,
     rpc_client: &RpcClient,
+    insert_mode: InsertMode,
 ) -> Result<(), EpochRewardsTrackerError> {
     let current_epoch_info = rpc_client.get_epoch_info().await?;
     let last_finalized_epoch = current_epoch_info.epoch as u16;
@@ -53,9 +53,11 @@ pub async fn load_and_record_cluster_history(
         )
         .collect();
     info!("Inserting {} cluster history entries ", entries.len());
-    ClusterHistoryEntry::bulk_insert(db_connection, entries).await?;
+    store
+        .insert_cluster_history_entries(entries, insert_mode)
+        .await?;
     info!("Upserting cluster history ");
-    ClusterHistory::upsert(db_connection, cluster_history.into()).await?;
+    store.upsert_cluster_history(cluster_history.into()).await?;
 
     Ok(())
 }
@@ -0,0 +1,19 @@
+use crate::EpochRewardsTrackerError;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use stakenet_simulator_db::store::SimulatorStore;
+use tracing::info;
+
+/// Records the epoch schedule of the cluster, used by the simulator to find the slots of epochs
+pub async fn record_epoch_schedule(
+    store: &dyn SimulatorStore,
+    rpc_client: &RpcClient,
+) -> Result<(), EpochRewardsTrackerError> {
+    let epoch_schedule = rpc_client.get_epoch_schedule().await?;
+    info!(
+        "Recording epoch schedule: {} slots per epoch, first normal epoch {}",
+        epoch_schedule.slots_per_epoch, epoch_schedule.first_normal_epoch
+    );
+    store.upsert_epoch_schedule(epoch_schedule.into()).await?;
+
+    Ok(())
+}
@@ -2,6 +2,7 @@ use crate::{config::ConfigError, rpc_utils::RpcUtilsError};
 use solana_client::client_error::ClientError;
 use solana_sdk::pubkey::{ParsePubkeyError, Pubkey};
 use sqlx::Error as SqlxError;
+use stakenet_simulator_db::error::StakenetSimulatorDbError;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -21,6 +22,9 @@ pub enum EpochRewardsTrackerError {
     #[error("SqlxError: {0}")]
     SqlxError(#[from] SqlxError),
 
+    #[error("StakenetSimulatorDbError: {0}")]
+    StakenetSimulatorDbError(#[from] StakenetSimulatorDbError),
+
     #[error("ParsePubkeyError: {0}")]
     ParsePubkeyError(#[from] ParsePubkeyError),
 
@@ -36,9 +40,15 @@ pub enum EpochRewardsTrackerError {
     #[error("Database connection error")]
     DatabaseConnectionError,
 
+    #[error("The {0} command needs a Postgres database")]
+    PostgresRequired(&'static str),
+
     #[error("Execution Id not provided")]
     EmptyExecutionId,
 
     #[error("Dune API Error")]
     DuneApiError,
+
+    #[error("Failed to deserialize anchor account")]
+    AnchorDeserializeError,
 }
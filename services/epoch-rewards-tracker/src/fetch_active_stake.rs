@@ -4,13 +4,14 @@ use crate::dune::{
 };
 use crate::errors::EpochRewardsTrackerError;
 use sqlx::types::BigDecimal;
-use sqlx::{Pool, Postgres};
-use stakenet_simulator_db::active_stake_jito_sol::ActiveStakeJitoSol;
+use stakenet_simulator_db::{active_stake_jito_sol::ActiveStakeJitoSol, store::SimulatorStore};
 use std::collections::HashMap;
 use std::str::FromStr;
 use tracing::info;
 
-pub async fn fetch_active_stake(db: &Pool<Postgres>) -> Result<(), EpochRewardsTrackerError> {
+pub async fn fetch_active_stake(
+    store: &dyn SimulatorStore,
+) -> Result<(), EpochRewardsTrackerError> {
     let execute_client = execute_dune_query(ACTIVE_STAKE_DUNE_QUERY)
         .await
         .map_err(|_| EpochRewardsTrackerError::DuneApiError)?;
@@ -45,7 +46,7 @@ pub async fn fetch_active_stake(db: &Pool<Postgres>) -> Result<(), EpochRewardsT
         info!("No records to process.");
     } else {
         info!("Processing records...");
-        ActiveStakeJitoSol::bulk_insert(db, records).await?;
+        store.upsert_active_stake(records).await?;
         info!("Processing complete. Records inserted/updated.");
     }
 
@@ -5,13 +5,15 @@ use crate::{
     },
     errors::EpochRewardsTrackerError,
 };
-use sqlx::{Pool, Postgres, types::BigDecimal};
-use stakenet_simulator_db::inactive_stake_jito_sol::InactiveStakeJitoSol;
+use sqlx::types::BigDecimal;
+use stakenet_simulator_db::{inactive_stake_jito_sol::InactiveStakeJitoSol, store::SimulatorStore};
 use std::collections::HashMap;
 use std::str::FromStr;
 use tracing::info;
 
-pub async fn fetch_inactive_stake(db: &Pool<Postgres>) -> Result<(), EpochRewardsTrackerError> {
+pub async fn fetch_inactive_stake(
+    store: &dyn SimulatorStore,
+) -> Result<(), EpochRewardsTrackerError> {
     let execute_client = execute_dune_query(INACTIVE_STAKE_DUNE_QUERY)
         .await
         .map_err(|_| EpochRewardsTrackerError::DuneApiError)?;
@@ -50,7 +52,7 @@ pub async fn fetch_inactive_stake(db: &Pool<Postgres>) -> Result<(), EpochReward
         info!("No records to process.");
     } else {
         info!("Processing records...");
-        InactiveStakeJitoSol::bulk_insert(db, records).await?;
+        store.upsert_inactive_stake(records).await?;
         info!("Processing complete. Records inserted/updated.");
     }
 
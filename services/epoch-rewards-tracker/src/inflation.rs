@@ -4,7 +4,9 @@ use futures::stream::{self, StreamExt};
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::pubkey::Pubkey;
 use sqlx::{Pool, Postgres};
-use stakenet_simulator_db::{inflation_rewards::InflationReward, stake_accounts::StakeAccount};
+use stakenet_simulator_db::{
+    bulk_insert::InsertMode, inflation_rewards::InflationReward, stake_accounts::StakeAccount,
+};
 use std::str::FromStr;
 use std::sync::Arc;
 use tokio::sync::Semaphore;
@@ -13,6 +15,7 @@ use tracing::{debug, error, info, warn};
 pub async fn gather_inflation_rewards(
     db_connection: &Pool<Postgres>,
     rpc_client: &RpcClient,
+    insert_mode: InsertMode,
 ) -> Result<(), EpochRewardsTrackerError> {
     let stake_account_keys = StakeAccount::get_all_pubkeys(db_connection).await?;
     let stake_account_keys: Vec<Pubkey> = stake_account_keys
@@ -42,7 +45,14 @@ pub async fn gather_inflation_rewards(
                 async move {
                     let _permit = semaphore.acquire().await.unwrap();
 
-                    process_batch_epoch(&db_connection, &rpc_client, &stake_accounts, epoch).await
+                    process_batch_epoch(
+                        &db_connection,
+                        &rpc_client,
+                        &stake_accounts,
+                        epoch,
+                        insert_mode,
+                    )
+                    .await
                 }
             })
         })
@@ -66,6 +76,7 @@ async fn process_batch_epoch(
     rpc_client: &RpcClient,
     stake_accounts: &[Pubkey],
     epoch: u64,
+    insert_mode: InsertMode,
 ) -> Result<(), EpochRewardsTrackerError> {
     let result = async {
         info!(
@@ -97,7 +108,7 @@ async fn process_batch_epoch(
             .collect();
 
         if !records.is_empty() {
-            InflationReward::bulk_insert(db_connection, records).await?;
+            InflationReward::bulk_insert(db_connection, records, insert_mode).await?;
         }
 
         Ok::<(), EpochRewardsTrackerError>(())
@@ -1,14 +1,26 @@
 use crate::{
-    cluster_history::load_and_record_cluster_history, config::Config,
-    errors::EpochRewardsTrackerError, inflation::gather_inflation_rewards,
-    priority_fees::gather_priority_fee_data_for_epoch, rpc_utils::fetch_slot_history,
+    cluster_history::load_and_record_cluster_history,
+    config::Config,
+    epoch_schedule::record_epoch_schedule,
+    errors::EpochRewardsTrackerError,
+    inflation::gather_inflation_rewards,
+    priority_fees::gather_priority_fee_data_for_epoch,
+    rpc_utils::fetch_slot_history,
     stake_accounts::gather_stake_accounts,
+    steward_blacklist::{backfill_steward_blacklists, record_steward_blacklist},
+    steward_delegations::record_steward_delegations,
+    validator_history_repair::repair_validator_history,
     validator_history_utils::load_and_record_validator_history,
 };
 use clap::{Parser, Subcommand};
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::pubkey::Pubkey;
-use sqlx::postgres::PgPoolOptions;
+use stakenet_simulator_db::{
+    bulk_insert::InsertMode,
+    migrations::{check_schema_version, migrate},
+    steward_config_snapshot::STEWARD_CONFIG_PUBKEY,
+    store,
+};
 use std::{str::FromStr, sync::Arc};
 use tracing::Level;
 use tracing_subscriber::EnvFilter;
@@ -16,6 +28,7 @@ use tracing_subscriber::EnvFilter;
 mod cluster_history;
 mod config;
 mod dune;
+mod epoch_schedule;
 mod errors;
 mod fetch_active_stake;
 mod fetch_inactive_stake;
@@ -23,6 +36,9 @@ mod inflation;
 mod priority_fees;
 mod rpc_utils;
 mod stake_accounts;
+mod steward_blacklist;
+mod steward_delegations;
+mod validator_history_repair;
 mod validator_history_utils;
 mod withdraw_and_deposit_sol;
 mod withdraw_and_deposits;
@@ -44,6 +60,11 @@ pub struct GlobalArgs {
 
     #[arg(long, env, default_value_t = 60)]
     pub epoch_check_cycle_sec: u64,
+
+    /// Overwrites the rows already recorded instead of keeping them, to fix the data of a range
+    /// that is fetched again
+    #[arg(long, env)]
+    pub upsert: bool,
 }
 
 #[derive(Parser, Debug)]
@@ -65,7 +86,29 @@ pub enum Commands {
     WithdrawAndDepositSol,
     FetchActiveStake,
     FetchInactiveStake,
-    GetPriorityFeeDataForEpoch { epoch: u64 },
+    GetPriorityFeeDataForEpoch {
+        epoch: u64,
+    },
+    /// Records the current scores and delegations of the on-chain steward, run once per epoch
+    FetchStewardDelegations {
+        #[arg(long, env, default_value_t = STEWARD_CONFIG_PUBKEY.to_string())]
+        steward_config: String,
+    },
+    /// Records the validators blacklisted by the steward config, run once per epoch
+    FetchStewardBlacklist {
+        #[arg(long, env, default_value_t = STEWARD_CONFIG_PUBKEY.to_string())]
+        steward_config: String,
+        /// Records the blacklist of every steward config snapshot instead of the current config
+        #[arg(long)]
+        from_snapshots: bool,
+    },
+    /// Records the epoch schedule of the cluster, only needs to run once
+    FetchEpochSchedule,
+    /// Reconstructs the validator history fields the program did not record, run after
+    /// `fetch-validator-history`
+    RepairValidatorHistory,
+    /// Applies the pending database migrations, run before the other commands on a new database
+    Migrate,
 }
 
 #[tokio::main]
@@ -86,19 +129,27 @@ async fn main() -> Result<(), EpochRewardsTrackerError> {
         .init();
 
     let cli: Cli = Cli::parse();
+    let insert_mode = if cli.globals.upsert {
+        InsertMode::Upsert
+    } else {
+        InsertMode::InsertIgnore
+    };
     let config = Config {
         rpc_url: cli.globals.rpc_url,
         validator_history_program_id: cli.globals.validator_history_program_id,
         db_connection_url: cli.globals.db_connection_url,
     };
 
-    let db_conn_pool = Arc::new(
-        PgPoolOptions::new()
-            .max_connections(5)
-            .connect(&config.db_connection_url)
-            .await
-            .map_err(|_| EpochRewardsTrackerError::DatabaseConnectionError)?,
-    );
+    // A SQLite store only holds the simulator tables, the other commands need Postgres
+    let store = store::connect(&config.db_connection_url)
+        .await
+        .map_err(|_| EpochRewardsTrackerError::DatabaseConnectionError)?;
+    if !matches!(cli.command, Commands::Migrate)
+        && let Some(db_connection) = store.postgres()
+    {
+        check_schema_version(db_connection).await?;
+    }
+    let store = store.as_ref();
 
     let rpc_client = Arc::new(RpcClient::new(config.rpc_url.clone()));
     let validator_history_program_id = Pubkey::from_str(&config.validator_history_program_id)
@@ -106,39 +157,97 @@ async fn main() -> Result<(), EpochRewardsTrackerError> {
     match cli.command {
         Commands::FetchValidatorHistory => {
             load_and_record_validator_history(
-                &db_conn_pool,
+                store,
                 &rpc_client,
                 validator_history_program_id,
+                insert_mode,
             )
             .await?
         }
         Commands::FetchClusterHistory => {
-            load_and_record_cluster_history(&db_conn_pool, &rpc_client).await?
+            load_and_record_cluster_history(store, &rpc_client, insert_mode).await?
+        }
+        Commands::GetStakeAccounts => {
+            let db_connection =
+                store
+                    .postgres()
+                    .ok_or(EpochRewardsTrackerError::PostgresRequired(
+                        "get-stake-accounts",
+                    ))?;
+            gather_stake_accounts(db_connection, &rpc_client, insert_mode).await?
         }
-        Commands::GetStakeAccounts => gather_stake_accounts(&db_conn_pool, &rpc_client).await?,
         Commands::GetInflationRewards => {
-            gather_inflation_rewards(&db_conn_pool, &rpc_client).await?
+            let db_connection =
+                store
+                    .postgres()
+                    .ok_or(EpochRewardsTrackerError::PostgresRequired(
+                        "get-inflation-rewards",
+                    ))?;
+            gather_inflation_rewards(db_connection, &rpc_client, insert_mode).await?
         }
         Commands::GetPriorityFeeDataForEpoch { epoch } => {
+            let db_connection =
+                store
+                    .postgres()
+                    .ok_or(EpochRewardsTrackerError::PostgresRequired(
+                        "get-priority-fee-data-for-epoch",
+                    ))?;
             gather_priority_fee_data_for_epoch(
-                &db_conn_pool,
+                db_connection,
                 &rpc_client,
                 epoch,
                 &rpc_client.get_epoch_schedule().await?,
                 &fetch_slot_history(&rpc_client).await?,
+                insert_mode,
             )
             .await?
         }
+        Commands::FetchStewardDelegations { steward_config } => {
+            let steward_config = Pubkey::from_str(&steward_config)
+                .map_err(|_| EpochRewardsTrackerError::InvalidPubkeyError)?;
+            let db_connection =
+                store
+                    .postgres()
+                    .ok_or(EpochRewardsTrackerError::PostgresRequired(
+                        "fetch-steward-delegations",
+                    ))?;
+            record_steward_delegations(db_connection, &rpc_client, steward_config, insert_mode)
+                .await?
+        }
+        Commands::FetchStewardBlacklist {
+            steward_config,
+            from_snapshots,
+        } => {
+            if from_snapshots {
+                let db_connection =
+                    store
+                        .postgres()
+                        .ok_or(EpochRewardsTrackerError::PostgresRequired(
+                            "fetch-steward-blacklist --from-snapshots",
+                        ))?;
+                backfill_steward_blacklists(store, db_connection).await?
+            } else {
+                let steward_config = Pubkey::from_str(&steward_config)
+                    .map_err(|_| EpochRewardsTrackerError::InvalidPubkeyError)?;
+                record_steward_blacklist(store, &rpc_client, steward_config).await?
+            }
+        }
+        Commands::FetchEpochSchedule => record_epoch_schedule(store, &rpc_client).await?,
+        Commands::RepairValidatorHistory => repair_validator_history(store, &rpc_client).await?,
         // THESE DO NOT REQUIRE AN RPC CLIENT
-        Commands::FetchActiveStake => fetch_active_stake::fetch_active_stake(&db_conn_pool).await?,
-        Commands::FetchInactiveStake => {
-            fetch_inactive_stake::fetch_inactive_stake(&db_conn_pool).await?
+        Commands::Migrate => {
+            let db_connection = store
+                .postgres()
+                .ok_or(EpochRewardsTrackerError::PostgresRequired("migrate"))?;
+            migrate(db_connection).await?
         }
+        Commands::FetchActiveStake => fetch_active_stake::fetch_active_stake(store).await?,
+        Commands::FetchInactiveStake => fetch_inactive_stake::fetch_inactive_stake(store).await?,
         Commands::WithdrawAndDepositStake => {
-            withdraw_and_deposits::withdraw_and_deposits(&db_conn_pool).await?
+            withdraw_and_deposits::withdraw_and_deposits(store, insert_mode).await?
         }
         Commands::WithdrawAndDepositSol => {
-            withdraw_and_deposit_sol::withdraw_and_deposit_sol(&db_conn_pool).await?
+            withdraw_and_deposit_sol::withdraw_and_deposit_sol(store, insert_mode).await?
         }
     }
 
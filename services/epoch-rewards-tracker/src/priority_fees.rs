@@ -3,7 +3,7 @@ use futures::future::join_all;
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::reward_type::RewardType;
 use sqlx::{Pool, Postgres};
-use stakenet_simulator_db::epoch_priority_fees::EpochPriorityFees;
+use stakenet_simulator_db::{bulk_insert::InsertMode, epoch_priority_fees::EpochPriorityFees};
 use std::collections::HashSet;
 use tracing::{error, info};
 
@@ -18,6 +18,7 @@ pub async fn gather_priority_fee_data_for_epoch(
     epoch: u64,
     epoch_schedule: &EpochSchedule,
     slot_history: &SlotHistory,
+    insert_mode: InsertMode,
 ) -> Result<(), EpochRewardsTrackerError> {
     let first_slot_of_epoch = epoch_schedule.get_first_slot_in_epoch(epoch);
     // Fetch the leader schedule for the epoch
@@ -83,6 +84,7 @@ pub async fn gather_priority_fee_data_for_epoch(
         EpochPriorityFees::bulk_insert(
             db_connection,
             vec![EpochPriorityFees::new(identity, epoch, total_fees)],
+            insert_mode,
         )
         .await?;
     }
@@ -2,20 +2,21 @@ use crate::{EpochRewardsTrackerError, rpc_utils::fetch_stake_accounts_for_valida
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{pubkey::Pubkey, stake::state::StakeStateV2};
 use sqlx::{Pool, Postgres};
+use stakenet_simulator_db::bulk_insert::InsertMode;
 use stakenet_simulator_db::stake_accounts::StakeAccount;
 use stakenet_simulator_db::validator_history_entry::ValidatorHistoryEntry;
-use std::str::FromStr;
 use tracing::info;
 
 pub async fn gather_stake_accounts(
     db_connection: &Pool<Postgres>,
     rpc_client: &RpcClient,
+    insert_mode: InsertMode,
 ) -> Result<(), EpochRewardsTrackerError> {
     let vote_keys = ValidatorHistoryEntry::get_all_vote_pubkeys(db_connection).await?;
 
     info!("Fetched {} vote keys", vote_keys.len());
     for vote_key in vote_keys {
-        let vote_pubkey = Pubkey::from_str(&vote_key)?;
+        let vote_pubkey = Pubkey::from(vote_key);
         let res = fetch_stake_accounts_for_validator(rpc_client, &vote_pubkey).await?;
         info!(
             "Fetched {} stake accounts for vote account {}",
@@ -42,7 +43,7 @@ pub async fn gather_stake_accounts(
         // Take the first 10 elements (or fewer if the vector has less than 10)
         res.truncate(10);
         let records: Vec<StakeAccount> = res.into_iter().map(|x| x.into()).collect();
-        StakeAccount::bulk_insert(db_connection, records).await?;
+        StakeAccount::bulk_insert(db_connection, records, insert_mode).await?;
     }
 
     Ok(())
@@ -0,0 +1,88 @@
+use crate::EpochRewardsTrackerError;
+use anchor_lang::AccountDeserialize;
+use jito_steward::Config;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use sqlx::{Pool, Postgres};
+use stakenet_simulator_db::{
+    domain::{Epoch, VotePubkey},
+    steward_blacklist::StewardBlacklist,
+    steward_config_snapshot::StewardConfigSnapshot,
+    store::SimulatorStore,
+    validator_history::ValidatorHistory,
+};
+use tracing::info;
+
+/// Vote accounts of the validators whose validator history index is set in the blacklist of the
+/// steward config
+fn blacklisted_vote_accounts(config: &Config, histories: &[ValidatorHistory]) -> Vec<VotePubkey> {
+    let mut vote_accounts: Vec<VotePubkey> = histories
+        .iter()
+        .filter(|history| {
+            config
+                .validator_history_blacklist
+                .get(history.index as usize)
+                .unwrap_or(false)
+        })
+        .map(|history| history.vote_account)
+        .collect();
+    vote_accounts.sort();
+    vote_accounts
+}
+
+/// Records the validators blacklisted by the current steward config for the current epoch
+pub async fn record_steward_blacklist(
+    store: &dyn SimulatorStore,
+    rpc_client: &RpcClient,
+    steward_config_pubkey: Pubkey,
+) -> Result<(), EpochRewardsTrackerError> {
+    let epoch = rpc_client.get_epoch_info().await?.epoch;
+    let config_account = rpc_client.get_account(&steward_config_pubkey).await?;
+    let config = Config::try_deserialize(&mut config_account.data.as_slice())
+        .map_err(|_| EpochRewardsTrackerError::AnchorDeserializeError)?;
+    let histories = store.fetch_validator_histories().await?;
+
+    let vote_pubkeys = blacklisted_vote_accounts(&config, &histories);
+    info!(
+        "Recording {} blacklisted validators for epoch {}",
+        vote_pubkeys.len(),
+        epoch
+    );
+    store
+        .upsert_steward_blacklist(StewardBlacklist {
+            epoch: Epoch(epoch),
+            vote_pubkeys,
+        })
+        .await?;
+
+    Ok(())
+}
+
+/// Records the blacklist of every steward config snapshot taken by `fetch-steward-delegations`,
+/// the snapshots are only kept in the Postgres database
+pub async fn backfill_steward_blacklists(
+    store: &dyn SimulatorStore,
+    db_connection: &Pool<Postgres>,
+) -> Result<(), EpochRewardsTrackerError> {
+    let snapshots = StewardConfigSnapshot::fetch_all(db_connection).await?;
+    let histories = store.fetch_validator_histories().await?;
+
+    for snapshot in snapshots {
+        let config = Config::try_deserialize(&mut snapshot.config_data.as_slice())
+            .map_err(|_| EpochRewardsTrackerError::AnchorDeserializeError)?;
+        let vote_pubkeys = blacklisted_vote_accounts(&config, &histories);
+        info!(
+            "Recording {} blacklisted validators for epoch {}",
+            vote_pubkeys.len(),
+            snapshot.epoch
+        );
+        store
+            .upsert_steward_blacklist(StewardBlacklist {
+                epoch: snapshot.epoch,
+                vote_pubkeys,
+            })
+            .await?;
+    }
+
+    Ok(())
+}
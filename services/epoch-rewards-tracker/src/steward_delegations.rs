@@ -0,0 +1,108 @@
+use crate::EpochRewardsTrackerError;
+use anchor_lang::AccountDeserialize;
+use jito_steward::{Config, StewardStateAccount};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use sqlx::{Pool, Postgres};
+use stakenet_simulator_db::{
+    bulk_insert::InsertMode, steward_config_snapshot::StewardConfigSnapshot,
+    steward_delegation::StewardDelegation,
+};
+use tracing::info;
+
+// Layout of the SPL stake pool validator list: u8 account type + u32 max validators, followed by
+// a u32 length prefixed list of `ValidatorStakeInfo`
+const VALIDATOR_LIST_HEADER_SIZE: usize = 1 + 4 + 4;
+const VALIDATOR_STAKE_INFO_SIZE: usize = 73;
+// Offset of the vote account in `ValidatorStakeInfo`, after 4 u64, 2 u32 and the u8 status
+const VOTE_ACCOUNT_OFFSET: usize = 8 * 4 + 4 * 2 + 1;
+
+/// Active stake and vote account of every validator in the stake pool validator list, in the
+/// order of the list
+fn parse_validator_list(data: &[u8]) -> Vec<(Pubkey, u64)> {
+    let Some(length) = data.get(5..VALIDATOR_LIST_HEADER_SIZE) else {
+        return Vec::new();
+    };
+    let length = u32::from_le_bytes(length.try_into().unwrap()) as usize;
+
+    data[VALIDATOR_LIST_HEADER_SIZE..]
+        .chunks_exact(VALIDATOR_STAKE_INFO_SIZE)
+        .take(length)
+        .map(|validator| {
+            let active_stake_lamports = u64::from_le_bytes(validator[..8].try_into().unwrap());
+            let vote_account = Pubkey::new_from_array(
+                validator[VOTE_ACCOUNT_OFFSET..VOTE_ACCOUNT_OFFSET + 32]
+                    .try_into()
+                    .unwrap(),
+            );
+            (vote_account, active_stake_lamports)
+        })
+        .collect()
+}
+
+/// Records the current scores, delegation targets and stake of the validators in the stake pool
+/// along with the steward config of the epoch. Should run once per epoch to build up the history
+/// of the steward.
+pub async fn record_steward_delegations(
+    db_connection: &Pool<Postgres>,
+    rpc_client: &RpcClient,
+    steward_config_pubkey: Pubkey,
+    insert_mode: InsertMode,
+) -> Result<(), EpochRewardsTrackerError> {
+    let config_account = rpc_client.get_account(&steward_config_pubkey).await?;
+    let config = Config::try_deserialize(&mut config_account.data.as_slice())
+        .map_err(|_| EpochRewardsTrackerError::AnchorDeserializeError)?;
+
+    let steward_state_pubkey = Pubkey::find_program_address(
+        &[StewardStateAccount::SEED, steward_config_pubkey.as_ref()],
+        &jito_steward::ID,
+    )
+    .0;
+    let steward_state_account = rpc_client.get_account(&steward_state_pubkey).await?;
+    let steward_state =
+        StewardStateAccount::try_deserialize(&mut steward_state_account.data.as_slice())
+            .map_err(|_| EpochRewardsTrackerError::AnchorDeserializeError)?
+            .state;
+
+    let validator_list_account = rpc_client.get_account(&config.validator_list).await?;
+    let validator_list = parse_validator_list(&validator_list_account.data);
+
+    let epoch = steward_state.current_epoch;
+    let num_pool_validators = steward_state.num_pool_validators as usize;
+    let records: Vec<StewardDelegation> = validator_list
+        .into_iter()
+        .take(num_pool_validators)
+        .enumerate()
+        .map(
+            |(index, (vote_account, active_stake_lamports))| StewardDelegation {
+                epoch: epoch.into(),
+                vote_pubkey: vote_account.into(),
+                validator_list_index: index as u32,
+                score: u64::from(steward_state.scores[index]),
+                yield_score: u64::from(steward_state.yield_scores[index]),
+                delegation_numerator: u64::from(steward_state.delegations[index].numerator),
+                delegation_denominator: u64::from(steward_state.delegations[index].denominator),
+                instant_unstake: steward_state.instant_unstake.get(index).unwrap_or(false),
+                active_stake_lamports: active_stake_lamports.into(),
+            },
+        )
+        .collect();
+
+    info!(
+        "Inserting {} steward delegations for epoch {}",
+        records.len(),
+        epoch
+    );
+    StewardDelegation::bulk_insert(db_connection, records, insert_mode).await?;
+    StewardConfigSnapshot::insert(
+        db_connection,
+        StewardConfigSnapshot {
+            epoch: epoch.into(),
+            config_data: config_account.data,
+        },
+        insert_mode,
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use crate::EpochRewardsTrackerError;
+use anchor_lang::prelude::EpochSchedule;
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::{
+    nonblocking::rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcBlockProductionConfig, RpcBlockProductionConfigRange},
+};
+use solana_sdk::vote::state::VoteState;
+use stakenet_simulator_db::{
+    domain::{Epoch, VotePubkey},
+    store::SimulatorStore,
+    validator_history_repair::{RecoveredEpochData, ValidatorHistoryRepair},
+};
+use std::collections::{HashMap, hash_map::Entry};
+use tracing::{info, warn};
+
+/// Leader slots and blocks produced by every validator identity during an epoch
+type BlockProduction = HashMap<String, (usize, usize)>;
+
+/// Reconstructs the validator history fields the program did not record and stores them in
+/// validator_history_repairs, leaving the recorded entries untouched. Epoch credits come from
+/// the vote account, which only holds its last 64 epochs, and blocks produced from the block
+/// production of the cluster, which RPC only serves for the epochs still in its ledger. Older
+/// epochs of the history can't be reconstructed, so repairs made by earlier runs of the same
+/// repair rules are kept for them. Repairs of entries that no longer need one are deleted.
+pub async fn repair_validator_history(
+    store: &dyn SimulatorStore,
+    rpc_client: &RpcClient,
+) -> Result<(), EpochRewardsTrackerError> {
+    let epoch_schedule = rpc_client.get_epoch_schedule().await?;
+    let mut previous_repairs: HashMap<(Epoch, VotePubkey), ValidatorHistoryRepair> = store
+        .fetch_validator_history_repairs(0, u64::from(u16::MAX))
+        .await?
+        .into_iter()
+        .map(|repair| ((repair.epoch, repair.vote_pubkey), repair))
+        .collect();
+    let mut block_production_by_epoch: HashMap<u64, Option<BlockProduction>> = HashMap::new();
+
+    let vote_pubkeys = store
+        .fetch_validator_histories()
+        .await?
+        .into_iter()
+        .map(|history| history.vote_account);
+    for vote_pubkey in vote_pubkeys {
+        let entries = store
+            .fetch_validator_history_entries_by_validator(&vote_pubkey)
+            .await?;
+        let vote_state = fetch_vote_state(rpc_client, &vote_pubkey).await?;
+        let epoch_credits: HashMap<u64, u32> = vote_state
+            .as_ref()
+            .map(|vote_state| {
+                vote_state
+                    .epoch_credits
+                    .iter()
+                    .map(|(epoch, credits, previous_credits)| {
+                        let earned = credits.saturating_sub(*previous_credits);
+                        (*epoch, u32::try_from(earned).unwrap_or(u32::MAX))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        // The current identity of the vote account, which may differ from the identity of
+        // older epochs
+        let identity = vote_state.map(|vote_state| vote_state.node_pubkey.to_string());
+
+        let mut repairs = Vec::new();
+        let mut stale_epochs = Vec::new();
+        for entry in entries {
+            let entry = entry.validator_history_entry;
+            let epoch = u64::from(entry.epoch);
+            let mut recovered = RecoveredEpochData {
+                epoch_credits: epoch_credits.get(&epoch).copied(),
+                block_production: None,
+            };
+            if (entry.blocks_produced == u32::MAX || entry.total_leader_slots == u32::MAX)
+                && let Some(identity) = &identity
+            {
+                let block_production = match block_production_by_epoch.entry(epoch) {
+                    Entry::Occupied(block_production) => block_production.into_mut(),
+                    Entry::Vacant(block_production) => block_production
+                        .insert(fetch_block_production(rpc_client, &epoch_schedule, epoch).await),
+                };
+                recovered.block_production = block_production
+                    .as_ref()
+                    .and_then(|block_production| block_production.get(identity))
+                    .map(|(leader_slots, blocks_produced)| {
+                        (*leader_slots as u32, *blocks_produced as u32)
+                    });
+            }
+
+            let repair = ValidatorHistoryRepair::new(vote_pubkey, &entry, recovered);
+            let previous = previous_repairs.remove(&(Epoch(epoch), vote_pubkey));
+            let repair = match &previous {
+                Some(previous) => repair.merge(previous),
+                None => repair,
+            };
+            if repair.is_repaired() {
+                repairs.push(repair);
+            } else if previous.is_some() {
+                stale_epochs.push(Epoch(epoch));
+            }
+        }
+
+        info!("Recording {} repairs for {}", repairs.len(), vote_pubkey);
+        store.upsert_validator_history_repairs(repairs).await?;
+        if !stale_epochs.is_empty() {
+            store
+                .delete_validator_history_repairs(&vote_pubkey, &stale_epochs)
+                .await?;
+        }
+    }
+
+    // Repairs left over have no recorded entry anymore
+    let mut orphaned_epochs: HashMap<VotePubkey, Vec<Epoch>> = HashMap::new();
+    for (epoch, vote_pubkey) in previous_repairs.into_keys() {
+        orphaned_epochs.entry(vote_pubkey).or_default().push(epoch);
+    }
+    for (vote_pubkey, epochs) in orphaned_epochs {
+        let deleted = store
+            .delete_validator_history_repairs(&vote_pubkey, &epochs)
+            .await?;
+        info!("Deleted {} stale repairs for {}", deleted, vote_pubkey);
+    }
+
+    Ok(())
+}
+
+async fn fetch_vote_state(
+    rpc_client: &RpcClient,
+    vote_pubkey: &VotePubkey,
+) -> Result<Option<VoteState>, EpochRewardsTrackerError> {
+    let response = rpc_client
+        .get_account_with_config(
+            &vote_pubkey.0,
+            RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64Zstd),
+                data_slice: None,
+                commitment: None,
+                min_context_slot: None,
+            },
+        )
+        .await?;
+    let Some(account) = response.value else {
+        warn!(
+            "Vote account {} not found, epoch credits not repaired",
+            vote_pubkey
+        );
+        return Ok(None);
+    };
+    match VoteState::deserialize(&account.data) {
+        Ok(vote_state) => Ok(Some(vote_state)),
+        Err(e) => {
+            warn!("Failed to deserialize vote account {}: {}", vote_pubkey, e);
+            Ok(None)
+        }
+    }
+}
+
+/// Fetches the block production of every identity for the epoch, if the RPC still serves it
+async fn fetch_block_production(
+    rpc_client: &RpcClient,
+    epoch_schedule: &EpochSchedule,
+    epoch: u64,
+) -> Option<BlockProduction> {
+    let range = RpcBlockProductionConfigRange {
+        first_slot: epoch_schedule.get_first_slot_in_epoch(epoch),
+        last_slot: Some(epoch_schedule.get_last_slot_in_epoch(epoch)),
+    };
+    let response = rpc_client
+        .get_block_production_with_config(RpcBlockProductionConfig {
+            identity: None,
+            range: Some(range),
+            commitment: None,
+        })
+        .await;
+    match response {
+        Ok(response) => Some(response.value.by_identity),
+        Err(e) => {
+            warn!("Block production of epoch {} not available: {}", epoch, e);
+            None
+        }
+    }
+}
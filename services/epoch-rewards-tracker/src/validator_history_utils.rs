@@ -7,17 +7,17 @@ use solana_client::{
     rpc_filter::{Memcmp, RpcFilterType},
 };
 use solana_sdk::pubkey::Pubkey;
-use sqlx::{Pool, Postgres};
 use stakenet_simulator_db::{
-    validator_history::ValidatorHistory, validator_history_entry::ValidatorHistoryEntry,
+    bulk_insert::InsertMode, store::SimulatorStore, validator_history_entry::ValidatorHistoryEntry,
 };
 use tracing::info;
 use validator_history::ValidatorHistory as JitoValidatorHistory;
 
 pub async fn load_and_record_validator_history(
-    db_connection: &Pool<Postgres>,
+    store: &dyn SimulatorStore,
     rpc_client: &RpcClient,
     program_id: Pubkey,
+    insert_mode: InsertMode,
 ) -> Result<(), EpochRewardsTrackerError> {
     let current_epoch_info = rpc_client.get_epoch_info().await?;
     let last_finalized_epoch = current_epoch_info.epoch as u16;
@@ -57,13 +57,17 @@ pub async fn load_and_record_validator_history(
                 if x?.epoch == u16::MAX {
                     return None;
                 }
-                Some(ValidatorHistoryEntry::new(vote_pubkey.to_string(), *x?))
+                Some(ValidatorHistoryEntry::new(vote_pubkey.into(), *x?))
             })
             .collect();
         info!("Inserting {} entries for {}", entries.len(), vote_pubkey);
-        ValidatorHistoryEntry::bulk_insert(db_connection, entries).await?;
+        store
+            .insert_validator_history_entries(entries, insert_mode)
+            .await?;
         info!("Inserting ValidatorHistory for {}", vote_pubkey);
-        ValidatorHistory::bulk_insert(db_connection, vec![validator_history.into()]).await?;
+        store
+            .insert_validator_histories(vec![validator_history.into()], insert_mode)
+            .await?;
     }
     Ok(())
 }
@@ -6,11 +6,16 @@ use crate::{
     errors::EpochRewardsTrackerError,
 };
 use num_traits::FromPrimitive;
-use sqlx::{Pool, Postgres, types::BigDecimal};
-use stakenet_simulator_db::withdraw_and_deposit_sol::WithdrawAndDepositSol;
+use sqlx::types::BigDecimal;
+use stakenet_simulator_db::{
+    bulk_insert::InsertMode, store::SimulatorStore, withdraw_and_deposit_sol::WithdrawAndDepositSol,
+};
 use tracing::info;
 
-pub async fn withdraw_and_deposit_sol(db: &Pool<Postgres>) -> Result<(), EpochRewardsTrackerError> {
+pub async fn withdraw_and_deposit_sol(
+    store: &dyn SimulatorStore,
+    insert_mode: InsertMode,
+) -> Result<(), EpochRewardsTrackerError> {
     let execute_client = execute_dune_query(WITHDRAW_DEPOSIT_SOL_QUERY)
         .await
         .map_err(|_| EpochRewardsTrackerError::DuneApiError)?;
@@ -44,7 +49,9 @@ pub async fn withdraw_and_deposit_sol(db: &Pool<Postgres>) -> Result<(), EpochRe
         info!("No records to process.");
     } else {
         info!("Processing {} records...", withdraw_and_deposit_sols.len());
-        WithdrawAndDepositSol::bulk_insert(db, withdraw_and_deposit_sols).await?;
+        store
+            .insert_withdraw_and_deposit_sol(withdraw_and_deposit_sols, insert_mode)
+            .await?;
         info!("Processing complete. Records inserted/updated.");
     }
 
@@ -6,12 +6,18 @@ use crate::{
     errors::EpochRewardsTrackerError,
 };
 use num_traits::FromPrimitive;
-use sqlx::{Pool, Postgres, types::BigDecimal};
-use stakenet_simulator_db::withdraw_and_deposits_stake::WithdrawsAndDepositStakes;
+use sqlx::types::BigDecimal;
+use stakenet_simulator_db::{
+    bulk_insert::InsertMode, store::SimulatorStore,
+    withdraw_and_deposits_stake::WithdrawsAndDepositStakes,
+};
 use std::collections::HashMap;
 use tracing::info;
 
-pub async fn withdraw_and_deposits(db: &Pool<Postgres>) -> Result<(), EpochRewardsTrackerError> {
+pub async fn withdraw_and_deposits(
+    store: &dyn SimulatorStore,
+    insert_mode: InsertMode,
+) -> Result<(), EpochRewardsTrackerError> {
     let execute_client_deposit = execute_dune_query(DEPOSIT_STAKE_TRANSACTIONS_QUERY)
         .await
         .map_err(|_| EpochRewardsTrackerError::DuneApiError)?;
@@ -51,7 +57,7 @@ pub async fn withdraw_and_deposits(db: &Pool<Postgres>) -> Result<(), EpochRewar
     for ((epoch, validator), (deposit, withdraw)) in combined {
         merged.push(WithdrawsAndDepositStakes::new(
             epoch,
-            validator,
+            validator.parse()?,
             BigDecimal::from_f64(withdraw).unwrap_or_else(|| BigDecimal::from(0)),
             BigDecimal::from_f64(deposit).unwrap_or_else(|| BigDecimal::from(0)),
         ));
@@ -61,7 +67,9 @@ pub async fn withdraw_and_deposits(db: &Pool<Postgres>) -> Result<(), EpochRewar
         info!("No records to process.");
     } else {
         info!("Processing {} records...", merged.len());
-        WithdrawsAndDepositStakes::bulk_insert(db, merged).await?;
+        store
+            .insert_withdraw_and_deposit_stakes(merged, insert_mode)
+            .await?;
         info!("Processing complete. Records inserted/updated.");
     }
 
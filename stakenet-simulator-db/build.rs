@@ -0,0 +1,4 @@
+fn main() {
+    // Embedded migrations are only picked up again when the crate is rebuilt
+    println!("cargo:rerun-if-changed=../supabase/migrations");
+}
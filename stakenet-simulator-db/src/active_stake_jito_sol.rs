@@ -1,60 +1,46 @@
-use crate::{EpochBalanceResponse, big_decimal_u64::BigDecimalU64};
-use sqlx::{Error, FromRow, Pool, Postgres, QueryBuilder, types::BigDecimal};
+use crate::{
+    EpochBalanceResponse,
+    bulk_insert::{InsertMode, bulk_insert},
+    domain::Epoch,
+};
+use sqlx::{Error, FromRow, Pool, Postgres, types::BigDecimal};
 
 #[derive(FromRow)]
 pub struct ActiveStakeJitoSol {
-    #[sqlx(try_from = "BigDecimalU64")]
-    pub epoch: u64,
+    pub epoch: Epoch,
     pub balance: BigDecimal,
 }
 
 impl ActiveStakeJitoSol {
-    const NUM_FIELDS: u8 = 2;
-    // Based on the bind limit of postgres
-    const INSERT_CHUNK_SIZE: usize = 65534 / Self::NUM_FIELDS as usize;
-    const INSERT_QUERY: &str = "INSERT INTO active_stake_jito_sol (epoch,balance) VALUES ";
+    const COLUMNS: &[&str] = &["epoch", "balance"];
+    const CONFLICT_TARGET: &[&str] = &["epoch"];
 
     pub fn new(epoch: u64, balance: BigDecimal) -> Self {
-        Self { epoch, balance }
+        Self {
+            epoch: Epoch(epoch),
+            balance,
+        }
     }
 
     pub async fn bulk_insert(
         db_connection: &Pool<Postgres>,
         records: Vec<Self>,
+        mode: InsertMode,
     ) -> Result<(), Error> {
-        if records.is_empty() {
-            return Ok(());
-        }
-
-        let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(Self::INSERT_QUERY);
-        let mut num_records: usize = 0;
-
-        for record in records.into_iter() {
-            num_records += 1;
-            if num_records > 1 {
-                query_builder.push(", (");
-            } else {
-                query_builder.push("(");
-            }
-            let mut separated = query_builder.separated(", ");
-            separated.push_bind(BigDecimal::from(record.epoch));
-            separated.push_bind(record.balance);
-            separated.push_unseparated(") ");
-
-            if num_records >= Self::INSERT_CHUNK_SIZE {
-                query_builder.push(" ON CONFLICT (epoch) DO UPDATE SET balance = EXCLUDED.balance");
-                let query = query_builder.build();
-                query.execute(db_connection).await?;
-                num_records = 0;
-                query_builder = QueryBuilder::new(Self::INSERT_QUERY);
-            }
-        }
-
-        if num_records > 0 {
-            query_builder.push(" ON CONFLICT (epoch) DO UPDATE SET balance = EXCLUDED.balance");
-            let query = query_builder.build();
-            query.execute(db_connection).await?;
-        }
+        bulk_insert(
+            db_connection,
+            "active_stake_jito_sol",
+            Self::COLUMNS,
+            Self::CONFLICT_TARGET,
+            |record: &Self| record.epoch,
+            records,
+            mode,
+            |mut separated, record| {
+                separated.push_bind(record.epoch);
+                separated.push_bind(record.balance);
+            },
+        )
+        .await?;
         Ok(())
     }
 
@@ -73,8 +59,8 @@ impl ActiveStakeJitoSol {
         ";
 
         let result: Option<(BigDecimal, i64)> = sqlx::query_as(query)
-            .bind(BigDecimal::from(start_epoch))
-            .bind(BigDecimal::from(end_epoch))
+            .bind(Epoch(start_epoch))
+            .bind(Epoch(end_epoch))
             .fetch_optional(db_connection)
             .await?;
 
@@ -95,8 +81,8 @@ impl ActiveStakeJitoSol {
 
     pub async fn get_active_stakes_for_epoch_range(
         db_connection: &Pool<Postgres>,
-        start_epoch: i64,
-        end_epoch: i64,
+        start_epoch: u64,
+        end_epoch: u64,
     ) -> Result<Vec<ActiveStakeJitoSol>, Error> {
         let query = r#"
             SELECT *
@@ -105,8 +91,8 @@ impl ActiveStakeJitoSol {
         "#;
 
         let result: Vec<ActiveStakeJitoSol> = sqlx::query_as(query)
-            .bind(start_epoch)
-            .bind(end_epoch)
+            .bind(Epoch(start_epoch))
+            .bind(Epoch(end_epoch))
             .fetch_all(db_connection)
             .await?;
 
@@ -0,0 +1,67 @@
+use crate::{
+    bulk_insert::{InsertMode, bulk_insert},
+    domain::{Epoch, Lamports},
+};
+use sqlx::{Acquire, Error, FromRow, Pool, Postgres};
+
+#[derive(FromRow)]
+pub struct BacktestCycle {
+    pub run_id: String,
+    /// Position of the cycle in the run, starting at 0
+    #[sqlx(try_from = "i64")]
+    pub cycle_index: u32,
+    pub start_epoch: Epoch,
+    pub end_epoch: Epoch,
+    pub starting_total_lamports: Lamports,
+    pub ending_total_lamports: Lamports,
+}
+
+impl BacktestCycle {
+    const COLUMNS: &[&str] = &[
+        "run_id",
+        "cycle_index",
+        "start_epoch",
+        "end_epoch",
+        "starting_total_lamports",
+        "ending_total_lamports",
+    ];
+    const CONFLICT_TARGET: &[&str] = &["run_id", "cycle_index"];
+
+    pub async fn bulk_insert(
+        db_connection: impl Acquire<'_, Database = Postgres>,
+        records: Vec<Self>,
+        mode: InsertMode,
+    ) -> Result<(), Error> {
+        bulk_insert(
+            db_connection,
+            "backtest_cycles",
+            Self::COLUMNS,
+            Self::CONFLICT_TARGET,
+            |record: &Self| (record.run_id.clone(), record.cycle_index),
+            records,
+            mode,
+            |mut separated, record| {
+                separated.push_bind(record.run_id);
+                separated.push_bind(i64::from(record.cycle_index));
+                separated.push_bind(record.start_epoch);
+                separated.push_bind(record.end_epoch);
+                separated.push_bind(record.starting_total_lamports);
+                separated.push_bind(record.ending_total_lamports);
+            },
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn fetch_for_run(
+        db_connection: &Pool<Postgres>,
+        run_id: &str,
+    ) -> Result<Vec<Self>, Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT * FROM backtest_cycles WHERE run_id = $1 ORDER BY cycle_index",
+        )
+        .bind(run_id)
+        .fetch_all(db_connection)
+        .await
+    }
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{
+    Error, FromRow, Pool, Postgres,
+    types::{
+        Json,
+        chrono::{DateTime, Utc},
+    },
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "backtest_job_status", rename_all = "snake_case")]
+pub enum BacktestJobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+}
+
+#[derive(FromRow)]
+pub struct BacktestJob {
+    pub id: String,
+    pub status: BacktestJobStatus,
+    /// Request the job was submitted with
+    pub request: Json<Value>,
+    /// Reason the job failed
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl BacktestJob {
+    pub async fn insert(
+        db_connection: &Pool<Postgres>,
+        id: &str,
+        request: Value,
+    ) -> Result<Self, Error> {
+        sqlx::query_as::<_, Self>(
+            "INSERT INTO backtest_jobs (id, request) VALUES ($1, $2) RETURNING *",
+        )
+        .bind(id)
+        .bind(Json(request))
+        .fetch_one(db_connection)
+        .await
+    }
+
+    pub async fn fetch(db_connection: &Pool<Postgres>, id: &str) -> Result<Option<Self>, Error> {
+        sqlx::query_as::<_, Self>("SELECT * FROM backtest_jobs WHERE id = $1")
+            .bind(id)
+            .fetch_optional(db_connection)
+            .await
+    }
+
+    pub async fn update_status(
+        db_connection: &Pool<Postgres>,
+        id: &str,
+        status: BacktestJobStatus,
+        error: Option<String>,
+    ) -> Result<(), Error> {
+        sqlx::query(
+            "UPDATE backtest_jobs SET status = $2, error = $3, updated_at = NOW() WHERE id = $1",
+        )
+        .bind(id)
+        .bind(status)
+        .bind(error)
+        .execute(db_connection)
+        .await?;
+        Ok(())
+    }
+
+    /// Marks every job that is still queued or running as failed, used on startup for jobs that
+    /// were interrupted by a restart. Returns the number of jobs updated.
+    pub async fn fail_unfinished(
+        db_connection: &Pool<Postgres>,
+        error: &str,
+    ) -> Result<u64, Error> {
+        let result = sqlx::query(
+            "UPDATE backtest_jobs SET status = 'failed', error = $1, updated_at = NOW() WHERE status IN ('queued', 'running')",
+        )
+        .bind(error)
+        .execute(db_connection)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[derive(FromRow)]
+pub struct BacktestJobReport {
+    pub job_id: String,
+    pub report: Json<Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl BacktestJobReport {
+    pub async fn insert(
+        db_connection: &Pool<Postgres>,
+        job_id: &str,
+        report: Value,
+    ) -> Result<(), Error> {
+        sqlx::query(
+            "INSERT INTO backtest_job_reports (job_id, report) VALUES ($1, $2) ON CONFLICT (job_id) DO UPDATE SET report = EXCLUDED.report",
+        )
+        .bind(job_id)
+        .bind(Json(report))
+        .execute(db_connection)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn fetch(
+        db_connection: &Pool<Postgres>,
+        job_id: &str,
+    ) -> Result<Option<Self>, Error> {
+        sqlx::query_as::<_, Self>("SELECT * FROM backtest_job_reports WHERE job_id = $1")
+            .bind(job_id)
+            .fetch_optional(db_connection)
+            .await
+    }
+}
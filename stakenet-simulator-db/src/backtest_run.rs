@@ -0,0 +1,80 @@
+use crate::{big_decimal_u64::BigDecimalU64, domain::Epoch};
+use serde_json::Value;
+use sqlx::{
+    Error, Executor, FromRow, Pool, Postgres,
+    types::{
+        BigDecimal, Json,
+        chrono::{DateTime, Utc},
+    },
+};
+
+#[derive(FromRow)]
+pub struct BacktestRun {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    /// Git revision of the simulator that produced the run
+    pub git_revision: String,
+    /// Scenario the run was loaded from
+    pub scenario: Option<String>,
+    pub start_epoch: Epoch,
+    pub end_epoch: Epoch,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub seed: u64,
+    #[sqlx(try_from = "i64")]
+    pub number_of_validator_delegations: u32,
+    /// Name and settings of the delegation strategy of the run
+    pub delegation_strategy: String,
+    /// Name and settings of the scoring model of the run
+    pub scoring_model: String,
+    /// Effective steward parameters of the run
+    pub parameters: Json<Value>,
+    pub aggregated_apy: f64,
+    pub stake_utilization_ratio: f64,
+    pub final_apy: f64,
+}
+
+impl BacktestRun {
+    /// Inserts the run and returns its id
+    pub async fn insert(
+        db_connection: impl Executor<'_, Database = Postgres>,
+        record: Self,
+    ) -> Result<String, Error> {
+        sqlx::query_scalar(
+            "INSERT INTO backtest_runs (id, created_at, git_revision, scenario, start_epoch, end_epoch, seed, number_of_validator_delegations, delegation_strategy, scoring_model, parameters, aggregated_apy, stake_utilization_ratio, final_apy) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14) RETURNING id",
+        )
+        .bind(record.id)
+        .bind(record.created_at)
+        .bind(record.git_revision)
+        .bind(record.scenario)
+        .bind(record.start_epoch)
+        .bind(record.end_epoch)
+        .bind(BigDecimal::from(record.seed))
+        .bind(i64::from(record.number_of_validator_delegations))
+        .bind(record.delegation_strategy)
+        .bind(record.scoring_model)
+        .bind(record.parameters)
+        .bind(record.aggregated_apy)
+        .bind(record.stake_utilization_ratio)
+        .bind(record.final_apy)
+        .fetch_one(db_connection)
+        .await
+    }
+
+    pub async fn fetch(db_connection: &Pool<Postgres>, id: &str) -> Result<Option<Self>, Error> {
+        sqlx::query_as::<_, Self>("SELECT * FROM backtest_runs WHERE id = $1")
+            .bind(id)
+            .fetch_optional(db_connection)
+            .await
+    }
+
+    /// Returns the latest `limit` runs, most recent first
+    pub async fn fetch_recent(
+        db_connection: &Pool<Postgres>,
+        limit: i64,
+    ) -> Result<Vec<Self>, Error> {
+        sqlx::query_as::<_, Self>("SELECT * FROM backtest_runs ORDER BY created_at DESC LIMIT $1")
+            .bind(limit)
+            .fetch_all(db_connection)
+            .await
+    }
+}
@@ -0,0 +1,66 @@
+use crate::{
+    bulk_insert::{InsertMode, bulk_insert},
+    domain::{Lamports, VotePubkey},
+};
+use sqlx::{Acquire, Error, FromRow, Pool, Postgres};
+
+#[derive(FromRow)]
+pub struct BacktestValidatorResult {
+    pub run_id: String,
+    pub vote_pubkey: VotePubkey,
+    /// Number of steward cycles the validator was selected for delegation
+    #[sqlx(try_from = "i64")]
+    pub cycles_selected: u32,
+    /// Score of the last cycle the validator was selected in
+    pub last_score: f64,
+    /// Stake of the pool on the validator at the end of the run
+    pub final_stake_lamports: Lamports,
+}
+
+impl BacktestValidatorResult {
+    const COLUMNS: &[&str] = &[
+        "run_id",
+        "vote_pubkey",
+        "cycles_selected",
+        "last_score",
+        "final_stake_lamports",
+    ];
+    const CONFLICT_TARGET: &[&str] = &["run_id", "vote_pubkey"];
+
+    pub async fn bulk_insert(
+        db_connection: impl Acquire<'_, Database = Postgres>,
+        records: Vec<Self>,
+        mode: InsertMode,
+    ) -> Result<(), Error> {
+        bulk_insert(
+            db_connection,
+            "backtest_validator_results",
+            Self::COLUMNS,
+            Self::CONFLICT_TARGET,
+            |record: &Self| (record.run_id.clone(), record.vote_pubkey),
+            records,
+            mode,
+            |mut separated, record| {
+                separated.push_bind(record.run_id);
+                separated.push_bind(record.vote_pubkey);
+                separated.push_bind(i64::from(record.cycles_selected));
+                separated.push_bind(record.last_score);
+                separated.push_bind(record.final_stake_lamports);
+            },
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn fetch_for_run(
+        db_connection: &Pool<Postgres>,
+        run_id: &str,
+    ) -> Result<Vec<Self>, Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT * FROM backtest_validator_results WHERE run_id = $1 ORDER BY vote_pubkey",
+        )
+        .bind(run_id)
+        .fetch_all(db_connection)
+        .await
+    }
+}
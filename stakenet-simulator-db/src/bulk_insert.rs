@@ -0,0 +1,127 @@
+use sqlx::{Acquire, Error, Postgres, QueryBuilder, query_builder::Separated};
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    hash::Hash,
+};
+
+// Based on the bind limit of postgres
+const BIND_LIMIT: usize = 65534;
+
+/// How `bulk_insert` handles the records conflicting with rows already in the table
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InsertMode {
+    /// Keeps the rows already in the table
+    #[default]
+    InsertIgnore,
+    /// Overwrites the rows already in the table, to fix data when re-ingesting a range
+    Upsert,
+}
+
+impl InsertMode {
+    fn on_conflict(self, columns: &[&str], conflict_target: &[&str]) -> String {
+        let target = conflict_target.join(", ");
+        let updates: Vec<String> = columns
+            .iter()
+            .filter(|column| !conflict_target.contains(column))
+            .map(|column| format!("{column} = EXCLUDED.{column}"))
+            .collect();
+        match self {
+            Self::Upsert if !updates.is_empty() => {
+                format!(
+                    " ON CONFLICT ({target}) DO UPDATE SET {}",
+                    updates.join(", ")
+                )
+            }
+            _ => format!(" ON CONFLICT ({target}) DO NOTHING"),
+        }
+    }
+}
+
+/// Keeps the last of the records sharing a key, at the position of the first one
+fn dedup_by_key<T, K: Eq + Hash>(records: Vec<T>, key: impl Fn(&T) -> K) -> Vec<T> {
+    let mut positions = HashMap::with_capacity(records.len());
+    let mut deduped: Vec<T> = Vec::with_capacity(records.len());
+    for record in records {
+        match positions.entry(key(&record)) {
+            Entry::Occupied(entry) => deduped[*entry.get()] = record,
+            Entry::Vacant(entry) => {
+                entry.insert(deduped.len());
+                deduped.push(record);
+            }
+        }
+    }
+    deduped
+}
+
+/// Inserts the records in chunks that stay under the bind limit, applying `mode` to every chunk.
+/// `conflict_key` returns the values of the `conflict_target` columns of a record, and
+/// `push_record` binds the fields of a record in the order of `columns`. `db_connection` can be a
+/// pool or a transaction the chunks are written in. Returns the number of rows written.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn bulk_insert<T: Send, K: Eq + Hash>(
+    db_connection: impl Acquire<'_, Database = Postgres>,
+    table: &str,
+    columns: &[&str],
+    conflict_target: &[&str],
+    conflict_key: impl Fn(&T) -> K,
+    records: Vec<T>,
+    mode: InsertMode,
+    mut push_record: impl FnMut(Separated<'_, '_, Postgres, &'static str>, T) + Send,
+) -> Result<u64, Error> {
+    let chunk_size = BIND_LIMIT / columns.len();
+    let insert_query = format!("INSERT INTO {table} ({}) ", columns.join(","));
+    let on_conflict = mode.on_conflict(columns, conflict_target);
+    let mut connection = db_connection.acquire().await?;
+    // Postgres can't update the same row twice in one statement, so only the last of the records
+    // sharing a key is upserted
+    let records = match mode {
+        InsertMode::Upsert => dedup_by_key(records, conflict_key),
+        InsertMode::InsertIgnore => records,
+    };
+
+    let mut rows_affected = 0;
+    let mut records = records.into_iter().peekable();
+    while records.peek().is_some() {
+        let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(&insert_query);
+        query_builder.push_values(records.by_ref().take(chunk_size), &mut push_record);
+        query_builder.push(&on_conflict);
+        rows_affected += query_builder
+            .build()
+            .execute(&mut *connection)
+            .await?
+            .rows_affected();
+    }
+    Ok(rows_affected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_on_conflict() {
+        let columns = ["epoch", "vote_pubkey", "balance"];
+        let conflict_target = ["epoch", "vote_pubkey"];
+        assert_eq!(
+            InsertMode::InsertIgnore.on_conflict(&columns, &conflict_target),
+            " ON CONFLICT (epoch, vote_pubkey) DO NOTHING"
+        );
+        assert_eq!(
+            InsertMode::Upsert.on_conflict(&columns, &conflict_target),
+            " ON CONFLICT (epoch, vote_pubkey) DO UPDATE SET balance = EXCLUDED.balance"
+        );
+        assert_eq!(
+            InsertMode::Upsert.on_conflict(&columns[..2], &conflict_target),
+            " ON CONFLICT (epoch, vote_pubkey) DO NOTHING"
+        );
+    }
+
+    #[test]
+    fn test_dedup_by_key() {
+        let records = vec![(740, "a", 1), (741, "a", 2), (740, "a", 3), (740, "b", 4)];
+        assert_eq!(
+            dedup_by_key(records, |record| (record.0, record.1)),
+            vec![(740, "a", 3), (741, "a", 2), (740, "b", 4)]
+        );
+    }
+}
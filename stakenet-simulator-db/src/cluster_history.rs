@@ -1,11 +1,17 @@
 use std::cmp::Ordering;
 
-use crate::{big_decimal_u64::BigDecimalU64, cluster_history_entry::ClusterHistoryEntry};
+use crate::{
+    big_decimal_u64::BigDecimalU64, cluster_history_entry::ClusterHistoryEntry,
+    error::StakenetSimulatorDbError,
+};
 use sqlx::{
     Error as SqlxError, Pool, Postgres, postgres::PgQueryResult, prelude::FromRow,
     types::BigDecimal,
 };
-use validator_history::{CircBufCluster, ClusterHistory as JitoClusterHistory};
+use validator_history::{
+    CircBufCluster, ClusterHistory as JitoClusterHistory,
+    ClusterHistoryEntry as JitoClusterHistoryEntry,
+};
 
 #[derive(FromRow)]
 pub struct ClusterHistory {
@@ -57,7 +63,7 @@ impl ClusterHistory {
     pub fn convert_to_jito_cluster_history(
         self,
         entries: Vec<ClusterHistoryEntry>,
-    ) -> JitoClusterHistory {
+    ) -> Result<JitoClusterHistory, StakenetSimulatorDbError> {
         let mut entries = entries;
         let mut cluster_history = JitoClusterHistory {
             struct_version: self.struct_version,
@@ -72,23 +78,24 @@ impl ClusterHistory {
         entries.sort_by(|a, b| a.epoch.cmp(&b.epoch));
         // Loop through sorted entries insert into ClusterHistory
         for entry in entries.into_iter() {
+            let entry = JitoClusterHistoryEntry::try_from(entry)?;
             if let Some(last_entry) = cluster_history.history.last_mut() {
                 match last_entry.epoch.cmp(&entry.epoch) {
                     Ordering::Equal => {
-                        *last_entry = entry.into();
+                        *last_entry = entry;
                     }
                     Ordering::Greater => {
-                        *last_entry = entry.into();
+                        *last_entry = entry;
                     }
                     Ordering::Less => {
-                        cluster_history.history.push(entry.into());
+                        cluster_history.history.push(entry);
                     }
                 }
             } else {
-                cluster_history.history.push(entry.into());
+                cluster_history.history.push(entry);
             }
         }
 
-        cluster_history
+        Ok(cluster_history)
     }
 }
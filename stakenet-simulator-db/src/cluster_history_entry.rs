@@ -1,13 +1,17 @@
-use crate::big_decimal_u64::BigDecimalU64;
+use crate::{
+    big_decimal_u64::BigDecimalU64,
+    bulk_insert::{InsertMode, bulk_insert},
+    domain::Epoch,
+    error::StakenetSimulatorDbError,
+};
 
-use sqlx::{Error as SqlxError, Pool, Postgres, QueryBuilder, prelude::FromRow, types::BigDecimal};
+use sqlx::{Error as SqlxError, Pool, Postgres, prelude::FromRow, types::BigDecimal};
 use validator_history::ClusterHistoryEntry as JitoClusterHistoryEntry;
 
 #[derive(FromRow)]
 pub struct ClusterHistoryEntry {
     /// Epoch number
-    #[sqlx(try_from = "i32")]
-    pub epoch: u16,
+    pub epoch: Epoch,
     /// Total number of blocks in the epoch
     #[sqlx(try_from = "i64")]
     pub total_blocks: u32,
@@ -19,71 +23,54 @@ pub struct ClusterHistoryEntry {
 impl From<JitoClusterHistoryEntry> for ClusterHistoryEntry {
     fn from(value: JitoClusterHistoryEntry) -> Self {
         Self {
-            epoch: value.epoch,
+            epoch: value.epoch.into(),
             total_blocks: value.total_blocks,
             epoch_start_timestamp: value.epoch_start_timestamp,
         }
     }
 }
 
-impl From<ClusterHistoryEntry> for JitoClusterHistoryEntry {
-    fn from(val: ClusterHistoryEntry) -> Self {
-        JitoClusterHistoryEntry {
+impl TryFrom<ClusterHistoryEntry> for JitoClusterHistoryEntry {
+    type Error = StakenetSimulatorDbError;
+
+    fn try_from(val: ClusterHistoryEntry) -> Result<Self, Self::Error> {
+        Ok(JitoClusterHistoryEntry {
             total_blocks: val.total_blocks,
-            epoch: val.epoch,
+            epoch: val
+                .epoch
+                .try_into()
+                .map_err(|_| StakenetSimulatorDbError::DecodeError(String::from("epoch")))?,
             padding0: [0u8; 2],
             epoch_start_timestamp: val.epoch_start_timestamp,
             padding: [0u8; 240],
-        }
+        })
     }
 }
 
 impl ClusterHistoryEntry {
-    const NUM_FIELDS: u8 = 3;
-    // Based on the bind limit of postgres
-    const INSERT_CHUNK_SIZE: usize = 65534 / Self::NUM_FIELDS as usize;
-    const INSERT_QUERY: &str =
-        "INSERT INTO cluster_history_entries (epoch,total_blocks,epoch_start_timestamp) VALUES ";
+    const COLUMNS: &[&str] = &["epoch", "total_blocks", "epoch_start_timestamp"];
+    const CONFLICT_TARGET: &[&str] = &["epoch"];
 
     pub async fn bulk_insert(
         db_connection: &Pool<Postgres>,
         records: Vec<Self>,
+        mode: InsertMode,
     ) -> Result<(), SqlxError> {
-        if records.is_empty() {
-            return Ok(());
-        }
-
-        let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(Self::INSERT_QUERY);
-        let mut num_records: usize = 0;
-
-        for record in records.into_iter() {
-            num_records += 1;
-            if num_records > 1 {
-                query_builder.push(", (");
-            } else {
-                query_builder.push("(");
-            }
-            let mut separated = query_builder.separated(", ");
-            separated.push_bind(i32::from(record.epoch));
-            separated.push_bind(BigDecimal::from(record.total_blocks));
-            separated.push_bind(BigDecimal::from(record.epoch_start_timestamp));
-
-            separated.push_unseparated(") ");
-
-            if num_records >= Self::INSERT_CHUNK_SIZE {
-                query_builder.push(" ON CONFLICT (epoch) DO NOTHING");
-                let query = query_builder.build();
-                query.execute(db_connection).await?;
-                num_records = 0;
-                query_builder = QueryBuilder::new(Self::INSERT_QUERY);
-            }
-        }
-
-        if num_records > 0 {
-            query_builder.push(" ON CONFLICT (epoch) DO NOTHING");
-            let query = query_builder.build();
-            query.execute(db_connection).await?;
-        }
+        bulk_insert(
+            db_connection,
+            "cluster_history_entries",
+            Self::COLUMNS,
+            Self::CONFLICT_TARGET,
+            |record: &Self| record.epoch,
+            records,
+            mode,
+            |mut separated, record| {
+                separated.push_bind(record.epoch);
+                separated.push_bind(BigDecimal::from(record.total_blocks));
+                separated.push_bind(BigDecimal::from(record.epoch_start_timestamp));
+            },
+        )
+        .await?;
         Ok(())
     }
 
@@ -0,0 +1,253 @@
+use crate::big_decimal_u64::BigDecimalU64;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use solana_sdk::pubkey::Pubkey;
+use sqlx::{
+    Decode, Encode, Postgres, Type, ValueRef,
+    encode::IsNull,
+    error::BoxDynError,
+    postgres::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueRef},
+    types::BigDecimal,
+};
+use std::{fmt, num::TryFromIntError, str::FromStr};
+
+/// Integer columns the `u_64` values are also stored in, e.g. the epochs of
+/// `validator_history_entries`
+fn is_integer(ty: &PgTypeInfo) -> bool {
+    *ty == PgTypeInfo::with_name("INT2")
+        || *ty == PgTypeInfo::with_name("INT4")
+        || *ty == PgTypeInfo::with_name("INT8")
+}
+
+/// Implements the conversions and the sqlx traits of a `u64` stored in the `u_64` domain
+macro_rules! u64_domain {
+    ($name:ident) => {
+        impl From<u64> for $name {
+            fn from(value: u64) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$name> for u64 {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl From<$name> for BigDecimal {
+            fn from(value: $name) -> Self {
+                BigDecimal::from(value.0)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl Type<Postgres> for $name {
+            fn type_info() -> PgTypeInfo {
+                PgTypeInfo::with_name("NUMERIC")
+            }
+
+            fn compatible(ty: &PgTypeInfo) -> bool {
+                *ty == PgTypeInfo::with_name("NUMERIC") || is_integer(ty)
+            }
+        }
+
+        impl Encode<'_, Postgres> for $name {
+            fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+                Encode::<Postgres>::encode_by_ref(&BigDecimal::from(self.0), buf)
+            }
+        }
+
+        impl<'r> Decode<'r, Postgres> for $name {
+            fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+                if is_integer(&value.type_info()) {
+                    let value: i64 = Decode::<Postgres>::decode(value)?;
+                    return Ok(Self(u64::try_from(value)?));
+                }
+                let value: BigDecimalU64 = Decode::<Postgres>::decode(value)?;
+                Ok(Self(value.into()))
+            }
+        }
+    };
+}
+
+/// An epoch. The simulator and the validator history program track epochs as `u16`, the tables
+/// as `u_64`.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Epoch(pub u64);
+
+u64_domain!(Epoch);
+
+impl From<u16> for Epoch {
+    fn from(value: u16) -> Self {
+        Self(value.into())
+    }
+}
+
+impl TryFrom<Epoch> for u16 {
+    type Error = TryFromIntError;
+
+    fn try_from(value: Epoch) -> Result<Self, Self::Error> {
+        u16::try_from(value.0)
+    }
+}
+
+/// An amount of lamports
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Lamports(pub u64);
+
+u64_domain!(Lamports);
+
+/// A commission in basis points, stored as a small integer
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Bps(pub u16);
+
+impl From<u16> for Bps {
+    fn from(value: u16) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Bps> for u16 {
+    fn from(value: Bps) -> Self {
+        value.0
+    }
+}
+
+impl fmt::Display for Bps {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Type<Postgres> for Bps {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("INT4")
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        is_integer(ty)
+    }
+}
+
+impl Encode<'_, Postgres> for Bps {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        Encode::<Postgres>::encode_by_ref(&i32::from(self.0), buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for Bps {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        let value: i64 = Decode::<Postgres>::decode(value)?;
+        Ok(Self(u16::try_from(value)?))
+    }
+}
+
+/// The vote account of a validator, stored as base58 in the `solana_pubkey` domain
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VotePubkey(pub Pubkey);
+
+impl From<Pubkey> for VotePubkey {
+    fn from(value: Pubkey) -> Self {
+        Self(value)
+    }
+}
+
+impl From<VotePubkey> for Pubkey {
+    fn from(value: VotePubkey) -> Self {
+        value.0
+    }
+}
+
+impl FromStr for VotePubkey {
+    type Err = <Pubkey as FromStr>::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Pubkey::from_str(s).map(Self)
+    }
+}
+
+impl fmt::Display for VotePubkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Serialize for VotePubkey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for VotePubkey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl Type<Postgres> for VotePubkey {
+    fn type_info() -> PgTypeInfo {
+        <String as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <String as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl PgHasArrayType for VotePubkey {
+    fn array_type_info() -> PgTypeInfo {
+        <String as PgHasArrayType>::array_type_info()
+    }
+
+    fn array_compatible(ty: &PgTypeInfo) -> bool {
+        <String as PgHasArrayType>::array_compatible(ty)
+    }
+}
+
+impl Encode<'_, Postgres> for VotePubkey {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        Encode::<Postgres>::encode_by_ref(&self.0.to_string(), buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for VotePubkey {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        let value: &str = Decode::<Postgres>::decode(value)?;
+        Ok(value.parse()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_domain_types() {
+        assert_eq!(u16::try_from(Epoch::from(800u16)), Ok(800));
+        assert!(u16::try_from(Epoch(u64::from(u16::MAX) + 1)).is_err());
+
+        let vote_pubkey = VotePubkey(Pubkey::new_unique());
+        let json = serde_json::to_string(&vote_pubkey).unwrap();
+        assert_eq!(json, format!("\"{vote_pubkey}\""));
+        assert_eq!(
+            serde_json::from_str::<VotePubkey>(&json).unwrap(),
+            vote_pubkey
+        );
+        assert_eq!(serde_json::to_string(&Lamports(5)).unwrap(), "5");
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{big_decimal_u64::BigDecimalU64, domain::Epoch};
+use solana_sdk::epoch_schedule::EpochSchedule as SolanaEpochSchedule;
+use sqlx::{Error, FromRow, Pool, Postgres, postgres::PgQueryResult, types::BigDecimal};
+
+/// Epoch schedule of the cluster, stored as a single row
+#[derive(Clone, Debug, FromRow)]
+pub struct EpochSchedule {
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub slots_per_epoch: u64,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub leader_schedule_slot_offset: u64,
+    pub warmup: bool,
+    pub first_normal_epoch: Epoch,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub first_normal_slot: u64,
+}
+
+impl From<SolanaEpochSchedule> for EpochSchedule {
+    fn from(value: SolanaEpochSchedule) -> Self {
+        Self {
+            slots_per_epoch: value.slots_per_epoch,
+            leader_schedule_slot_offset: value.leader_schedule_slot_offset,
+            warmup: value.warmup,
+            first_normal_epoch: value.first_normal_epoch.into(),
+            first_normal_slot: value.first_normal_slot,
+        }
+    }
+}
+
+impl From<EpochSchedule> for SolanaEpochSchedule {
+    fn from(value: EpochSchedule) -> Self {
+        SolanaEpochSchedule {
+            slots_per_epoch: value.slots_per_epoch,
+            leader_schedule_slot_offset: value.leader_schedule_slot_offset,
+            warmup: value.warmup,
+            first_normal_epoch: value.first_normal_epoch.into(),
+            first_normal_slot: value.first_normal_slot,
+        }
+    }
+}
+
+impl EpochSchedule {
+    pub async fn upsert(
+        db_connection: &Pool<Postgres>,
+        record: Self,
+    ) -> Result<PgQueryResult, Error> {
+        sqlx::query(
+            "INSERT INTO epoch_schedules (id, slots_per_epoch, leader_schedule_slot_offset, warmup, \
+            first_normal_epoch, first_normal_slot) VALUES ($1, $2, $3, $4, $5, $6) \
+            ON CONFLICT (id) DO UPDATE SET \
+            slots_per_epoch = EXCLUDED.slots_per_epoch, \
+            leader_schedule_slot_offset = EXCLUDED.leader_schedule_slot_offset, \
+            warmup = EXCLUDED.warmup, \
+            first_normal_epoch = EXCLUDED.first_normal_epoch, \
+            first_normal_slot = EXCLUDED.first_normal_slot",
+        )
+        .bind(1)
+        .bind(BigDecimal::from(record.slots_per_epoch))
+        .bind(BigDecimal::from(record.leader_schedule_slot_offset))
+        .bind(record.warmup)
+        .bind(record.first_normal_epoch)
+        .bind(BigDecimal::from(record.first_normal_slot))
+        .execute(db_connection)
+        .await
+    }
+
+    /// Fetches the stored epoch schedule, if the tracker recorded it
+    pub async fn fetch(db_connection: &Pool<Postgres>) -> Result<Option<Self>, Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT slots_per_epoch, leader_schedule_slot_offset, warmup, first_normal_epoch, \
+            first_normal_slot FROM epoch_schedules WHERE id = 1",
+        )
+        .fetch_optional(db_connection)
+        .await
+    }
+}
@@ -4,4 +4,34 @@ use thiserror::Error;
 pub enum StakenetSimulatorDbError {
     #[error("Error decodign column {0}")]
     DecodeError(String),
+
+    #[error("SqlxError: {0}")]
+    SqlxError(#[from] sqlx::Error),
+
+    #[error("Migration error: {0}")]
+    MigrateError(#[from] sqlx::migrate::MigrateError),
+
+    #[error(
+        "Database schema is at version {version} but {latest} is expected, {pending} migrations are pending. Run the migrate command to apply them"
+    )]
+    SchemaBehind {
+        version: i64,
+        latest: i64,
+        pending: usize,
+    },
+
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Serde JSON error: {0}")]
+    SerdeJsonError(#[from] serde_json::Error),
+
+    #[error("Invalid snapshot: {0}")]
+    InvalidSnapshot(String),
+
+    #[error("Error joining tokio task: {0}")]
+    TaskJoinError(#[from] tokio::task::JoinError),
+
+    #[error("Snapshot checksum mismatch, expected {expected} but the data hashes to {actual}")]
+    SnapshotChecksumMismatch { expected: String, actual: String },
 }
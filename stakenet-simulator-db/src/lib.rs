@@ -1,18 +1,31 @@
 use sqlx::types::BigDecimal;
 
 pub mod active_stake_jito_sol;
+pub mod backtest_cycle;
+pub mod backtest_job;
+pub mod backtest_run;
+pub mod backtest_validator_result;
 mod big_decimal_u64;
+pub mod bulk_insert;
 pub mod cluster_history;
 pub mod cluster_history_entry;
+pub mod domain;
 pub mod epoch_priority_fees;
 pub mod epoch_rewards;
+pub mod epoch_schedule;
 pub mod error;
 pub mod inactive_stake_jito_sol;
 pub mod inflation_rewards;
 mod macros;
+pub mod migrations;
 pub mod stake_accounts;
+pub mod steward_blacklist;
+pub mod steward_config_snapshot;
+pub mod steward_delegation;
+pub mod store;
 pub mod validator_history;
 pub mod validator_history_entry;
+pub mod validator_history_repair;
 pub mod withdraw_and_deposit_sol;
 pub mod withdraw_and_deposits_stake;
 
@@ -0,0 +1,157 @@
+use crate::error::StakenetSimulatorDbError;
+use sqlx::{
+    Pool, Postgres,
+    migrate::{Migrate, Migrator},
+};
+use std::collections::HashSet;
+use tracing::info;
+
+/// Migrations of `supabase/migrations`, embedded so the binaries can set up a database without
+/// the supabase CLI
+pub static MIGRATOR: Migrator = sqlx::migrate!("../supabase/migrations");
+
+/// Roles the migrations grant table access to. Supabase creates them, on plain Postgres they are
+/// created before migrating so the grants apply.
+const SUPABASE_ROLES: [&str; 3] = ["anon", "authenticated", "service_role"];
+
+/// Version of the latest embedded migration
+pub fn latest_schema_version() -> i64 {
+    MIGRATOR
+        .iter()
+        .map(|migration| migration.version)
+        .max()
+        .unwrap_or_default()
+}
+
+/// Applies the pending migrations. Migrations the supabase CLI already applied are recorded as
+/// applied first, so existing databases are not migrated twice.
+pub async fn migrate(db_connection: &Pool<Postgres>) -> Result<(), StakenetSimulatorDbError> {
+    for role in SUPABASE_ROLES {
+        sqlx::query(&format!(
+            "DO $$ BEGIN IF NOT EXISTS (SELECT FROM pg_roles WHERE rolname = '{role}') THEN CREATE ROLE {role} NOLOGIN; END IF; END $$"
+        ))
+        .execute(db_connection)
+        .await?;
+    }
+    record_supabase_migrations(db_connection).await?;
+    MIGRATOR.run(db_connection).await?;
+    info!("Database schema is at version {}", latest_schema_version());
+    Ok(())
+}
+
+/// Checks that every embedded migration was applied, through `migrate` or the supabase CLI
+pub async fn check_schema_version(
+    db_connection: &Pool<Postgres>,
+) -> Result<(), StakenetSimulatorDbError> {
+    let mut applied = supabase_migration_versions(db_connection).await?;
+    if table_exists(db_connection, "_sqlx_migrations").await? {
+        let versions: Vec<i64> =
+            sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success")
+                .fetch_all(db_connection)
+                .await?;
+        applied.extend(versions);
+    }
+
+    let pending = MIGRATOR
+        .iter()
+        .filter(|migration| !applied.contains(&migration.version))
+        .count();
+    if pending > 0 {
+        return Err(StakenetSimulatorDbError::SchemaBehind {
+            version: applied.into_iter().max().unwrap_or_default(),
+            latest: latest_schema_version(),
+            pending,
+        });
+    }
+    Ok(())
+}
+
+/// Records the migrations applied by the supabase CLI in the migrations table of sqlx
+async fn record_supabase_migrations(
+    db_connection: &Pool<Postgres>,
+) -> Result<(), StakenetSimulatorDbError> {
+    let supabase_versions = supabase_migration_versions(db_connection).await?;
+    if supabase_versions.is_empty() {
+        return Ok(());
+    }
+
+    let mut connection = db_connection.acquire().await?;
+    connection.ensure_migrations_table().await?;
+    let applied: HashSet<i64> = connection
+        .list_applied_migrations()
+        .await?
+        .into_iter()
+        .map(|migration| migration.version)
+        .collect();
+    for migration in MIGRATOR.iter() {
+        if !supabase_versions.contains(&migration.version) || applied.contains(&migration.version) {
+            continue;
+        }
+        sqlx::query(
+            "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time) VALUES ($1, $2, TRUE, $3, 0)",
+        )
+        .bind(migration.version)
+        .bind(migration.description.as_ref())
+        .bind(migration.checksum.as_ref())
+        .execute(&mut *connection)
+        .await?;
+        info!(
+            "Recorded migration {} applied by the supabase CLI",
+            migration.version
+        );
+    }
+    Ok(())
+}
+
+/// Versions of the migrations the supabase CLI applied, empty when it never ran
+async fn supabase_migration_versions(
+    db_connection: &Pool<Postgres>,
+) -> Result<HashSet<i64>, sqlx::Error> {
+    if !table_exists(db_connection, "supabase_migrations.schema_migrations").await? {
+        return Ok(HashSet::new());
+    }
+    let versions: Vec<String> =
+        sqlx::query_scalar("SELECT version FROM supabase_migrations.schema_migrations")
+            .fetch_all(db_connection)
+            .await?;
+    Ok(versions
+        .into_iter()
+        .filter_map(|version| version.parse().ok())
+        .collect())
+}
+
+async fn table_exists(db_connection: &Pool<Postgres>, table: &str) -> Result<bool, sqlx::Error> {
+    let table: Option<String> = sqlx::query_scalar("SELECT to_regclass($1)::text")
+        .bind(table)
+        .fetch_one(db_connection)
+        .await?;
+    Ok(table.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedded_migrations() {
+        let files = std::fs::read_dir(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../supabase/migrations"
+        ))
+        .unwrap()
+        .filter(|entry| {
+            entry
+                .as_ref()
+                .unwrap()
+                .path()
+                .extension()
+                .is_some_and(|extension| extension == "sql")
+        })
+        .count();
+        assert_eq!(MIGRATOR.iter().count(), files);
+
+        let versions: Vec<i64> = MIGRATOR.iter().map(|migration| migration.version).collect();
+        assert!(versions.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(latest_schema_version(), *versions.last().unwrap());
+    }
+}
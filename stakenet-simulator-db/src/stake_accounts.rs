@@ -1,7 +1,10 @@
 use solana_sdk::{pubkey::Pubkey, stake::state::StakeStateV2};
-use sqlx::{Error as SqlxError, FromRow, Pool, Postgres, QueryBuilder, types::BigDecimal};
+use sqlx::{Error as SqlxError, FromRow, Pool, Postgres, types::BigDecimal};
 
-use crate::stake_accounts;
+use crate::{
+    bulk_insert::{InsertMode, bulk_insert},
+    stake_accounts,
+};
 
 #[derive(Default)]
 pub struct StakeAccount {
@@ -65,61 +68,55 @@ impl From<(Pubkey, StakeStateV2)> for StakeAccount {
 }
 
 impl StakeAccount {
-    const NUM_FIELDS: u8 = 14;
-    // Based on the bind limit of postgres
-    const INSERT_CHUNK_SIZE: usize = 65534 / Self::NUM_FIELDS as usize;
-    const INSERT_QUERY: &str = "INSERT INTO stake_accounts (pubkey,discriminator,rent_exempt_reserve,authorized_staker,authorized_withdrawer,lockup_unix_timestamp,lockup_epoch,lockup_custodian,delegation_voter_pubkey,delegation_stake,delegation_activation_epoch,delegation_deactivation_epoch,delegation_warmup_cooldown_rate,credits_observed) VALUES ";
+    const COLUMNS: &[&str] = &[
+        "pubkey",
+        "discriminator",
+        "rent_exempt_reserve",
+        "authorized_staker",
+        "authorized_withdrawer",
+        "lockup_unix_timestamp",
+        "lockup_epoch",
+        "lockup_custodian",
+        "delegation_voter_pubkey",
+        "delegation_stake",
+        "delegation_activation_epoch",
+        "delegation_deactivation_epoch",
+        "delegation_warmup_cooldown_rate",
+        "credits_observed",
+    ];
+    const CONFLICT_TARGET: &[&str] = &["pubkey"];
 
     pub async fn bulk_insert(
         db_connection: &Pool<Postgres>,
         records: Vec<Self>,
+        mode: InsertMode,
     ) -> Result<(), SqlxError> {
-        if records.is_empty() {
-            return Ok(());
-        }
-
-        let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(Self::INSERT_QUERY);
-        let mut num_records: usize = 0;
-
-        for record in records.into_iter() {
-            num_records += 1;
-            if num_records > 1 {
-                query_builder.push(", (");
-            } else {
-                query_builder.push("(");
-            }
-            let mut separated = query_builder.separated(", ");
-            separated.push_bind(record.pubkey);
-            separated.push_bind(i32::try_from(record.discriminator).unwrap());
-            separated.push_bind(record.rent_exempt_reserve.map(BigDecimal::from));
-            separated.push_bind(record.authorized_staker);
-            separated.push_bind(record.authorized_withdrawer);
-            separated.push_bind(record.lockup_unix_timestamp);
-            separated.push_bind(record.lockup_epoch.map(BigDecimal::from));
-            separated.push_bind(record.lockup_custodian);
-            separated.push_bind(record.delegation_voter_pubkey);
-            separated.push_bind(record.delegation_stake.map(BigDecimal::from));
-            separated.push_bind(record.delegation_activation_epoch.map(BigDecimal::from));
-            separated.push_bind(record.delegation_deactivation_epoch.map(BigDecimal::from));
-            separated.push_bind(record.delegation_warmup_cooldown_rate);
-            separated.push_bind(record.credits_observed.map(BigDecimal::from));
-
-            separated.push_unseparated(") ");
-
-            if num_records >= Self::INSERT_CHUNK_SIZE {
-                query_builder.push(" ON CONFLICT (pubkey) DO NOTHING");
-                let query = query_builder.build();
-                query.execute(db_connection).await?;
-                num_records = 0;
-                query_builder = QueryBuilder::new(Self::INSERT_QUERY);
-            }
-        }
-
-        if num_records > 0 {
-            query_builder.push(" ON CONFLICT (pubkey) DO NOTHING");
-            let query = query_builder.build();
-            query.execute(db_connection).await?;
-        }
+        bulk_insert(
+            db_connection,
+            "stake_accounts",
+            Self::COLUMNS,
+            Self::CONFLICT_TARGET,
+            |record: &Self| record.pubkey.clone(),
+            records,
+            mode,
+            |mut separated, record| {
+                separated.push_bind(record.pubkey);
+                separated.push_bind(i32::try_from(record.discriminator).unwrap());
+                separated.push_bind(record.rent_exempt_reserve.map(BigDecimal::from));
+                separated.push_bind(record.authorized_staker);
+                separated.push_bind(record.authorized_withdrawer);
+                separated.push_bind(record.lockup_unix_timestamp);
+                separated.push_bind(record.lockup_epoch.map(BigDecimal::from));
+                separated.push_bind(record.lockup_custodian);
+                separated.push_bind(record.delegation_voter_pubkey);
+                separated.push_bind(record.delegation_stake.map(BigDecimal::from));
+                separated.push_bind(record.delegation_activation_epoch.map(BigDecimal::from));
+                separated.push_bind(record.delegation_deactivation_epoch.map(BigDecimal::from));
+                separated.push_bind(record.delegation_warmup_cooldown_rate);
+                separated.push_bind(record.credits_observed.map(BigDecimal::from));
+            },
+        )
+        .await?;
         Ok(())
     }
 
@@ -0,0 +1,38 @@
+use crate::domain::{Epoch, VotePubkey};
+use sqlx::{Error, FromRow, Pool, Postgres, postgres::PgQueryResult};
+
+/// Vote accounts blacklisted by the steward config during an epoch
+#[derive(Clone, Debug, FromRow)]
+pub struct StewardBlacklist {
+    pub epoch: Epoch,
+    pub vote_pubkeys: Vec<VotePubkey>,
+}
+
+impl StewardBlacklist {
+    pub async fn upsert(
+        db_connection: &Pool<Postgres>,
+        record: Self,
+    ) -> Result<PgQueryResult, Error> {
+        sqlx::query(
+            "INSERT INTO steward_blacklists (epoch, vote_pubkeys) VALUES ($1, $2) \
+            ON CONFLICT (epoch) DO UPDATE SET vote_pubkeys = EXCLUDED.vote_pubkeys",
+        )
+        .bind(record.epoch)
+        .bind(record.vote_pubkeys)
+        .execute(db_connection)
+        .await
+    }
+
+    /// Fetches every blacklist recorded before `end_epoch`, sorted by epoch
+    pub async fn fetch_before(
+        db_connection: &Pool<Postgres>,
+        end_epoch: u64,
+    ) -> Result<Vec<Self>, Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT * FROM steward_blacklists WHERE epoch < $1 ORDER BY epoch ASC",
+        )
+        .bind(Epoch(end_epoch))
+        .fetch_all(db_connection)
+        .await
+    }
+}
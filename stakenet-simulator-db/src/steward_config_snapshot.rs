@@ -0,0 +1,56 @@
+use crate::{bulk_insert::InsertMode, domain::Epoch};
+use solana_sdk::{pubkey, pubkey::Pubkey};
+use sqlx::{Error, FromRow, Pool, Postgres, postgres::PgQueryResult};
+
+/// Steward config of the JitoSOL stake pool
+pub const STEWARD_CONFIG_PUBKEY: Pubkey = pubkey!("jitoVjT9jRUyeXHzvCwzPgHj7yWNRhLcUoXtes4wtjv");
+
+/// Raw data of the steward config account as it was during an epoch
+#[derive(FromRow)]
+pub struct StewardConfigSnapshot {
+    pub epoch: Epoch,
+    pub config_data: Vec<u8>,
+}
+
+impl StewardConfigSnapshot {
+    /// Records the config of an epoch. The config already recorded for the epoch is kept unless
+    /// `mode` is `Upsert`, so the history isn't overwritten by later runs.
+    pub async fn insert(
+        db_connection: &Pool<Postgres>,
+        record: Self,
+        mode: InsertMode,
+    ) -> Result<PgQueryResult, Error> {
+        let on_conflict = match mode {
+            InsertMode::InsertIgnore => "DO NOTHING",
+            InsertMode::Upsert => "DO UPDATE SET config_data = EXCLUDED.config_data",
+        };
+        sqlx::query(&format!(
+            "INSERT INTO steward_config_snapshots (epoch, config_data) VALUES ($1, $2) \
+            ON CONFLICT (epoch) {on_conflict}"
+        ))
+        .bind(record.epoch)
+        .bind(record.config_data)
+        .execute(db_connection)
+        .await
+    }
+
+    /// Fetches the latest snapshot taken at or before the epoch
+    pub async fn fetch_at_or_before(
+        db_connection: &Pool<Postgres>,
+        epoch: u64,
+    ) -> Result<Option<Self>, Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT * FROM steward_config_snapshots WHERE epoch <= $1 ORDER BY epoch DESC LIMIT 1",
+        )
+        .bind(Epoch(epoch))
+        .fetch_optional(db_connection)
+        .await
+    }
+
+    /// Fetches every snapshot, sorted by epoch
+    pub async fn fetch_all(db_connection: &Pool<Postgres>) -> Result<Vec<Self>, Error> {
+        sqlx::query_as::<_, Self>("SELECT * FROM steward_config_snapshots ORDER BY epoch ASC")
+            .fetch_all(db_connection)
+            .await
+    }
+}
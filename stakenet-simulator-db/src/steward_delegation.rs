@@ -0,0 +1,95 @@
+use crate::{
+    big_decimal_u64::BigDecimalU64,
+    bulk_insert::{InsertMode, bulk_insert},
+    domain::{Epoch, Lamports, VotePubkey},
+};
+use sqlx::{Error, FromRow, Pool, Postgres, types::BigDecimal};
+
+/// State of a validator of the stake pool as recorded by the on-chain steward for an epoch
+#[derive(Clone, Debug, FromRow)]
+pub struct StewardDelegation {
+    pub epoch: Epoch,
+    pub vote_pubkey: VotePubkey,
+    /// Index of the validator in the stake pool validator list
+    #[sqlx(try_from = "i32")]
+    pub validator_list_index: u32,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub score: u64,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub yield_score: u64,
+    /// Delegation target of the steward, as a fraction of the pool
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub delegation_numerator: u64,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub delegation_denominator: u64,
+    pub instant_unstake: bool,
+    /// Active stake of the pool on the validator
+    pub active_stake_lamports: Lamports,
+}
+
+impl StewardDelegation {
+    const COLUMNS: &[&str] = &[
+        "epoch",
+        "vote_pubkey",
+        "validator_list_index",
+        "score",
+        "yield_score",
+        "delegation_numerator",
+        "delegation_denominator",
+        "instant_unstake",
+        "active_stake_lamports",
+    ];
+    const CONFLICT_TARGET: &[&str] = &["epoch", "vote_pubkey"];
+
+    /// Share of the pool the steward targets for the validator
+    pub fn delegation_share(&self) -> f64 {
+        if self.delegation_denominator == 0 {
+            return 0.0;
+        }
+        self.delegation_numerator as f64 / self.delegation_denominator as f64
+    }
+
+    pub async fn bulk_insert(
+        db_connection: &Pool<Postgres>,
+        records: Vec<Self>,
+        mode: InsertMode,
+    ) -> Result<(), Error> {
+        bulk_insert(
+            db_connection,
+            "steward_delegations",
+            Self::COLUMNS,
+            Self::CONFLICT_TARGET,
+            |record: &Self| (record.epoch, record.vote_pubkey),
+            records,
+            mode,
+            |mut separated, record| {
+                separated.push_bind(record.epoch);
+                separated.push_bind(record.vote_pubkey);
+                separated.push_bind(i64::from(record.validator_list_index));
+                separated.push_bind(BigDecimal::from(record.score));
+                separated.push_bind(BigDecimal::from(record.yield_score));
+                separated.push_bind(BigDecimal::from(record.delegation_numerator));
+                separated.push_bind(BigDecimal::from(record.delegation_denominator));
+                separated.push_bind(record.instant_unstake);
+                separated.push_bind(record.active_stake_lamports);
+            },
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Fetches the recorded delegations for epochs `start_epoch..end_epoch`
+    pub async fn fetch_for_epoch_range(
+        db_connection: &Pool<Postgres>,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<Self>, Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT * FROM steward_delegations WHERE epoch >= $1 AND epoch < $2 ORDER BY epoch, validator_list_index",
+        )
+        .bind(Epoch(start_epoch))
+        .bind(Epoch(end_epoch))
+        .fetch_all(db_connection)
+        .await
+    }
+}
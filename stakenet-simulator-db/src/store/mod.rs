@@ -0,0 +1,254 @@
+use crate::{
+    active_stake_jito_sol::ActiveStakeJitoSol,
+    bulk_insert::InsertMode,
+    cluster_history::ClusterHistory,
+    cluster_history_entry::ClusterHistoryEntry,
+    domain::{Epoch, VotePubkey},
+    epoch_rewards::EpochRewards,
+    epoch_schedule::EpochSchedule,
+    inactive_stake_jito_sol::InactiveStakeJitoSol,
+    steward_blacklist::StewardBlacklist,
+    validator_history::ValidatorHistory,
+    validator_history_entry::ValidatorHistoryEntry,
+    validator_history_repair::ValidatorHistoryRepair,
+    withdraw_and_deposit_sol::WithdrawAndDepositSol,
+    withdraw_and_deposits_stake::WithdrawsAndDepositStakes,
+};
+use async_trait::async_trait;
+use sqlx::{Error, Pool, Postgres};
+use tracing::info;
+
+pub mod postgres;
+pub mod snapshot;
+pub mod sqlite;
+
+pub use postgres::PostgresStore;
+pub use snapshot::{Snapshot, SnapshotHeader};
+pub use sqlite::SqliteStore;
+
+/// Reads and writes of the tables the simulator runs on. The tracker records them in the
+/// supabase Postgres database, and they can be copied to an embedded SQLite file to run
+/// backtests without it. Epoch ranges are inclusive, like the queries of the models.
+#[async_trait]
+pub trait SimulatorStore: Send + Sync {
+    /// Postgres pool behind the store, for the tables only the Postgres database holds
+    fn postgres(&self) -> Option<&Pool<Postgres>> {
+        None
+    }
+
+    async fn fetch_validator_histories(&self) -> Result<Vec<ValidatorHistory>, Error>;
+
+    async fn fetch_cluster_history(&self) -> Result<ClusterHistory, Error>;
+
+    async fn fetch_cluster_history_entries(&self) -> Result<Vec<ClusterHistoryEntry>, Error>;
+
+    async fn fetch_epoch_schedule(&self) -> Result<Option<EpochSchedule>, Error>;
+
+    async fn fetch_validator_history_entries(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<ValidatorHistoryEntry>, Error>;
+
+    async fn fetch_validator_history_entries_by_validator(
+        &self,
+        vote_pubkey: &VotePubkey,
+    ) -> Result<Vec<ValidatorHistoryEntry>, Error>;
+
+    async fn fetch_validator_history_repairs(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<ValidatorHistoryRepair>, Error>;
+
+    async fn fetch_epoch_rewards(
+        &self,
+        vote_accounts: &[VotePubkey],
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<EpochRewards>, Error>;
+
+    async fn fetch_withdraw_and_deposit_sol(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<WithdrawAndDepositSol>, Error>;
+
+    async fn fetch_withdraw_and_deposit_stakes(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<WithdrawsAndDepositStakes>, Error>;
+
+    async fn fetch_active_stake(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<ActiveStakeJitoSol>, Error>;
+
+    async fn fetch_inactive_stake(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<InactiveStakeJitoSol>, Error>;
+
+    /// Blacklists recorded before `end_epoch`, sorted by epoch
+    async fn fetch_steward_blacklists_before(
+        &self,
+        end_epoch: u64,
+    ) -> Result<Vec<StewardBlacklist>, Error>;
+
+    async fn insert_validator_histories(
+        &self,
+        records: Vec<ValidatorHistory>,
+        mode: InsertMode,
+    ) -> Result<(), Error>;
+
+    async fn insert_validator_history_entries(
+        &self,
+        records: Vec<ValidatorHistoryEntry>,
+        mode: InsertMode,
+    ) -> Result<(), Error>;
+
+    async fn upsert_validator_history_repairs(
+        &self,
+        records: Vec<ValidatorHistoryRepair>,
+    ) -> Result<(), Error>;
+
+    /// Deletes the repairs of a validator for the epochs, returns the number of rows deleted
+    async fn delete_validator_history_repairs(
+        &self,
+        vote_pubkey: &VotePubkey,
+        epochs: &[Epoch],
+    ) -> Result<u64, Error>;
+
+    async fn upsert_cluster_history(&self, record: ClusterHistory) -> Result<(), Error>;
+
+    async fn insert_cluster_history_entries(
+        &self,
+        records: Vec<ClusterHistoryEntry>,
+        mode: InsertMode,
+    ) -> Result<(), Error>;
+
+    async fn upsert_epoch_schedule(&self, record: EpochSchedule) -> Result<(), Error>;
+
+    async fn insert_epoch_rewards(
+        &self,
+        records: Vec<EpochRewards>,
+        mode: InsertMode,
+    ) -> Result<(), Error>;
+
+    async fn insert_withdraw_and_deposit_sol(
+        &self,
+        records: Vec<WithdrawAndDepositSol>,
+        mode: InsertMode,
+    ) -> Result<(), Error>;
+
+    async fn insert_withdraw_and_deposit_stakes(
+        &self,
+        records: Vec<WithdrawsAndDepositStakes>,
+        mode: InsertMode,
+    ) -> Result<(), Error>;
+
+    async fn upsert_active_stake(&self, records: Vec<ActiveStakeJitoSol>) -> Result<(), Error>;
+
+    async fn upsert_inactive_stake(&self, records: Vec<InactiveStakeJitoSol>) -> Result<(), Error>;
+
+    async fn upsert_steward_blacklist(&self, record: StewardBlacklist) -> Result<(), Error>;
+}
+
+/// Connects to the store of the URL, a SQLite file for `sqlite:` URLs and Postgres otherwise
+pub async fn connect(url: &str) -> Result<Box<dyn SimulatorStore>, Error> {
+    if url.starts_with("sqlite:") {
+        Ok(Box::new(SqliteStore::connect(url).await?))
+    } else {
+        Ok(Box::new(PostgresStore::connect(url).await?))
+    }
+}
+
+/// Copies the records of epochs `start_epoch..=end_epoch` from one store to another. Validator
+/// histories, the cluster history, the epoch schedule and the blacklists recorded before the
+/// range are copied whole, as the simulator reads them regardless of the window.
+pub async fn copy_simulator_data(
+    source: &dyn SimulatorStore,
+    destination: &dyn SimulatorStore,
+    start_epoch: u64,
+    end_epoch: u64,
+) -> Result<(), Error> {
+    let histories = source.fetch_validator_histories().await?;
+    let vote_accounts: Vec<VotePubkey> = histories
+        .iter()
+        .map(|history| history.vote_account)
+        .collect();
+    info!("Copying {} validator histories", histories.len());
+    destination
+        .insert_validator_histories(histories, InsertMode::InsertIgnore)
+        .await?;
+
+    destination
+        .upsert_cluster_history(source.fetch_cluster_history().await?)
+        .await?;
+    destination
+        .insert_cluster_history_entries(
+            source.fetch_cluster_history_entries().await?,
+            InsertMode::InsertIgnore,
+        )
+        .await?;
+    if let Some(epoch_schedule) = source.fetch_epoch_schedule().await? {
+        destination.upsert_epoch_schedule(epoch_schedule).await?;
+    }
+
+    let entries = source
+        .fetch_validator_history_entries(start_epoch, end_epoch)
+        .await?;
+    info!("Copying {} validator history entries", entries.len());
+    destination
+        .insert_validator_history_entries(entries, InsertMode::InsertIgnore)
+        .await?;
+    destination
+        .upsert_validator_history_repairs(
+            source
+                .fetch_validator_history_repairs(start_epoch, end_epoch)
+                .await?,
+        )
+        .await?;
+
+    let rewards = source
+        .fetch_epoch_rewards(&vote_accounts, start_epoch, end_epoch)
+        .await?;
+    info!("Copying {} epoch rewards", rewards.len());
+    destination
+        .insert_epoch_rewards(rewards, InsertMode::InsertIgnore)
+        .await?;
+
+    destination
+        .insert_withdraw_and_deposit_sol(
+            source
+                .fetch_withdraw_and_deposit_sol(start_epoch, end_epoch)
+                .await?,
+            InsertMode::InsertIgnore,
+        )
+        .await?;
+    destination
+        .insert_withdraw_and_deposit_stakes(
+            source
+                .fetch_withdraw_and_deposit_stakes(start_epoch, end_epoch)
+                .await?,
+            InsertMode::InsertIgnore,
+        )
+        .await?;
+    destination
+        .upsert_active_stake(source.fetch_active_stake(start_epoch, end_epoch).await?)
+        .await?;
+    destination
+        .upsert_inactive_stake(source.fetch_inactive_stake(start_epoch, end_epoch).await?)
+        .await?;
+
+    for blacklist in source
+        .fetch_steward_blacklists_before(end_epoch.saturating_add(1))
+        .await?
+    {
+        destination.upsert_steward_blacklist(blacklist).await?;
+    }
+    Ok(())
+}
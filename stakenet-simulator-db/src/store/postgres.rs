@@ -0,0 +1,251 @@
+use crate::{
+    active_stake_jito_sol::ActiveStakeJitoSol,
+    bulk_insert::InsertMode,
+    cluster_history::ClusterHistory,
+    cluster_history_entry::ClusterHistoryEntry,
+    domain::{Epoch, VotePubkey},
+    epoch_rewards::EpochRewards,
+    epoch_schedule::EpochSchedule,
+    inactive_stake_jito_sol::InactiveStakeJitoSol,
+    steward_blacklist::StewardBlacklist,
+    store::SimulatorStore,
+    validator_history::ValidatorHistory,
+    validator_history_entry::ValidatorHistoryEntry,
+    validator_history_repair::ValidatorHistoryRepair,
+    withdraw_and_deposit_sol::WithdrawAndDepositSol,
+    withdraw_and_deposits_stake::WithdrawsAndDepositStakes,
+};
+use async_trait::async_trait;
+use sqlx::{Error, Pool, Postgres, postgres::PgPoolOptions};
+
+/// The supabase Postgres database the tracker writes to
+#[derive(Clone)]
+pub struct PostgresStore {
+    db_connection: Pool<Postgres>,
+}
+
+impl PostgresStore {
+    pub fn new(db_connection: Pool<Postgres>) -> Self {
+        Self { db_connection }
+    }
+
+    pub async fn connect(url: &str) -> Result<Self, Error> {
+        let db_connection = PgPoolOptions::new()
+            .max_connections(10)
+            .connect(url)
+            .await?;
+        Ok(Self::new(db_connection))
+    }
+}
+
+#[async_trait]
+impl SimulatorStore for PostgresStore {
+    fn postgres(&self) -> Option<&Pool<Postgres>> {
+        Some(&self.db_connection)
+    }
+
+    async fn fetch_validator_histories(&self) -> Result<Vec<ValidatorHistory>, Error> {
+        ValidatorHistory::fetch_all(&self.db_connection).await
+    }
+
+    async fn fetch_cluster_history(&self) -> Result<ClusterHistory, Error> {
+        ClusterHistory::fetch(&self.db_connection).await
+    }
+
+    async fn fetch_cluster_history_entries(&self) -> Result<Vec<ClusterHistoryEntry>, Error> {
+        ClusterHistoryEntry::fetch_all(&self.db_connection).await
+    }
+
+    async fn fetch_epoch_schedule(&self) -> Result<Option<EpochSchedule>, Error> {
+        EpochSchedule::fetch(&self.db_connection).await
+    }
+
+    async fn fetch_validator_history_entries(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<ValidatorHistoryEntry>, Error> {
+        ValidatorHistoryEntry::fetch_all_records_between_epochs(
+            &self.db_connection,
+            start_epoch,
+            end_epoch,
+        )
+        .await
+    }
+
+    async fn fetch_validator_history_entries_by_validator(
+        &self,
+        vote_pubkey: &VotePubkey,
+    ) -> Result<Vec<ValidatorHistoryEntry>, Error> {
+        ValidatorHistoryEntry::fetch_by_validator(&self.db_connection, vote_pubkey).await
+    }
+
+    async fn fetch_validator_history_repairs(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<ValidatorHistoryRepair>, Error> {
+        ValidatorHistoryRepair::fetch_between_epochs(&self.db_connection, start_epoch, end_epoch)
+            .await
+    }
+
+    async fn fetch_epoch_rewards(
+        &self,
+        vote_accounts: &[VotePubkey],
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<EpochRewards>, Error> {
+        EpochRewards::fetch_for_validators_and_epochs(
+            &self.db_connection,
+            vote_accounts,
+            start_epoch,
+            end_epoch,
+        )
+        .await
+    }
+
+    async fn fetch_withdraw_and_deposit_sol(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<WithdrawAndDepositSol>, Error> {
+        WithdrawAndDepositSol::get_records_for_epoch_range(
+            &self.db_connection,
+            start_epoch,
+            end_epoch,
+        )
+        .await
+    }
+
+    async fn fetch_withdraw_and_deposit_stakes(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<WithdrawsAndDepositStakes>, Error> {
+        WithdrawsAndDepositStakes::get_records_for_epoch_range(
+            &self.db_connection,
+            start_epoch,
+            end_epoch,
+        )
+        .await
+    }
+
+    async fn fetch_active_stake(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<ActiveStakeJitoSol>, Error> {
+        ActiveStakeJitoSol::get_active_stakes_for_epoch_range(
+            &self.db_connection,
+            start_epoch,
+            end_epoch,
+        )
+        .await
+    }
+
+    async fn fetch_inactive_stake(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<InactiveStakeJitoSol>, Error> {
+        InactiveStakeJitoSol::get_inactive_stakes_for_epoch_range(
+            &self.db_connection,
+            start_epoch,
+            end_epoch,
+        )
+        .await
+    }
+
+    async fn fetch_steward_blacklists_before(
+        &self,
+        end_epoch: u64,
+    ) -> Result<Vec<StewardBlacklist>, Error> {
+        StewardBlacklist::fetch_before(&self.db_connection, end_epoch).await
+    }
+
+    async fn insert_validator_histories(
+        &self,
+        records: Vec<ValidatorHistory>,
+        mode: InsertMode,
+    ) -> Result<(), Error> {
+        ValidatorHistory::bulk_insert(&self.db_connection, records, mode).await
+    }
+
+    async fn insert_validator_history_entries(
+        &self,
+        records: Vec<ValidatorHistoryEntry>,
+        mode: InsertMode,
+    ) -> Result<(), Error> {
+        ValidatorHistoryEntry::bulk_insert(&self.db_connection, records, mode).await
+    }
+
+    async fn upsert_validator_history_repairs(
+        &self,
+        records: Vec<ValidatorHistoryRepair>,
+    ) -> Result<(), Error> {
+        ValidatorHistoryRepair::bulk_insert(&self.db_connection, records, InsertMode::Upsert).await
+    }
+
+    async fn delete_validator_history_repairs(
+        &self,
+        vote_pubkey: &VotePubkey,
+        epochs: &[Epoch],
+    ) -> Result<u64, Error> {
+        ValidatorHistoryRepair::delete(&self.db_connection, vote_pubkey, epochs).await
+    }
+
+    async fn upsert_cluster_history(&self, record: ClusterHistory) -> Result<(), Error> {
+        ClusterHistory::upsert(&self.db_connection, record).await?;
+        Ok(())
+    }
+
+    async fn insert_cluster_history_entries(
+        &self,
+        records: Vec<ClusterHistoryEntry>,
+        mode: InsertMode,
+    ) -> Result<(), Error> {
+        ClusterHistoryEntry::bulk_insert(&self.db_connection, records, mode).await
+    }
+
+    async fn upsert_epoch_schedule(&self, record: EpochSchedule) -> Result<(), Error> {
+        EpochSchedule::upsert(&self.db_connection, record).await?;
+        Ok(())
+    }
+
+    async fn insert_epoch_rewards(
+        &self,
+        records: Vec<EpochRewards>,
+        mode: InsertMode,
+    ) -> Result<(), Error> {
+        EpochRewards::bulk_insert(&self.db_connection, records, mode).await
+    }
+
+    async fn insert_withdraw_and_deposit_sol(
+        &self,
+        records: Vec<WithdrawAndDepositSol>,
+        mode: InsertMode,
+    ) -> Result<(), Error> {
+        WithdrawAndDepositSol::bulk_insert(&self.db_connection, records, mode).await
+    }
+
+    async fn insert_withdraw_and_deposit_stakes(
+        &self,
+        records: Vec<WithdrawsAndDepositStakes>,
+        mode: InsertMode,
+    ) -> Result<(), Error> {
+        WithdrawsAndDepositStakes::bulk_insert(&self.db_connection, records, mode).await
+    }
+
+    async fn upsert_active_stake(&self, records: Vec<ActiveStakeJitoSol>) -> Result<(), Error> {
+        ActiveStakeJitoSol::bulk_insert(&self.db_connection, records, InsertMode::Upsert).await
+    }
+
+    async fn upsert_inactive_stake(&self, records: Vec<InactiveStakeJitoSol>) -> Result<(), Error> {
+        InactiveStakeJitoSol::bulk_insert(&self.db_connection, records, InsertMode::Upsert).await
+    }
+
+    async fn upsert_steward_blacklist(&self, record: StewardBlacklist) -> Result<(), Error> {
+        StewardBlacklist::upsert(&self.db_connection, record).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,270 @@
+use crate::{
+    error::StakenetSimulatorDbError,
+    store::{SimulatorStore, SqliteStore, copy_simulator_data, sqlite::SQLITE_SCHEMA_VERSION},
+};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read, Write},
+    path::{Path, PathBuf},
+};
+use tracing::info;
+use uuid::Uuid;
+
+/// Version of the snapshot file layout
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// First line of a snapshot file, followed by the gzip compressed SQLite store
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SnapshotHeader {
+    pub format_version: u32,
+    /// Version of the SQLite schema of the store
+    pub schema_version: u32,
+    /// Epochs of the records in the snapshot, inclusive
+    pub start_epoch: u64,
+    pub end_epoch: u64,
+    /// Hex encoded SHA-256 of the uncompressed store
+    pub checksum: String,
+}
+
+/// Frozen copy of the simulator data for an epoch range, packed in a single file so it can be
+/// shared and attached to bug reports. The store is unpacked to a temporary SQLite file that is
+/// removed when the snapshot is dropped.
+pub struct Snapshot {
+    pub header: SnapshotHeader,
+    store: SqliteStore,
+    path: PathBuf,
+}
+
+impl Snapshot {
+    /// Writes the records of epochs `start_epoch..=end_epoch` in the source store to a snapshot
+    /// file
+    pub async fn export(
+        source: &dyn SimulatorStore,
+        path: &Path,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<SnapshotHeader, StakenetSimulatorDbError> {
+        let store_path = temporary_store_path();
+        let store = SqliteStore::open(&store_path).await?;
+        let copied = copy_simulator_data(source, &store, start_epoch, end_epoch).await;
+        store.close().await;
+        let database = tokio::fs::read(&store_path).await;
+        tokio::fs::remove_file(&store_path).await?;
+        copied?;
+        let database = database?;
+
+        let header = SnapshotHeader {
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            schema_version: SQLITE_SCHEMA_VERSION,
+            start_epoch,
+            end_epoch,
+            checksum: checksum(&database),
+        };
+        // Compressing the store is CPU bound, so it runs off the async runtime
+        let file_path = path.to_path_buf();
+        let file_header = header.clone();
+        tokio::task::spawn_blocking(move || {
+            write_snapshot_file(&file_path, &file_header, &database)
+        })
+        .await??;
+
+        info!(
+            "Snapshot of epochs {} to {} written to {}",
+            start_epoch,
+            end_epoch,
+            path.display()
+        );
+        Ok(header)
+    }
+
+    /// Reads a snapshot file, checking its versions and checksum
+    pub async fn open(path: &Path) -> Result<Self, StakenetSimulatorDbError> {
+        let file_path = path.to_path_buf();
+        let (header, database) =
+            tokio::task::spawn_blocking(move || read_snapshot_file(&file_path)).await??;
+
+        let store_path = temporary_store_path();
+        tokio::fs::write(&store_path, database).await?;
+        let store = SqliteStore::open(&store_path).await?;
+        Ok(Self {
+            header,
+            store,
+            path: store_path,
+        })
+    }
+
+    /// Store holding the records of the snapshot, to load a `BacktestDataset` from
+    pub fn store(&self) -> &SqliteStore {
+        &self.store
+    }
+
+    /// Copies the records of the snapshot to another store
+    pub async fn import(
+        &self,
+        destination: &dyn SimulatorStore,
+    ) -> Result<(), StakenetSimulatorDbError> {
+        copy_simulator_data(
+            &self.store,
+            destination,
+            self.header.start_epoch,
+            self.header.end_epoch,
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+impl Drop for Snapshot {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Writes the header line and the compressed store to the snapshot file
+fn write_snapshot_file(
+    path: &Path,
+    header: &SnapshotHeader,
+    database: &[u8],
+) -> Result<(), StakenetSimulatorDbError> {
+    let mut file = File::create(path)?;
+    serde_json::to_writer(&mut file, header)?;
+    file.write_all(b"\n")?;
+    let mut encoder = GzEncoder::new(file, Compression::default());
+    encoder.write_all(database)?;
+    encoder.finish()?;
+    Ok(())
+}
+
+/// Reads the header and the decompressed store of a snapshot file, checking the versions and the
+/// checksum
+fn read_snapshot_file(path: &Path) -> Result<(SnapshotHeader, Vec<u8>), StakenetSimulatorDbError> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut header_line = Vec::new();
+    reader.read_until(b'\n', &mut header_line)?;
+    let header: SnapshotHeader = serde_json::from_slice(&header_line).map_err(|_| {
+        StakenetSimulatorDbError::InvalidSnapshot(format!(
+            "{} has no snapshot header",
+            path.display()
+        ))
+    })?;
+    if header.format_version != SNAPSHOT_FORMAT_VERSION
+        || header.schema_version != SQLITE_SCHEMA_VERSION
+    {
+        return Err(StakenetSimulatorDbError::InvalidSnapshot(format!(
+            "format version {} and schema version {} are not supported, expected {} and {}",
+            header.format_version,
+            header.schema_version,
+            SNAPSHOT_FORMAT_VERSION,
+            SQLITE_SCHEMA_VERSION
+        )));
+    }
+
+    let mut database = Vec::new();
+    GzDecoder::new(reader).read_to_end(&mut database)?;
+    let actual = checksum(&database);
+    if actual != header.checksum {
+        return Err(StakenetSimulatorDbError::SnapshotChecksumMismatch {
+            expected: header.checksum,
+            actual,
+        });
+    }
+    Ok((header, database))
+}
+
+fn temporary_store_path() -> PathBuf {
+    std::env::temp_dir().join(format!("stakenet-snapshot-{}.db", Uuid::new_v4()))
+}
+
+fn checksum(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bulk_insert::InsertMode,
+        cluster_history::ClusterHistory,
+        domain::{Bps, Epoch, Lamports, VotePubkey},
+        epoch_rewards::EpochRewards,
+        validator_history::ValidatorHistory,
+    };
+    use solana_sdk::pubkey::Pubkey;
+
+    #[tokio::test]
+    async fn test_snapshot() {
+        let source = SqliteStore::connect("sqlite::memory:").await.unwrap();
+        let vote_account = VotePubkey(Pubkey::new_unique());
+        source
+            .upsert_cluster_history(ClusterHistory {
+                struct_version: 0,
+                bump: 255,
+                cluster_history_last_update_slot: 1_000,
+            })
+            .await
+            .unwrap();
+        source
+            .insert_validator_histories(
+                vec![ValidatorHistory {
+                    struct_version: 0,
+                    vote_account,
+                    index: 0,
+                    bump: 255,
+                    last_ip_timestamp: 0,
+                    last_version_timestamp: 0,
+                }],
+                InsertMode::InsertIgnore,
+            )
+            .await
+            .unwrap();
+        let rewards = |epoch: u64| EpochRewards {
+            id: format!("{epoch}-{vote_account}"),
+            vote_pubkey: vote_account,
+            epoch: Epoch(epoch),
+            inflation_commission_bps: Bps(500),
+            total_inflation_rewards: Lamports(1_000),
+            mev_commission_bps: Bps(800),
+            total_mev_rewards: Lamports(100),
+            priority_fee_commission_bps: Bps(0),
+            total_priority_fee_rewards: Lamports(0),
+            active_stake: Lamports(1_000_000),
+        };
+        source
+            .insert_epoch_rewards(vec![rewards(700), rewards(720)], InsertMode::InsertIgnore)
+            .await
+            .unwrap();
+
+        let path = temporary_store_path().with_extension("snapshot");
+        let header = Snapshot::export(&source, &path, 700, 710).await.unwrap();
+        assert_eq!(header.schema_version, SQLITE_SCHEMA_VERSION);
+
+        let snapshot = Snapshot::open(&path).await.unwrap();
+        assert_eq!(snapshot.header, header);
+        let loaded = snapshot
+            .store()
+            .fetch_epoch_rewards(&[vote_account], 0, 800)
+            .await
+            .unwrap();
+        // Epochs outside of the range are left out
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].epoch, Epoch(700));
+
+        let contents = std::fs::read(&path).unwrap();
+        let header_end = contents.iter().position(|byte| *byte == b'\n').unwrap();
+        let mut tampered = serde_json::to_vec(&SnapshotHeader {
+            checksum: "00".to_string(),
+            ..header
+        })
+        .unwrap();
+        tampered.extend_from_slice(&contents[header_end..]);
+        std::fs::write(&path, tampered).unwrap();
+        assert!(matches!(
+            Snapshot::open(&path).await,
+            Err(StakenetSimulatorDbError::SnapshotChecksumMismatch { .. })
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,840 @@
+use crate::{
+    active_stake_jito_sol::ActiveStakeJitoSol,
+    bulk_insert::InsertMode,
+    cluster_history::ClusterHistory,
+    cluster_history_entry::ClusterHistoryEntry,
+    domain::{Epoch, VotePubkey},
+    epoch_rewards::EpochRewards,
+    epoch_schedule::EpochSchedule,
+    error::StakenetSimulatorDbError,
+    inactive_stake_jito_sol::InactiveStakeJitoSol,
+    steward_blacklist::StewardBlacklist,
+    store::SimulatorStore,
+    validator_history::ValidatorHistory,
+    validator_history_entry::{ClientVersion, ValidatorHistoryEntry, int_to_upload_authority},
+    validator_history_repair::{RepairSource, ValidatorHistoryRepair},
+    withdraw_and_deposit_sol::WithdrawAndDepositSol,
+    withdraw_and_deposits_stake::WithdrawsAndDepositStakes,
+};
+use async_trait::async_trait;
+use sqlx::{
+    Error, Pool, QueryBuilder, Row, Sqlite,
+    query_builder::Separated,
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions, SqliteRow},
+    types::BigDecimal,
+};
+use std::{collections::HashSet, path::Path, str::FromStr};
+use validator_history::{
+    ClientVersion as JitoClientVersion, ValidatorHistoryEntry as JitoValidatorHistoryEntry,
+};
+
+const SCHEMA: &str = include_str!("sqlite_schema.sql");
+
+/// Version of the SQLite schema, bumped whenever the tables change
+pub const SQLITE_SCHEMA_VERSION: u32 = 1;
+
+/// Maximum number of bound parameters in a SQLite statement
+const BIND_LIMIT: usize = 32766;
+
+/// Embedded store in a single SQLite file, created with the simulator tables when missing
+#[derive(Clone)]
+pub struct SqliteStore {
+    db_connection: Pool<Sqlite>,
+}
+
+impl SqliteStore {
+    /// Opens the SQLite file of a `sqlite:` URL, e.g. `sqlite://backtest.db`
+    pub async fn connect(url: &str) -> Result<Self, Error> {
+        Self::connect_with(SqliteConnectOptions::from_str(url)?).await
+    }
+
+    /// Opens the SQLite file at the path
+    pub async fn open(path: &Path) -> Result<Self, Error> {
+        Self::connect_with(SqliteConnectOptions::new().filename(path)).await
+    }
+
+    /// Closes the connection, writing every change to the file
+    pub async fn close(self) {
+        self.db_connection.close().await;
+    }
+
+    async fn connect_with(options: SqliteConnectOptions) -> Result<Self, Error> {
+        let db_connection = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options.create_if_missing(true))
+            .await?;
+        sqlx::raw_sql(SCHEMA).execute(&db_connection).await?;
+        Ok(Self { db_connection })
+    }
+
+    async fn fetch_rows(
+        &self,
+        query: &'static str,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<SqliteRow>, Error> {
+        sqlx::query(query)
+            .bind(to_integer(start_epoch))
+            .bind(to_integer(end_epoch))
+            .fetch_all(&self.db_connection)
+            .await
+    }
+
+    /// Inserts the records in chunks that stay under the bind limit. `into_query` holds the
+    /// table and columns, the conflict handling comes from the mode.
+    async fn bulk_insert<T: Send>(
+        &self,
+        mode: InsertMode,
+        into_query: &'static str,
+        num_fields: usize,
+        records: Vec<T>,
+        mut push_record: impl FnMut(Separated<'_, '_, Sqlite, &'static str>, T) + Send,
+    ) -> Result<(), Error> {
+        let chunk_size = BIND_LIMIT / num_fields;
+        let mut records = records.into_iter().peekable();
+        while records.peek().is_some() {
+            let mut query_builder: QueryBuilder<Sqlite> = QueryBuilder::new(match mode {
+                InsertMode::InsertIgnore => "INSERT OR IGNORE INTO ",
+                InsertMode::Upsert => "INSERT OR REPLACE INTO ",
+            });
+            query_builder.push(into_query);
+            query_builder.push_values(records.by_ref().take(chunk_size), &mut push_record);
+            query_builder.build().execute(&self.db_connection).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SimulatorStore for SqliteStore {
+    async fn fetch_validator_histories(&self) -> Result<Vec<ValidatorHistory>, Error> {
+        let rows = sqlx::query("SELECT * FROM validator_histories")
+            .fetch_all(&self.db_connection)
+            .await?;
+        rows.iter()
+            .map(|row| {
+                Ok(ValidatorHistory {
+                    struct_version: get_int(row, "struct_version")?,
+                    vote_account: get_pubkey(row, "vote_account")?,
+                    index: get_int(row, "index")?,
+                    bump: get_int(row, "bump")?,
+                    last_ip_timestamp: get_u64(row, "last_ip_timestamp")?,
+                    last_version_timestamp: get_u64(row, "last_version_timestamp")?,
+                })
+            })
+            .collect()
+    }
+
+    async fn fetch_cluster_history(&self) -> Result<ClusterHistory, Error> {
+        let row = sqlx::query("SELECT * FROM cluster_histories WHERE id = 1")
+            .fetch_one(&self.db_connection)
+            .await?;
+        Ok(ClusterHistory {
+            struct_version: get_u64(&row, "struct_version")?,
+            bump: get_int(&row, "bump")?,
+            cluster_history_last_update_slot: get_u64(&row, "cluster_history_last_update_slot")?,
+        })
+    }
+
+    async fn fetch_cluster_history_entries(&self) -> Result<Vec<ClusterHistoryEntry>, Error> {
+        let rows = sqlx::query("SELECT * FROM cluster_history_entries")
+            .fetch_all(&self.db_connection)
+            .await?;
+        rows.iter()
+            .map(|row| {
+                Ok(ClusterHistoryEntry {
+                    epoch: get_u64(row, "epoch")?,
+                    total_blocks: get_int(row, "total_blocks")?,
+                    epoch_start_timestamp: get_u64(row, "epoch_start_timestamp")?,
+                })
+            })
+            .collect()
+    }
+
+    async fn fetch_epoch_schedule(&self) -> Result<Option<EpochSchedule>, Error> {
+        let row = sqlx::query("SELECT * FROM epoch_schedules WHERE id = 1")
+            .fetch_optional(&self.db_connection)
+            .await?;
+        row.map(|row| {
+            Ok(EpochSchedule {
+                slots_per_epoch: get_u64(&row, "slots_per_epoch")?,
+                leader_schedule_slot_offset: get_u64(&row, "leader_schedule_slot_offset")?,
+                warmup: row.try_get("warmup")?,
+                first_normal_epoch: get_u64(&row, "first_normal_epoch")?,
+                first_normal_slot: get_u64(&row, "first_normal_slot")?,
+            })
+        })
+        .transpose()
+    }
+
+    async fn fetch_validator_history_entries(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<ValidatorHistoryEntry>, Error> {
+        let rows = self
+            .fetch_rows(
+                "SELECT * FROM validator_history_entries WHERE epoch >= $1 AND epoch <= $2",
+                start_epoch,
+                end_epoch,
+            )
+            .await?;
+        rows.iter().map(validator_history_entry_from_row).collect()
+    }
+
+    async fn fetch_validator_history_entries_by_validator(
+        &self,
+        vote_pubkey: &VotePubkey,
+    ) -> Result<Vec<ValidatorHistoryEntry>, Error> {
+        let rows = sqlx::query("SELECT * FROM validator_history_entries WHERE vote_pubkey = $1")
+            .bind(vote_pubkey.to_string())
+            .fetch_all(&self.db_connection)
+            .await?;
+        rows.iter().map(validator_history_entry_from_row).collect()
+    }
+
+    async fn fetch_validator_history_repairs(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<ValidatorHistoryRepair>, Error> {
+        let rows = self
+            .fetch_rows(
+                "SELECT * FROM validator_history_repairs WHERE epoch >= $1 AND epoch <= $2",
+                start_epoch,
+                end_epoch,
+            )
+            .await?;
+        rows.iter()
+            .map(|row| {
+                Ok(ValidatorHistoryRepair {
+                    epoch: get_u64(row, "epoch")?,
+                    vote_pubkey: get_pubkey(row, "vote_pubkey")?,
+                    repair_version: get_int(row, "repair_version")?,
+                    epoch_credits: get_int(row, "epoch_credits")?,
+                    epoch_credits_source: get_repair_source(row, "epoch_credits_source")?,
+                    blocks_produced: get_int(row, "blocks_produced")?,
+                    total_leader_slots: get_int(row, "total_leader_slots")?,
+                    block_production_source: get_repair_source(row, "block_production_source")?,
+                    priority_fee_commission: get_int::<u16>(row, "priority_fee_commission")?.into(),
+                    priority_fee_tips: get_u64(row, "priority_fee_tips")?,
+                    priority_fee_source: get_repair_source(row, "priority_fee_source")?,
+                })
+            })
+            .collect()
+    }
+
+    async fn fetch_epoch_rewards(
+        &self,
+        vote_accounts: &[VotePubkey],
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<EpochRewards>, Error> {
+        let vote_accounts: HashSet<&VotePubkey> = vote_accounts.iter().collect();
+        let rows = self
+            .fetch_rows(
+                "SELECT * FROM epoch_rewards WHERE epoch BETWEEN $1 AND $2",
+                start_epoch,
+                end_epoch,
+            )
+            .await?;
+        let mut rewards = Vec::new();
+        for row in &rows {
+            let vote_pubkey = get_pubkey(row, "vote_pubkey")?;
+            if !vote_accounts.contains(&vote_pubkey) {
+                continue;
+            }
+            rewards.push(EpochRewards {
+                id: row.try_get("id")?,
+                vote_pubkey,
+                epoch: get_u64(row, "epoch")?,
+                inflation_commission_bps: get_int::<u16>(row, "inflation_commission_bps")?.into(),
+                total_inflation_rewards: get_u64(row, "total_inflation_rewards")?,
+                mev_commission_bps: get_int::<u16>(row, "mev_commission_bps")?.into(),
+                total_mev_rewards: get_u64(row, "total_mev_rewards")?,
+                priority_fee_commission_bps: get_int::<u16>(row, "priority_fee_commission_bps")?
+                    .into(),
+                total_priority_fee_rewards: get_u64(row, "total_priority_fee_rewards")?,
+                active_stake: get_u64(row, "active_stake")?,
+            });
+        }
+        Ok(rewards)
+    }
+
+    async fn fetch_withdraw_and_deposit_sol(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<WithdrawAndDepositSol>, Error> {
+        let rows = self
+            .fetch_rows(
+                "SELECT * FROM withdraw_and_deposit_sol WHERE epoch BETWEEN $1 AND $2 \
+                ORDER BY epoch",
+                start_epoch,
+                end_epoch,
+            )
+            .await?;
+        rows.iter()
+            .map(|row| {
+                Ok(WithdrawAndDepositSol {
+                    epoch: get_u64(row, "epoch")?,
+                    withdraw_sol: get_decimal(row, "withdraw_sol")?,
+                    deposit_sol: get_decimal(row, "deposit_sol")?,
+                })
+            })
+            .collect()
+    }
+
+    async fn fetch_withdraw_and_deposit_stakes(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<WithdrawsAndDepositStakes>, Error> {
+        let rows = self
+            .fetch_rows(
+                "SELECT * FROM withdraw_and_deposit_stakes WHERE epoch BETWEEN $1 AND $2 \
+                ORDER BY epoch, vote_pubkey",
+                start_epoch,
+                end_epoch,
+            )
+            .await?;
+        rows.iter()
+            .map(|row| {
+                Ok(WithdrawsAndDepositStakes {
+                    id: row.try_get("id")?,
+                    epoch: get_u64(row, "epoch")?,
+                    vote_pubkey: get_pubkey(row, "vote_pubkey")?,
+                    withdraw_stake: get_decimal(row, "withdraw_stake")?,
+                    deposit_stake: get_decimal(row, "deposit_stake")?,
+                })
+            })
+            .collect()
+    }
+
+    async fn fetch_active_stake(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<ActiveStakeJitoSol>, Error> {
+        let rows = self
+            .fetch_rows(
+                "SELECT * FROM active_stake_jito_sol WHERE epoch BETWEEN $1 AND $2",
+                start_epoch,
+                end_epoch,
+            )
+            .await?;
+        rows.iter()
+            .map(|row| {
+                Ok(ActiveStakeJitoSol::new(
+                    get_u64(row, "epoch")?,
+                    get_decimal(row, "balance")?,
+                ))
+            })
+            .collect()
+    }
+
+    async fn fetch_inactive_stake(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<InactiveStakeJitoSol>, Error> {
+        let rows = self
+            .fetch_rows(
+                "SELECT * FROM inactive_stake_jito_sol WHERE epoch BETWEEN $1 AND $2",
+                start_epoch,
+                end_epoch,
+            )
+            .await?;
+        rows.iter()
+            .map(|row| {
+                Ok(InactiveStakeJitoSol::new(
+                    get_u64(row, "epoch")?,
+                    get_decimal(row, "balance")?,
+                ))
+            })
+            .collect()
+    }
+
+    async fn fetch_steward_blacklists_before(
+        &self,
+        end_epoch: u64,
+    ) -> Result<Vec<StewardBlacklist>, Error> {
+        let rows =
+            sqlx::query("SELECT * FROM steward_blacklists WHERE epoch < $1 ORDER BY epoch ASC")
+                .bind(to_integer(end_epoch))
+                .fetch_all(&self.db_connection)
+                .await?;
+        rows.iter()
+            .map(|row| {
+                let vote_pubkeys: String = row.try_get("vote_pubkeys")?;
+                Ok(StewardBlacklist {
+                    epoch: get_u64(row, "epoch")?,
+                    vote_pubkeys: serde_json::from_str(&vote_pubkeys)
+                        .map_err(|_| decode_error("vote_pubkeys"))?,
+                })
+            })
+            .collect()
+    }
+
+    async fn insert_validator_histories(
+        &self,
+        records: Vec<ValidatorHistory>,
+        mode: InsertMode,
+    ) -> Result<(), Error> {
+        self.bulk_insert(
+            mode,
+            "validator_histories (vote_account,struct_version,\"index\",\
+            bump,last_ip_timestamp,last_version_timestamp) ",
+            6,
+            records,
+            |mut separated, record| {
+                separated.push_bind(record.vote_account.to_string());
+                separated.push_bind(i64::from(record.struct_version));
+                separated.push_bind(i64::from(record.index));
+                separated.push_bind(i64::from(record.bump));
+                separated.push_bind(to_integer(record.last_ip_timestamp));
+                separated.push_bind(to_integer(record.last_version_timestamp));
+            },
+        )
+        .await
+    }
+
+    async fn insert_validator_history_entries(
+        &self,
+        records: Vec<ValidatorHistoryEntry>,
+        mode: InsertMode,
+    ) -> Result<(), Error> {
+        // The client versions are encoded up front, as binding a record can't fail
+        let records = records
+            .into_iter()
+            .map(|record| {
+                let version = serde_json::to_string(&ClientVersion::from(
+                    record.validator_history_entry.version,
+                ))
+                .map_err(|e| Error::Encode(Box::new(e)))?;
+                Ok((record, version))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        self.bulk_insert(
+            mode,
+            "validator_history_entries (id,vote_pubkey,\
+            activated_stake_lamports,epoch,mev_commission,epoch_credits,commission,client_type,\
+            version,ip,merkle_root_upload_authority,is_superminority,rank,\
+            vote_account_last_update_slot,mev_earned,priority_fee_commission,priority_fee_tips,\
+            total_priority_fees,total_leader_slots,blocks_produced,block_data_updated_at_slot,\
+            priority_fee_merkle_root_upload_authority) ",
+            22,
+            records,
+            |mut separated, (record, version)| {
+                let entry = record.validator_history_entry;
+                separated.push_bind(record.id);
+                separated.push_bind(record.vote_pubkey.to_string());
+                separated.push_bind(to_integer(entry.activated_stake_lamports));
+                separated.push_bind(i64::from(entry.epoch));
+                separated.push_bind(i64::from(entry.mev_commission));
+                separated.push_bind(i64::from(entry.epoch_credits));
+                separated.push_bind(i64::from(entry.commission));
+                separated.push_bind(i64::from(entry.client_type));
+                separated.push_bind(version);
+                separated.push_bind(
+                    entry
+                        .ip
+                        .into_iter()
+                        .map(|x| x.to_string())
+                        .collect::<Vec<String>>()
+                        .join("."),
+                );
+                separated.push_bind(i64::from(entry.merkle_root_upload_authority as u8));
+                separated.push_bind(i64::from(entry.is_superminority));
+                separated.push_bind(i64::from(entry.rank));
+                separated.push_bind(to_integer(entry.vote_account_last_update_slot));
+                separated.push_bind(i64::from(entry.mev_earned));
+                separated.push_bind(i64::from(entry.priority_fee_commission));
+                separated.push_bind(to_integer(entry.priority_fee_tips));
+                separated.push_bind(to_integer(entry.total_priority_fees));
+                separated.push_bind(i64::from(entry.total_leader_slots));
+                separated.push_bind(i64::from(entry.blocks_produced));
+                separated.push_bind(to_integer(entry.block_data_updated_at_slot));
+                separated.push_bind(i64::from(
+                    entry.priority_fee_merkle_root_upload_authority as u8,
+                ));
+            },
+        )
+        .await
+    }
+
+    async fn upsert_validator_history_repairs(
+        &self,
+        records: Vec<ValidatorHistoryRepair>,
+    ) -> Result<(), Error> {
+        self.bulk_insert(
+            InsertMode::Upsert,
+            "validator_history_repairs (epoch,vote_pubkey,repair_version,\
+            epoch_credits,epoch_credits_source,blocks_produced,total_leader_slots,\
+            block_production_source,priority_fee_commission,priority_fee_tips,\
+            priority_fee_source) ",
+            11,
+            records,
+            |mut separated, record| {
+                separated.push_bind(to_integer(record.epoch));
+                separated.push_bind(record.vote_pubkey.to_string());
+                separated.push_bind(i64::from(record.repair_version));
+                separated.push_bind(i64::from(record.epoch_credits));
+                separated.push_bind(record.epoch_credits_source.as_str());
+                separated.push_bind(i64::from(record.blocks_produced));
+                separated.push_bind(i64::from(record.total_leader_slots));
+                separated.push_bind(record.block_production_source.as_str());
+                separated.push_bind(i64::from(record.priority_fee_commission.0));
+                separated.push_bind(to_integer(record.priority_fee_tips));
+                separated.push_bind(record.priority_fee_source.as_str());
+            },
+        )
+        .await
+    }
+
+    async fn delete_validator_history_repairs(
+        &self,
+        vote_pubkey: &VotePubkey,
+        epochs: &[Epoch],
+    ) -> Result<u64, Error> {
+        if epochs.is_empty() {
+            return Ok(0);
+        }
+        let mut query_builder: QueryBuilder<Sqlite> =
+            QueryBuilder::new("DELETE FROM validator_history_repairs WHERE vote_pubkey = ");
+        query_builder.push_bind(vote_pubkey.to_string());
+        query_builder.push(" AND epoch IN (");
+        let mut separated = query_builder.separated(",");
+        for epoch in epochs {
+            separated.push_bind(to_integer(*epoch));
+        }
+        query_builder.push(")");
+        let result = query_builder.build().execute(&self.db_connection).await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn upsert_cluster_history(&self, record: ClusterHistory) -> Result<(), Error> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO cluster_histories \
+            (id,struct_version,bump,cluster_history_last_update_slot) VALUES (1, $1, $2, $3)",
+        )
+        .bind(to_integer(record.struct_version))
+        .bind(i64::from(record.bump))
+        .bind(to_integer(record.cluster_history_last_update_slot))
+        .execute(&self.db_connection)
+        .await?;
+        Ok(())
+    }
+
+    async fn insert_cluster_history_entries(
+        &self,
+        records: Vec<ClusterHistoryEntry>,
+        mode: InsertMode,
+    ) -> Result<(), Error> {
+        self.bulk_insert(
+            mode,
+            "cluster_history_entries \
+            (epoch,total_blocks,epoch_start_timestamp) ",
+            3,
+            records,
+            |mut separated, record| {
+                separated.push_bind(to_integer(record.epoch));
+                separated.push_bind(i64::from(record.total_blocks));
+                separated.push_bind(to_integer(record.epoch_start_timestamp));
+            },
+        )
+        .await
+    }
+
+    async fn upsert_epoch_schedule(&self, record: EpochSchedule) -> Result<(), Error> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO epoch_schedules (id, slots_per_epoch, \
+            leader_schedule_slot_offset, warmup, first_normal_epoch, first_normal_slot) \
+            VALUES (1, $1, $2, $3, $4, $5)",
+        )
+        .bind(to_integer(record.slots_per_epoch))
+        .bind(to_integer(record.leader_schedule_slot_offset))
+        .bind(record.warmup)
+        .bind(to_integer(record.first_normal_epoch))
+        .bind(to_integer(record.first_normal_slot))
+        .execute(&self.db_connection)
+        .await?;
+        Ok(())
+    }
+
+    async fn insert_epoch_rewards(
+        &self,
+        records: Vec<EpochRewards>,
+        mode: InsertMode,
+    ) -> Result<(), Error> {
+        self.bulk_insert(
+            mode,
+            "epoch_rewards (id,vote_pubkey,epoch,inflation_commission_bps,\
+            total_inflation_rewards,mev_commission_bps,total_mev_rewards,\
+            priority_fee_commission_bps,total_priority_fee_rewards,active_stake) ",
+            10,
+            records,
+            |mut separated, record| {
+                separated.push_bind(record.id);
+                separated.push_bind(record.vote_pubkey.to_string());
+                separated.push_bind(to_integer(record.epoch));
+                separated.push_bind(i64::from(record.inflation_commission_bps.0));
+                separated.push_bind(to_integer(record.total_inflation_rewards));
+                separated.push_bind(i64::from(record.mev_commission_bps.0));
+                separated.push_bind(to_integer(record.total_mev_rewards));
+                separated.push_bind(i64::from(record.priority_fee_commission_bps.0));
+                separated.push_bind(to_integer(record.total_priority_fee_rewards));
+                separated.push_bind(to_integer(record.active_stake));
+            },
+        )
+        .await
+    }
+
+    async fn insert_withdraw_and_deposit_sol(
+        &self,
+        records: Vec<WithdrawAndDepositSol>,
+        mode: InsertMode,
+    ) -> Result<(), Error> {
+        self.bulk_insert(
+            mode,
+            "withdraw_and_deposit_sol (epoch,withdraw_sol,deposit_sol) ",
+            3,
+            records,
+            |mut separated, record| {
+                separated.push_bind(to_integer(record.epoch));
+                separated.push_bind(record.withdraw_sol.to_string());
+                separated.push_bind(record.deposit_sol.to_string());
+            },
+        )
+        .await
+    }
+
+    async fn insert_withdraw_and_deposit_stakes(
+        &self,
+        records: Vec<WithdrawsAndDepositStakes>,
+        mode: InsertMode,
+    ) -> Result<(), Error> {
+        self.bulk_insert(
+            mode,
+            "withdraw_and_deposit_stakes \
+            (id,epoch,vote_pubkey,withdraw_stake,deposit_stake) ",
+            5,
+            records,
+            |mut separated, record| {
+                separated.push_bind(record.id);
+                separated.push_bind(to_integer(record.epoch));
+                separated.push_bind(record.vote_pubkey.to_string());
+                separated.push_bind(record.withdraw_stake.to_string());
+                separated.push_bind(record.deposit_stake.to_string());
+            },
+        )
+        .await
+    }
+
+    async fn upsert_active_stake(&self, records: Vec<ActiveStakeJitoSol>) -> Result<(), Error> {
+        self.bulk_insert(
+            InsertMode::Upsert,
+            "active_stake_jito_sol (epoch,balance) ",
+            2,
+            records,
+            |mut separated, record| {
+                separated.push_bind(to_integer(record.epoch));
+                separated.push_bind(record.balance.to_string());
+            },
+        )
+        .await
+    }
+
+    async fn upsert_inactive_stake(&self, records: Vec<InactiveStakeJitoSol>) -> Result<(), Error> {
+        self.bulk_insert(
+            InsertMode::Upsert,
+            "inactive_stake_jito_sol (epoch,balance) ",
+            2,
+            records,
+            |mut separated, record| {
+                separated.push_bind(to_integer(record.epoch));
+                separated.push_bind(record.balance.to_string());
+            },
+        )
+        .await
+    }
+
+    async fn upsert_steward_blacklist(&self, record: StewardBlacklist) -> Result<(), Error> {
+        let vote_pubkeys =
+            serde_json::to_string(&record.vote_pubkeys).map_err(|e| Error::Encode(Box::new(e)))?;
+        sqlx::query(
+            "INSERT OR REPLACE INTO steward_blacklists (epoch, vote_pubkeys) VALUES ($1, $2)",
+        )
+        .bind(to_integer(record.epoch))
+        .bind(vote_pubkeys)
+        .execute(&self.db_connection)
+        .await?;
+        Ok(())
+    }
+}
+
+fn validator_history_entry_from_row(row: &SqliteRow) -> Result<ValidatorHistoryEntry, Error> {
+    let version: String = row.try_get("version")?;
+    let version: ClientVersion =
+        serde_json::from_str(&version).map_err(|_| decode_error("version"))?;
+    let ip: String = row.try_get("ip")?;
+    let ip: [u8; 4] = ip
+        .split(".")
+        .map(|x| x.parse::<u8>())
+        .collect::<Result<Vec<u8>, _>>()
+        .ok()
+        .and_then(|ip| ip.try_into().ok())
+        .ok_or_else(|| decode_error("ip"))?;
+
+    Ok(ValidatorHistoryEntry {
+        id: row.try_get("id")?,
+        vote_pubkey: get_pubkey(row, "vote_pubkey")?,
+        validator_history_entry: JitoValidatorHistoryEntry {
+            activated_stake_lamports: get_u64(row, "activated_stake_lamports")?,
+            epoch: get_int(row, "epoch")?,
+            mev_commission: get_int(row, "mev_commission")?,
+            epoch_credits: get_int(row, "epoch_credits")?,
+            commission: get_int(row, "commission")?,
+            client_type: get_int(row, "client_type")?,
+            version: JitoClientVersion {
+                major: version.major,
+                minor: version.minor,
+                patch: version.patch,
+            },
+            ip,
+            merkle_root_upload_authority: int_to_upload_authority(get_int(
+                row,
+                "merkle_root_upload_authority",
+            )?),
+            is_superminority: get_int(row, "is_superminority")?,
+            rank: get_int(row, "rank")?,
+            vote_account_last_update_slot: get_u64(row, "vote_account_last_update_slot")?,
+            mev_earned: get_int(row, "mev_earned")?,
+            priority_fee_commission: get_int(row, "priority_fee_commission")?,
+            priority_fee_tips: get_u64(row, "priority_fee_tips")?,
+            total_priority_fees: get_u64(row, "total_priority_fees")?,
+            total_leader_slots: get_int(row, "total_leader_slots")?,
+            blocks_produced: get_int(row, "blocks_produced")?,
+            block_data_updated_at_slot: get_u64(row, "block_data_updated_at_slot")?,
+            priority_fee_merkle_root_upload_authority: int_to_upload_authority(get_int(
+                row,
+                "priority_fee_merkle_root_upload_authority",
+            )?),
+            ..JitoValidatorHistoryEntry::default()
+        },
+    })
+}
+
+/// SQLite integers are signed, u64 values are stored as their bits
+fn to_integer(value: impl Into<u64>) -> i64 {
+    value.into() as i64
+}
+
+fn get_u64<T: From<u64>>(row: &SqliteRow, column: &str) -> Result<T, Error> {
+    let value: i64 = row.try_get(column)?;
+    Ok(T::from(value as u64))
+}
+
+fn get_int<T: TryFrom<i64>>(row: &SqliteRow, column: &str) -> Result<T, Error> {
+    let value: i64 = row.try_get(column)?;
+    T::try_from(value).map_err(|_| decode_error(column))
+}
+
+fn get_pubkey(row: &SqliteRow, column: &str) -> Result<VotePubkey, Error> {
+    let value: String = row.try_get(column)?;
+    VotePubkey::from_str(&value).map_err(|_| decode_error(column))
+}
+
+fn get_decimal(row: &SqliteRow, column: &str) -> Result<BigDecimal, Error> {
+    let value: String = row.try_get(column)?;
+    BigDecimal::from_str(&value).map_err(|_| decode_error(column))
+}
+
+fn get_repair_source(row: &SqliteRow, column: &str) -> Result<RepairSource, Error> {
+    let value: String = row.try_get(column)?;
+    RepairSource::from_str(&value).map_err(|e| Error::Decode(Box::new(e)))
+}
+
+fn decode_error(column: &str) -> Error {
+    Error::Decode(Box::new(StakenetSimulatorDbError::DecodeError(
+        column.to_string(),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sqlite_store() {
+        let store = SqliteStore::connect("sqlite::memory:").await.unwrap();
+        let entry = JitoValidatorHistoryEntry {
+            epoch: 700,
+            activated_stake_lamports: 1_000,
+            ip: [127, 0, 0, 1],
+            ..JitoValidatorHistoryEntry::default()
+        };
+        store
+            .insert_validator_history_entries(
+                vec![ValidatorHistoryEntry::new(VotePubkey::default(), entry)],
+                InsertMode::InsertIgnore,
+            )
+            .await
+            .unwrap();
+        store
+            .upsert_active_stake(vec![ActiveStakeJitoSol::new(
+                700,
+                BigDecimal::from_str("1234.5").unwrap(),
+            )])
+            .await
+            .unwrap();
+
+        let entries = store
+            .fetch_validator_history_entries(700, 700)
+            .await
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        let loaded = entries[0].validator_history_entry;
+        assert_eq!(loaded.activated_stake_lamports, 1_000);
+        assert_eq!(loaded.ip, [127, 0, 0, 1]);
+        // The u64::MAX defaults of the program survive the signed columns
+        assert_eq!(loaded.priority_fee_tips, u64::MAX);
+        assert!(
+            store
+                .fetch_validator_history_entries(701, 710)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // Entries recorded again are only overwritten when upserting
+        let updated = JitoValidatorHistoryEntry {
+            activated_stake_lamports: 2_000,
+            ..entry
+        };
+        for (mode, activated_stake_lamports) in [
+            (InsertMode::InsertIgnore, 1_000),
+            (InsertMode::Upsert, 2_000),
+        ] {
+            store
+                .insert_validator_history_entries(
+                    vec![ValidatorHistoryEntry::new(VotePubkey::default(), updated)],
+                    mode,
+                )
+                .await
+                .unwrap();
+            let entries = store
+                .fetch_validator_history_entries_by_validator(&VotePubkey::default())
+                .await
+                .unwrap();
+            assert_eq!(entries.len(), 1);
+            assert_eq!(
+                entries[0].validator_history_entry.activated_stake_lamports,
+                activated_stake_lamports
+            );
+        }
+
+        let active_stake = store.fetch_active_stake(690, 710).await.unwrap();
+        assert_eq!(active_stake[0].balance.to_string(), "1234.5");
+    }
+}
@@ -0,0 +1,121 @@
+-- Tables of the simulator in the embedded SQLite store. u64 values are stored as the bits of
+-- an INTEGER so that the u64::MAX defaults of the validator history program round trip, and
+-- balances as the text of the decimal.
+
+CREATE TABLE IF NOT EXISTS validator_histories (
+    vote_account TEXT PRIMARY KEY NOT NULL,
+    struct_version INTEGER NOT NULL,
+    "index" INTEGER NOT NULL,
+    bump INTEGER NOT NULL,
+    last_ip_timestamp INTEGER NOT NULL,
+    last_version_timestamp INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS validator_history_entries (
+    id TEXT PRIMARY KEY NOT NULL,
+    vote_pubkey TEXT NOT NULL,
+    activated_stake_lamports INTEGER NOT NULL,
+    epoch INTEGER NOT NULL,
+    mev_commission INTEGER NOT NULL,
+    epoch_credits INTEGER NOT NULL,
+    commission INTEGER NOT NULL,
+    client_type INTEGER NOT NULL,
+    version TEXT NOT NULL,
+    ip TEXT NOT NULL,
+    merkle_root_upload_authority INTEGER NOT NULL,
+    is_superminority INTEGER NOT NULL,
+    rank INTEGER NOT NULL,
+    vote_account_last_update_slot INTEGER NOT NULL,
+    mev_earned INTEGER NOT NULL,
+    priority_fee_commission INTEGER NOT NULL,
+    priority_fee_tips INTEGER NOT NULL,
+    total_priority_fees INTEGER NOT NULL,
+    total_leader_slots INTEGER NOT NULL,
+    blocks_produced INTEGER NOT NULL,
+    block_data_updated_at_slot INTEGER NOT NULL,
+    priority_fee_merkle_root_upload_authority INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS validator_history_entries_epoch_idx
+    ON validator_history_entries (epoch);
+
+CREATE TABLE IF NOT EXISTS validator_history_repairs (
+    epoch INTEGER NOT NULL,
+    vote_pubkey TEXT NOT NULL,
+    repair_version INTEGER NOT NULL,
+    epoch_credits INTEGER NOT NULL,
+    epoch_credits_source TEXT NOT NULL,
+    blocks_produced INTEGER NOT NULL,
+    total_leader_slots INTEGER NOT NULL,
+    block_production_source TEXT NOT NULL,
+    priority_fee_commission INTEGER NOT NULL,
+    priority_fee_tips INTEGER NOT NULL,
+    priority_fee_source TEXT NOT NULL,
+    PRIMARY KEY (epoch, vote_pubkey)
+);
+
+CREATE TABLE IF NOT EXISTS cluster_histories (
+    id INTEGER PRIMARY KEY NOT NULL,
+    struct_version INTEGER NOT NULL,
+    bump INTEGER NOT NULL,
+    cluster_history_last_update_slot INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS cluster_history_entries (
+    epoch INTEGER PRIMARY KEY NOT NULL,
+    total_blocks INTEGER NOT NULL,
+    epoch_start_timestamp INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS epoch_schedules (
+    id INTEGER PRIMARY KEY NOT NULL,
+    slots_per_epoch INTEGER NOT NULL,
+    leader_schedule_slot_offset INTEGER NOT NULL,
+    warmup INTEGER NOT NULL,
+    first_normal_epoch INTEGER NOT NULL,
+    first_normal_slot INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS epoch_rewards (
+    id TEXT PRIMARY KEY NOT NULL,
+    vote_pubkey TEXT NOT NULL,
+    epoch INTEGER NOT NULL,
+    inflation_commission_bps INTEGER NOT NULL,
+    total_inflation_rewards INTEGER NOT NULL,
+    mev_commission_bps INTEGER NOT NULL,
+    total_mev_rewards INTEGER NOT NULL,
+    priority_fee_commission_bps INTEGER NOT NULL,
+    total_priority_fee_rewards INTEGER NOT NULL,
+    active_stake INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS epoch_rewards_epoch_idx ON epoch_rewards (epoch);
+
+CREATE TABLE IF NOT EXISTS withdraw_and_deposit_sol (
+    epoch INTEGER PRIMARY KEY NOT NULL,
+    withdraw_sol TEXT NOT NULL,
+    deposit_sol TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS withdraw_and_deposit_stakes (
+    id TEXT PRIMARY KEY NOT NULL,
+    epoch INTEGER NOT NULL,
+    vote_pubkey TEXT NOT NULL,
+    withdraw_stake TEXT NOT NULL,
+    deposit_stake TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS active_stake_jito_sol (
+    epoch INTEGER PRIMARY KEY NOT NULL,
+    balance TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS inactive_stake_jito_sol (
+    epoch INTEGER PRIMARY KEY NOT NULL,
+    balance TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS steward_blacklists (
+    epoch INTEGER PRIMARY KEY NOT NULL,
+    vote_pubkeys TEXT NOT NULL
+);
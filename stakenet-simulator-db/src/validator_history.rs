@@ -1,7 +1,11 @@
-use crate::{big_decimal_u64::BigDecimalU64, validator_history_entry::ValidatorHistoryEntry};
-use solana_sdk::pubkey::Pubkey;
-use sqlx::{Error as SqlxError, Pool, Postgres, QueryBuilder, prelude::FromRow, types::BigDecimal};
-use std::{cmp::Ordering, str::FromStr};
+use crate::{
+    big_decimal_u64::BigDecimalU64,
+    bulk_insert::{InsertMode, bulk_insert},
+    domain::VotePubkey,
+    validator_history_entry::ValidatorHistoryEntry,
+};
+use sqlx::{Error as SqlxError, Pool, Postgres, prelude::FromRow, types::BigDecimal};
+use std::cmp::Ordering;
 use validator_history::{CircBuf, ValidatorHistory as JitoValidatorHistory};
 
 #[derive(FromRow, Clone)]
@@ -9,7 +13,7 @@ pub struct ValidatorHistory {
     #[sqlx(try_from = "i64")]
     pub struct_version: u32,
     /// Vote account pubkey
-    pub vote_account: String,
+    pub vote_account: VotePubkey,
     #[sqlx(try_from = "i64")]
     pub index: u32,
     #[sqlx(try_from = "i16")]
@@ -26,7 +30,7 @@ impl From<JitoValidatorHistory> for ValidatorHistory {
     fn from(value: JitoValidatorHistory) -> Self {
         Self {
             struct_version: value.struct_version,
-            vote_account: value.vote_account.to_string(),
+            vote_account: value.vote_account.into(),
             index: value.index,
             bump: value.bump,
             last_ip_timestamp: value.last_ip_timestamp,
@@ -36,54 +40,39 @@ impl From<JitoValidatorHistory> for ValidatorHistory {
 }
 
 impl ValidatorHistory {
-    /// Number of fields in the validator_histories table
-    const NUM_FIELDS: u8 = 6;
-    // Based on the bind limit of postgres
-    const INSERT_CHUNK_SIZE: usize = 65534 / Self::NUM_FIELDS as usize;
-    const INSERT_QUERY: &str = "INSERT INTO validator_histories (vote_account,struct_version,index,bump,last_ip_timestamp,last_version_timestamp) VALUES ";
+    const COLUMNS: &[&str] = &[
+        "vote_account",
+        "struct_version",
+        "index",
+        "bump",
+        "last_ip_timestamp",
+        "last_version_timestamp",
+    ];
+    const CONFLICT_TARGET: &[&str] = &["vote_account"];
 
     pub async fn bulk_insert(
         db_connection: &Pool<Postgres>,
         records: Vec<Self>,
+        mode: InsertMode,
     ) -> Result<(), SqlxError> {
-        if records.is_empty() {
-            return Ok(());
-        }
-
-        let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(Self::INSERT_QUERY);
-        let mut num_records: usize = 0;
-
-        for record in records.into_iter() {
-            num_records += 1;
-            if num_records > 1 {
-                query_builder.push(", (");
-            } else {
-                query_builder.push("(");
-            }
-            let mut separated = query_builder.separated(", ");
-            separated.push_bind(record.vote_account);
-            separated.push_bind(i64::from(record.struct_version));
-            separated.push_bind(i64::from(record.index));
-            separated.push_bind(i16::from(record.bump));
-            separated.push_bind(BigDecimal::from(record.last_ip_timestamp));
-            separated.push_bind(BigDecimal::from(record.last_version_timestamp));
-
-            separated.push_unseparated(") ");
-
-            if num_records >= Self::INSERT_CHUNK_SIZE {
-                query_builder.push(" ON CONFLICT (vote_account) DO NOTHING");
-                let query = query_builder.build();
-                query.execute(db_connection).await?;
-                num_records = 0;
-                query_builder = QueryBuilder::new(Self::INSERT_QUERY);
-            }
-        }
-
-        if num_records > 0 {
-            query_builder.push(" ON CONFLICT (vote_account) DO NOTHING");
-            let query = query_builder.build();
-            query.execute(db_connection).await?;
-        }
+        bulk_insert(
+            db_connection,
+            "validator_histories",
+            Self::COLUMNS,
+            Self::CONFLICT_TARGET,
+            |record: &Self| record.vote_account,
+            records,
+            mode,
+            |mut separated, record| {
+                separated.push_bind(record.vote_account);
+                separated.push_bind(i64::from(record.struct_version));
+                separated.push_bind(i64::from(record.index));
+                separated.push_bind(i16::from(record.bump));
+                separated.push_bind(BigDecimal::from(record.last_ip_timestamp));
+                separated.push_bind(BigDecimal::from(record.last_version_timestamp));
+            },
+        )
+        .await?;
         Ok(())
     }
 
@@ -100,7 +89,7 @@ impl ValidatorHistory {
     ) -> JitoValidatorHistory {
         let mut validator_history = JitoValidatorHistory {
             struct_version: self.struct_version,
-            vote_account: Pubkey::from_str(&self.vote_account).unwrap(),
+            vote_account: self.vote_account.into(),
             index: self.index,
             bump: self.bump,
             _padding0: [0u8; 7],
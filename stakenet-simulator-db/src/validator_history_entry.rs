@@ -1,7 +1,14 @@
-use crate::{big_decimal_u64::BigDecimalU64, decode_db, error::StakenetSimulatorDbError};
+use crate::{
+    big_decimal_u64::BigDecimalU64,
+    bulk_insert::{InsertMode, bulk_insert},
+    decode_db,
+    domain::{Epoch, VotePubkey},
+    error::StakenetSimulatorDbError,
+    validator_history_repair::PRIORITY_FEES_UNTRACKED_UNTIL_EPOCH,
+};
 use serde::{Deserialize, Serialize};
 use sqlx::{
-    Error, FromRow, Pool, Postgres, QueryBuilder, Row,
+    Error, FromRow, Pool, Postgres, Row,
     postgres::PgRow,
     types::{BigDecimal, Json},
 };
@@ -12,7 +19,7 @@ use validator_history::{
 #[derive(Clone)]
 pub struct ValidatorHistoryEntry {
     pub id: String,
-    pub vote_pubkey: String,
+    pub vote_pubkey: VotePubkey,
     pub validator_history_entry: JitoValidatorHistoryEntry,
 }
 
@@ -21,7 +28,7 @@ impl FromRow<'_, PgRow> for ValidatorHistoryEntry {
         let id = row.try_get("id")?;
         let vote_pubkey = row.try_get("vote_pubkey")?;
         let activated_stake_lamports: BigDecimalU64 = row.try_get("activated_stake_lamports")?;
-        let epoch: i32 = row.try_get("epoch")?;
+        let epoch: Epoch = row.try_get("epoch")?;
         let mev_commission: i32 = row.try_get("mev_commission")?;
         let epoch_credits: i64 = row.try_get("epoch_credits")?;
         let commission: i32 = row.try_get("commission")?;
@@ -95,7 +102,7 @@ impl FromRow<'_, PgRow> for ValidatorHistoryEntry {
     }
 }
 
-fn int_to_upload_authority(int: i16) -> validator_history::MerkleRootUploadAuthority {
+pub(crate) fn int_to_upload_authority(int: i16) -> validator_history::MerkleRootUploadAuthority {
     match int {
         0 | 255 => validator_history::MerkleRootUploadAuthority::Unset,
         1 => validator_history::MerkleRootUploadAuthority::Other,
@@ -109,13 +116,36 @@ fn int_to_upload_authority(int: i16) -> validator_history::MerkleRootUploadAutho
 }
 
 impl ValidatorHistoryEntry {
-    /// Number of fields in the validator_history_entries table
-    const NUM_FIELDS: u8 = 22;
-    // Based on the bind limit of postgres
-    const INSERT_CHUNK_SIZE: usize = 65534 / Self::NUM_FIELDS as usize;
-    const INSERT_QUERY: &str = "INSERT INTO validator_history_entries (id,vote_pubkey,activated_stake_lamports,epoch,mev_commission,epoch_credits,commission,client_type,version,ip,merkle_root_upload_authority,is_superminority,rank,vote_account_last_update_slot,mev_earned,priority_fee_commission,priority_fee_tips,total_priority_fees,total_leader_slots,blocks_produced,block_data_updated_at_slot,priority_fee_merkle_root_upload_authority) VALUES ";
+    const COLUMNS: &[&str] = &[
+        "id",
+        "vote_pubkey",
+        "activated_stake_lamports",
+        "epoch",
+        "mev_commission",
+        "epoch_credits",
+        "commission",
+        "client_type",
+        "version",
+        "ip",
+        "merkle_root_upload_authority",
+        "is_superminority",
+        "rank",
+        "vote_account_last_update_slot",
+        "mev_earned",
+        "priority_fee_commission",
+        "priority_fee_tips",
+        "total_priority_fees",
+        "total_leader_slots",
+        "blocks_produced",
+        "block_data_updated_at_slot",
+        "priority_fee_merkle_root_upload_authority",
+    ];
+    const CONFLICT_TARGET: &[&str] = &["id"];
 
-    pub fn new(vote_pubkey: String, validator_history_entry: JitoValidatorHistoryEntry) -> Self {
+    pub fn new(
+        vote_pubkey: VotePubkey,
+        validator_history_entry: JitoValidatorHistoryEntry,
+    ) -> Self {
         Self {
             id: format!("{}-{}", validator_history_entry.epoch, vote_pubkey),
             vote_pubkey,
@@ -126,135 +156,115 @@ impl ValidatorHistoryEntry {
     pub async fn bulk_insert(
         db_connection: &Pool<Postgres>,
         records: Vec<Self>,
+        mode: InsertMode,
     ) -> Result<(), Error> {
-        if records.is_empty() {
-            return Ok(());
-        }
-
-        let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(Self::INSERT_QUERY);
-        let mut num_records: usize = 0;
-
-        for record in records.into_iter() {
-            num_records += 1;
-            if num_records > 1 {
-                query_builder.push(", (");
-            } else {
-                query_builder.push("(");
-            }
-            let mut separated = query_builder.separated(", ");
-            separated.push_bind(record.id);
-            separated.push_bind(record.vote_pubkey);
-            separated.push_bind(BigDecimal::from(
-                record.validator_history_entry.activated_stake_lamports,
-            ));
-            separated.push_bind(i32::from(record.validator_history_entry.epoch));
-            separated
-                .push_bind(i32::from(record.validator_history_entry.mev_commission));
-            separated
-                .push_bind(i64::from(record.validator_history_entry.epoch_credits));
-            separated.push_bind(i32::from(record.validator_history_entry.commission));
-            separated.push_bind(i16::from(record.validator_history_entry.client_type));
-            let version: ClientVersion = record.validator_history_entry.version.into();
-            separated.push_bind(Json(version));
-            separated.push_bind(
-                record
-                    .validator_history_entry
-                    .ip
-                    .into_iter()
-                    .map(|x| x.to_string())
-                    .collect::<Vec<String>>()
-                    .join("."),
-            );
-
-            separated.push_bind(i16::from(
-                record.validator_history_entry.merkle_root_upload_authority as u8,
-            ));
-            separated.push_bind(i16::from(record.validator_history_entry.is_superminority));
-            separated.push_bind(i64::from(record.validator_history_entry.rank));
-            separated.push_bind(BigDecimal::from(
-                record.validator_history_entry.vote_account_last_update_slot,
-            ));
-            separated.push_bind(BigDecimal::from(record.validator_history_entry.mev_earned));
+        bulk_insert(
+            db_connection,
+            "validator_history_entries",
+            Self::COLUMNS,
+            Self::CONFLICT_TARGET,
+            |record: &Self| record.id.clone(),
+            records,
+            mode,
+            |mut separated, record| {
+                separated.push_bind(record.id);
+                separated.push_bind(record.vote_pubkey);
+                separated.push_bind(BigDecimal::from(
+                    record.validator_history_entry.activated_stake_lamports,
+                ));
+                separated.push_bind(i32::from(record.validator_history_entry.epoch));
+                separated.push_bind(i32::from(record.validator_history_entry.mev_commission));
+                separated.push_bind(i64::from(record.validator_history_entry.epoch_credits));
+                separated.push_bind(i32::from(record.validator_history_entry.commission));
+                separated.push_bind(i16::from(record.validator_history_entry.client_type));
+                let version: ClientVersion = record.validator_history_entry.version.into();
+                separated.push_bind(Json(version));
+                separated.push_bind(
+                    record
+                        .validator_history_entry
+                        .ip
+                        .into_iter()
+                        .map(|x| x.to_string())
+                        .collect::<Vec<String>>()
+                        .join("."),
+                );
 
-            // This is done as these fields were not tracked by the validator history porgram before this epoch
-            let priority_fee_commission = if record.validator_history_entry.epoch <= 735 {
-                0
-            } else {
-                i32::from(record.validator_history_entry.priority_fee_commission)
-            };
-            separated.push_bind(priority_fee_commission);
+                separated.push_bind(i16::from(
+                    record.validator_history_entry.merkle_root_upload_authority as u8,
+                ));
+                separated.push_bind(i16::from(record.validator_history_entry.is_superminority));
+                separated.push_bind(i64::from(record.validator_history_entry.rank));
+                separated.push_bind(BigDecimal::from(
+                    record.validator_history_entry.vote_account_last_update_slot,
+                ));
+                separated.push_bind(BigDecimal::from(record.validator_history_entry.mev_earned));
 
-            let priority_fee_tips = if record.validator_history_entry.epoch <= 735 {
-                BigDecimal::from(0)
-            } else {
-                BigDecimal::from(record.validator_history_entry.priority_fee_tips)
-            };
-            separated.push_bind(priority_fee_tips);
+                // This is done as these fields were not tracked by the validator history program
+                // before this epoch
+                let untracked =
+                    record.validator_history_entry.epoch <= PRIORITY_FEES_UNTRACKED_UNTIL_EPOCH;
+                let priority_fee_commission = if untracked {
+                    0
+                } else {
+                    i32::from(record.validator_history_entry.priority_fee_commission)
+                };
+                separated.push_bind(priority_fee_commission);
 
-            separated.push_bind(BigDecimal::from(
-                record.validator_history_entry.total_priority_fees,
-            ));
-            separated.push_bind(
-                i64::from(record.validator_history_entry.total_leader_slots),
-            );
-            separated
-                .push_bind(i64::from(record.validator_history_entry.blocks_produced));
-            separated.push_bind(BigDecimal::from(
-                record.validator_history_entry.block_data_updated_at_slot,
-            ));
+                let priority_fee_tips = if untracked {
+                    BigDecimal::from(0)
+                } else {
+                    BigDecimal::from(record.validator_history_entry.priority_fee_tips)
+                };
+                separated.push_bind(priority_fee_tips);
 
-            // TODO: The simulator is looking at historical data that has already been set to DNE. Hence,
-            // for now to prevent a lot of DNE's we currently modify the data to change all the DNE's to UNSET
-            // To be reverted when this issue is fixed
-            let mut value = record
-                .validator_history_entry
-                .priority_fee_merkle_root_upload_authority as u8;
+                separated.push_bind(BigDecimal::from(
+                    record.validator_history_entry.total_priority_fees,
+                ));
+                separated.push_bind(i64::from(record.validator_history_entry.total_leader_slots));
+                separated.push_bind(i64::from(record.validator_history_entry.blocks_produced));
+                separated.push_bind(BigDecimal::from(
+                    record.validator_history_entry.block_data_updated_at_slot,
+                ));
 
-            if value == 4 {
-                value = 255;
-            }
-
-            separated.push_bind(i16::from(value));
-
-            separated.push_unseparated(") ");
+                // TODO: The simulator is looking at historical data that has already been set to DNE. Hence,
+                // for now to prevent a lot of DNE's we currently modify the data to change all the DNE's to UNSET
+                // To be reverted when this issue is fixed
+                let mut value = record
+                    .validator_history_entry
+                    .priority_fee_merkle_root_upload_authority
+                    as u8;
 
-            if num_records >= Self::INSERT_CHUNK_SIZE {
-                query_builder.push(" ON CONFLICT (id) DO NOTHING");
-                let query = query_builder.build();
-                query.execute(db_connection).await?;
-                num_records = 0;
-                query_builder = QueryBuilder::new(Self::INSERT_QUERY);
-            }
-        }
+                if value == 4 {
+                    value = 255;
+                }
 
-        if num_records > 0 {
-            query_builder.push(" ON CONFLICT (id) DO NOTHING");
-            let query = query_builder.build();
-            query.execute(db_connection).await?;
-        }
+                separated.push_bind(i16::from(value));
+            },
+        )
+        .await?;
         Ok(())
     }
 
     pub async fn fetch_by_validator(
         db_connection: &Pool<Postgres>,
-        vote_pubkey: &str,
+        vote_pubkey: &VotePubkey,
     ) -> Result<Vec<Self>, Error> {
         sqlx::query_as::<_, Self>("SELECT * FROM validator_history_entries WHERE vote_pubkey = $1")
-        .bind(vote_pubkey)
-        .fetch_all(db_connection)
-        .await
+            .bind(*vote_pubkey)
+            .fetch_all(db_connection)
+            .await
     }
 
     pub async fn fetch_by_validator_and_epoch(
         db_connection: &Pool<Postgres>,
-        vote_pubkey: &str,
+        vote_pubkey: &VotePubkey,
         epoch: u64,
     ) -> Result<Option<Self>, Error> {
         let id = format!("{}-{}", epoch, vote_pubkey);
         sqlx::query_as::<_, Self>("SELECT * FROM validator_history_entries WHERE id = $1")
-        .bind(id)
-        .fetch_optional(db_connection)
-        .await
+            .bind(id)
+            .fetch_optional(db_connection)
+            .await
     }
 
     pub async fn fetch_all_records_between_epochs(
@@ -262,21 +272,21 @@ impl ValidatorHistoryEntry {
         start_epoch: u64,
         end_epoch: u64,
     ) -> Result<Vec<Self>, Error> {
-        sqlx::query_as::<_, Self>("SELECT * FROM validator_history_entries WHERE epoch >= $1 AND epoch <= $2")
-        .bind(start_epoch as i32)
-        .bind(end_epoch as i32)
+        sqlx::query_as::<_, Self>(
+            "SELECT * FROM validator_history_entries WHERE epoch >= $1 AND epoch <= $2",
+        )
+        .bind(Epoch(start_epoch))
+        .bind(Epoch(end_epoch))
         .fetch_all(db_connection)
         .await
     }
 
     pub async fn get_all_vote_pubkeys(
         db_connection: &Pool<Postgres>,
-    ) -> Result<Vec<String>, Error> {
-        let pubkeys = sqlx::query_as::<_, VotePubkey>("SELECT DISTINCT ON(vote_pubkey) vote_pubkey FROM validator_history_entries GROUP BY vote_pubkey")
+    ) -> Result<Vec<VotePubkey>, Error> {
+        sqlx::query_scalar::<_, VotePubkey>("SELECT DISTINCT ON(vote_pubkey) vote_pubkey FROM validator_history_entries GROUP BY vote_pubkey")
         .fetch_all(db_connection)
-        .await?;
-
-        Ok(pubkeys.into_iter().map(|row| row.vote_pubkey).collect())
+        .await
     }
 
     pub async fn fetch_all_validator_history_entries(
@@ -288,11 +298,6 @@ impl ValidatorHistoryEntry {
     }
 }
 
-#[derive(FromRow)]
-struct VotePubkey {
-    vote_pubkey: String,
-}
-
 #[derive(Deserialize, Serialize)]
 pub struct ClientVersion {
     pub major: u8,
@@ -0,0 +1,373 @@
+use crate::{
+    bulk_insert::{InsertMode, bulk_insert},
+    domain::{Bps, Epoch, Lamports, VotePubkey},
+    error::StakenetSimulatorDbError,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{Error, FromRow, Pool, Postgres, types::BigDecimal};
+use std::str::FromStr;
+use validator_history::ValidatorHistoryEntry as JitoValidatorHistoryEntry;
+
+/// Version of the repair rules, bumped whenever they change so that repairs can be recomputed
+pub const REPAIR_VERSION: i32 = 2;
+
+/// Last epoch where the validator history program did not track priority fees
+pub const PRIORITY_FEES_UNTRACKED_UNTIL_EPOCH: u16 = 735;
+
+/// Where the value of a repaired field comes from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(
+    type_name = "validator_history_repair_source",
+    rename_all = "snake_case"
+)]
+pub enum RepairSource {
+    /// Recorded by the validator history program
+    Recorded,
+    /// Reconstructed from the epoch credits of the vote account
+    VoteAccount,
+    /// Reconstructed from the block production of the cluster
+    BlockProduction,
+    /// Set to a default as the program did not track the field
+    Default,
+    /// Not recorded and could not be reconstructed, the recorded value is kept
+    Missing,
+}
+
+impl RepairSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Recorded => "recorded",
+            Self::VoteAccount => "vote_account",
+            Self::BlockProduction => "block_production",
+            Self::Default => "default",
+            Self::Missing => "missing",
+        }
+    }
+}
+
+impl FromStr for RepairSource {
+    type Err = StakenetSimulatorDbError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "recorded" => Ok(Self::Recorded),
+            "vote_account" => Ok(Self::VoteAccount),
+            "block_production" => Ok(Self::BlockProduction),
+            "default" => Ok(Self::Default),
+            "missing" => Ok(Self::Missing),
+            _ => Err(StakenetSimulatorDbError::DecodeError(format!(
+                "repair source {value}"
+            ))),
+        }
+    }
+}
+
+/// Values of an epoch recovered from RPC, used to fill the fields the program did not record
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RecoveredEpochData {
+    /// Credits earned by the vote account during the epoch
+    pub epoch_credits: Option<u32>,
+    /// Leader slots and blocks produced by the validator identity during the epoch
+    pub block_production: Option<(u32, u32)>,
+}
+
+/// Normalized values of the validator history fields that need repairing for an epoch, kept
+/// apart from the recorded entries
+#[derive(Clone, Debug, FromRow, PartialEq)]
+pub struct ValidatorHistoryRepair {
+    pub epoch: Epoch,
+    pub vote_pubkey: VotePubkey,
+    pub repair_version: i32,
+    #[sqlx(try_from = "i64")]
+    pub epoch_credits: u32,
+    pub epoch_credits_source: RepairSource,
+    #[sqlx(try_from = "i64")]
+    pub blocks_produced: u32,
+    #[sqlx(try_from = "i64")]
+    pub total_leader_slots: u32,
+    pub block_production_source: RepairSource,
+    pub priority_fee_commission: Bps,
+    pub priority_fee_tips: Lamports,
+    pub priority_fee_source: RepairSource,
+}
+
+impl ValidatorHistoryRepair {
+    const COLUMNS: &[&str] = &[
+        "epoch",
+        "vote_pubkey",
+        "repair_version",
+        "epoch_credits",
+        "epoch_credits_source",
+        "blocks_produced",
+        "total_leader_slots",
+        "block_production_source",
+        "priority_fee_commission",
+        "priority_fee_tips",
+        "priority_fee_source",
+    ];
+    const CONFLICT_TARGET: &[&str] = &["epoch", "vote_pubkey"];
+
+    /// Applies the repair rules to a recorded entry. Fields still holding the default value of
+    /// the program are filled from `recovered`, and priority fees are zeroed for the epochs the
+    /// program did not track them. Unset priority fees of later epochs are left missing, like the
+    /// program scores them.
+    pub fn new(
+        vote_pubkey: VotePubkey,
+        entry: &JitoValidatorHistoryEntry,
+        recovered: RecoveredEpochData,
+    ) -> Self {
+        let (epoch_credits, epoch_credits_source) = if entry.epoch_credits != u32::MAX {
+            (entry.epoch_credits, RepairSource::Recorded)
+        } else {
+            match recovered.epoch_credits {
+                Some(epoch_credits) => (epoch_credits, RepairSource::VoteAccount),
+                None => (entry.epoch_credits, RepairSource::Missing),
+            }
+        };
+
+        let (total_leader_slots, blocks_produced, block_production_source) =
+            if entry.blocks_produced != u32::MAX && entry.total_leader_slots != u32::MAX {
+                (
+                    entry.total_leader_slots,
+                    entry.blocks_produced,
+                    RepairSource::Recorded,
+                )
+            } else {
+                match recovered.block_production {
+                    Some((total_leader_slots, blocks_produced)) => (
+                        total_leader_slots,
+                        blocks_produced,
+                        RepairSource::BlockProduction,
+                    ),
+                    None => (
+                        entry.total_leader_slots,
+                        entry.blocks_produced,
+                        RepairSource::Missing,
+                    ),
+                }
+            };
+
+        let priority_fee_source = if entry.epoch <= PRIORITY_FEES_UNTRACKED_UNTIL_EPOCH {
+            RepairSource::Default
+        } else if entry.priority_fee_commission == u16::MAX || entry.priority_fee_tips == u64::MAX {
+            RepairSource::Missing
+        } else {
+            RepairSource::Recorded
+        };
+        let (priority_fee_commission, priority_fee_tips) =
+            if priority_fee_source == RepairSource::Default {
+                (0, 0)
+            } else {
+                (entry.priority_fee_commission, entry.priority_fee_tips)
+            };
+
+        Self {
+            epoch: entry.epoch.into(),
+            vote_pubkey,
+            repair_version: REPAIR_VERSION,
+            epoch_credits,
+            epoch_credits_source,
+            blocks_produced,
+            total_leader_slots,
+            block_production_source,
+            priority_fee_commission: Bps(priority_fee_commission),
+            priority_fee_tips: Lamports(priority_fee_tips),
+            priority_fee_source,
+        }
+    }
+
+    /// Returns true if any field differs from what the program recorded
+    pub fn is_repaired(&self) -> bool {
+        [
+            self.epoch_credits_source,
+            self.block_production_source,
+            self.priority_fee_source,
+        ]
+        .iter()
+        .any(|source| *source != RepairSource::Recorded)
+    }
+
+    /// Keeps the values of an earlier repair for the fields this one could not reconstruct, as
+    /// RPC only serves the recent epochs of vote accounts and block production. Repairs made
+    /// with older repair rules are overwritten.
+    pub fn merge(mut self, previous: &Self) -> Self {
+        if previous.repair_version < REPAIR_VERSION {
+            return self;
+        }
+        if self.epoch_credits_source == RepairSource::Missing {
+            self.epoch_credits = previous.epoch_credits;
+            self.epoch_credits_source = previous.epoch_credits_source;
+        }
+        if self.block_production_source == RepairSource::Missing {
+            self.blocks_produced = previous.blocks_produced;
+            self.total_leader_slots = previous.total_leader_slots;
+            self.block_production_source = previous.block_production_source;
+        }
+        self
+    }
+
+    /// Overwrites the fields of a recorded entry with the repaired values
+    pub fn apply(&self, entry: &mut JitoValidatorHistoryEntry) {
+        if self.epoch_credits_source != RepairSource::Missing {
+            entry.epoch_credits = self.epoch_credits;
+        }
+        if self.block_production_source != RepairSource::Missing {
+            entry.blocks_produced = self.blocks_produced;
+            entry.total_leader_slots = self.total_leader_slots;
+        }
+        if self.priority_fee_source != RepairSource::Missing {
+            entry.priority_fee_commission = self.priority_fee_commission.into();
+            entry.priority_fee_tips = self.priority_fee_tips.into();
+        }
+    }
+
+    pub async fn bulk_insert(
+        db_connection: &Pool<Postgres>,
+        records: Vec<Self>,
+        mode: InsertMode,
+    ) -> Result<(), Error> {
+        bulk_insert(
+            db_connection,
+            "validator_history_repairs",
+            Self::COLUMNS,
+            Self::CONFLICT_TARGET,
+            |record: &Self| (record.epoch, record.vote_pubkey),
+            records,
+            mode,
+            |mut separated, record| {
+                separated.push_bind(record.epoch);
+                separated.push_bind(record.vote_pubkey);
+                separated.push_bind(record.repair_version);
+                separated.push_bind(i64::from(record.epoch_credits));
+                separated.push_bind(record.epoch_credits_source);
+                separated.push_bind(i64::from(record.blocks_produced));
+                separated.push_bind(i64::from(record.total_leader_slots));
+                separated.push_bind(record.block_production_source);
+                separated.push_bind(record.priority_fee_commission);
+                separated.push_bind(record.priority_fee_tips);
+                separated.push_bind(record.priority_fee_source);
+            },
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Deletes the repairs of a validator for the epochs, returns the number of rows deleted
+    pub async fn delete(
+        db_connection: &Pool<Postgres>,
+        vote_pubkey: &VotePubkey,
+        epochs: &[Epoch],
+    ) -> Result<u64, Error> {
+        let epochs: Vec<BigDecimal> = epochs
+            .iter()
+            .map(|epoch| BigDecimal::from(*epoch))
+            .collect();
+        let result = sqlx::query(
+            "DELETE FROM validator_history_repairs WHERE vote_pubkey = $1 AND epoch = ANY($2)",
+        )
+        .bind(vote_pubkey)
+        .bind(epochs)
+        .execute(db_connection)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn fetch_all(db_connection: &Pool<Postgres>) -> Result<Vec<Self>, Error> {
+        sqlx::query_as::<_, Self>("SELECT * FROM validator_history_repairs")
+            .fetch_all(db_connection)
+            .await
+    }
+
+    /// Fetches the repairs of epochs `start_epoch..=end_epoch`, matching the range of
+    /// `ValidatorHistoryEntry::fetch_all_records_between_epochs`
+    pub async fn fetch_between_epochs(
+        db_connection: &Pool<Postgres>,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<Self>, Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT * FROM validator_history_repairs WHERE epoch >= $1 AND epoch <= $2",
+        )
+        .bind(Epoch(start_epoch))
+        .bind(Epoch(end_epoch))
+        .fetch_all(db_connection)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(epoch: u16) -> JitoValidatorHistoryEntry {
+        JitoValidatorHistoryEntry {
+            epoch,
+            ..JitoValidatorHistoryEntry::default()
+        }
+    }
+
+    #[test]
+    fn test_validator_history_repair() {
+        let recovered = RecoveredEpochData {
+            epoch_credits: Some(6_000_000),
+            block_production: None,
+        };
+        let repair = ValidatorHistoryRepair::new(VotePubkey::default(), &entry(700), recovered);
+        assert_eq!(repair.epoch_credits_source, RepairSource::VoteAccount);
+        assert_eq!(repair.block_production_source, RepairSource::Missing);
+        assert_eq!(repair.priority_fee_source, RepairSource::Default);
+
+        let previous = ValidatorHistoryRepair {
+            blocks_produced: 40,
+            total_leader_slots: 44,
+            block_production_source: RepairSource::BlockProduction,
+            ..repair.clone()
+        };
+        // Repairs of older rules are recomputed instead of kept
+        let stale = ValidatorHistoryRepair {
+            repair_version: REPAIR_VERSION - 1,
+            ..previous.clone()
+        };
+        assert_eq!(
+            repair.clone().merge(&stale).block_production_source,
+            RepairSource::Missing
+        );
+        let repair = repair.merge(&previous);
+        let mut repaired = entry(700);
+        repair.apply(&mut repaired);
+        assert_eq!(repaired.epoch_credits, 6_000_000);
+        assert_eq!(repaired.blocks_produced, 40);
+        assert_eq!(repaired.total_leader_slots, 44);
+        assert_eq!(repaired.priority_fee_commission, 0);
+
+        let recorded = JitoValidatorHistoryEntry {
+            epoch_credits: 10,
+            total_leader_slots: 4,
+            blocks_produced: 4,
+            priority_fee_commission: 500,
+            priority_fee_tips: 0,
+            ..entry(800)
+        };
+        let repair = ValidatorHistoryRepair::new(
+            VotePubkey::default(),
+            &recorded,
+            RecoveredEpochData::default(),
+        );
+        assert!(!repair.is_repaired());
+
+        let unset = JitoValidatorHistoryEntry {
+            priority_fee_commission: u16::MAX,
+            ..recorded
+        };
+        let repair = ValidatorHistoryRepair::new(
+            VotePubkey::default(),
+            &unset,
+            RecoveredEpochData::default(),
+        );
+        assert_eq!(repair.priority_fee_source, RepairSource::Missing);
+        let mut repaired = unset;
+        repair.apply(&mut repaired);
+        assert_eq!(repaired.priority_fee_commission, u16::MAX);
+    }
+}
@@ -1,32 +1,38 @@
-use crate::big_decimal_u64::BigDecimalU64;
-use sqlx::{Error, FromRow, Pool, Postgres, QueryBuilder, types::BigDecimal};
+use crate::{
+    bulk_insert::{InsertMode, bulk_insert},
+    domain::{Epoch, VotePubkey},
+};
+use sqlx::{Error, FromRow, Pool, Postgres, types::BigDecimal};
 
 #[derive(FromRow, Debug)]
 pub struct WithdrawsAndDepositStakes {
     pub id: String, // {epoch}-{vote_pubkey}
-    #[sqlx(try_from = "BigDecimalU64")]
-    pub epoch: u64,
-    pub vote_pubkey: String,
+    pub epoch: Epoch,
+    pub vote_pubkey: VotePubkey,
     pub withdraw_stake: BigDecimal,
     pub deposit_stake: BigDecimal,
 }
 
 impl WithdrawsAndDepositStakes {
-    const NUM_FIELDS: u8 = 5;
-    const INSERT_CHUNK_SIZE: usize = 65534 / Self::NUM_FIELDS as usize;
-    const INSERT_QUERY: &str = "INSERT INTO withdraw_and_deposit_stakes \
-        (id, epoch, vote_pubkey, withdraw_stake, deposit_stake) VALUES ";
+    const COLUMNS: &[&str] = &[
+        "id",
+        "epoch",
+        "vote_pubkey",
+        "withdraw_stake",
+        "deposit_stake",
+    ];
+    const CONFLICT_TARGET: &[&str] = &["id"];
 
     pub fn new(
         epoch: u64,
-        vote_pubkey: String,
+        vote_pubkey: VotePubkey,
         withdraw_stake: BigDecimal,
         deposit_stake: BigDecimal,
     ) -> Self {
         let id = format!("{}-{}", epoch, vote_pubkey);
         Self {
             id,
-            epoch,
+            epoch: Epoch(epoch),
             vote_pubkey,
             withdraw_stake,
             deposit_stake,
@@ -36,51 +42,32 @@ impl WithdrawsAndDepositStakes {
     pub async fn bulk_insert(
         db_connection: &Pool<Postgres>,
         records: Vec<Self>,
+        mode: InsertMode,
     ) -> Result<(), Error> {
-        if records.is_empty() {
-            return Ok(());
-        }
-
-        let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(Self::INSERT_QUERY);
-        let mut num_records: usize = 0;
-
-        for record in records.into_iter() {
-            num_records += 1;
-            if num_records > 1 {
-                query_builder.push(", (");
-            } else {
-                query_builder.push("(");
-            }
-
-            let mut separated = query_builder.separated(", ");
-            separated.push_bind(record.id);
-            separated.push_bind(BigDecimal::from(record.epoch));
-            separated.push_bind(record.vote_pubkey);
-            separated.push_bind(record.withdraw_stake);
-            separated.push_bind(record.deposit_stake);
-            separated.push_unseparated(") ");
-
-            if num_records >= Self::INSERT_CHUNK_SIZE {
-                query_builder.push(" ON CONFLICT (id) DO NOTHING");
-                let query = query_builder.build();
-                query.execute(db_connection).await?;
-                num_records = 0;
-                query_builder = QueryBuilder::new(Self::INSERT_QUERY);
-            }
-        }
-
-        if num_records > 0 {
-            query_builder.push(" ON CONFLICT (id) DO NOTHING");
-            let query = query_builder.build();
-            query.execute(db_connection).await?;
-        }
+        bulk_insert(
+            db_connection,
+            "withdraw_and_deposit_stakes",
+            Self::COLUMNS,
+            Self::CONFLICT_TARGET,
+            |record: &Self| record.id.clone(),
+            records,
+            mode,
+            |mut separated, record| {
+                separated.push_bind(record.id);
+                separated.push_bind(record.epoch);
+                separated.push_bind(record.vote_pubkey);
+                separated.push_bind(record.withdraw_stake);
+                separated.push_bind(record.deposit_stake);
+            },
+        )
+        .await?;
         Ok(())
     }
 
     pub async fn get_records_for_epoch_range(
         db_connection: &Pool<Postgres>,
-        start_epoch: i64,
-        end_epoch: i64,
+        start_epoch: u64,
+        end_epoch: u64,
     ) -> Result<Vec<Self>, Error> {
         let query = r#"
             SELECT id, epoch, vote_pubkey, withdraw_stake, deposit_stake
@@ -90,8 +77,8 @@ impl WithdrawsAndDepositStakes {
         "#;
 
         sqlx::query_as::<_, Self>(query)
-            .bind(start_epoch)
-            .bind(end_epoch)
+            .bind(Epoch(start_epoch))
+            .bind(Epoch(end_epoch))
             .fetch_all(db_connection)
             .await
     }
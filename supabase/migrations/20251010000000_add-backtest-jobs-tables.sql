@@ -0,0 +1,94 @@
+CREATE TYPE "public"."backtest_job_status" AS ENUM ('queued', 'running', 'completed', 'failed');
+
+CREATE TABLE
+    IF NOT EXISTS "public"."backtest_jobs" (
+        "id" TEXT NOT NULL PRIMARY KEY,
+        "status" "public"."backtest_job_status" NOT NULL DEFAULT 'queued',
+        "request" JSONB NOT NULL,
+        "error" TEXT,
+        "created_at" TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        "updated_at" TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    );
+
+CREATE TABLE
+    IF NOT EXISTS "public"."backtest_job_reports" (
+        "job_id" TEXT NOT NULL PRIMARY KEY REFERENCES "public"."backtest_jobs" ("id") ON DELETE CASCADE,
+        "report" JSONB NOT NULL,
+        "created_at" TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    );
+
+
+-- Enable RLS
+ALTER TABLE public.backtest_jobs ENABLE ROW LEVEL SECURITY;
+
+ALTER TABLE public.backtest_job_reports ENABLE ROW LEVEL SECURITY;
+
+-- Grants: anon
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.backtest_jobs TO anon;
+
+-- Grants: authenticated
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.backtest_jobs TO authenticated;
+
+-- Grants: service_role
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.backtest_jobs TO service_role;
+
+-- Grants: anon
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.backtest_job_reports TO anon;
+
+-- Grants: authenticated
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.backtest_job_reports TO authenticated;
+
+-- Grants: service_role
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.backtest_job_reports TO service_role;
+
+-- Policy: Enable read access for all users
+CREATE POLICY "Enable read access for all users" ON public.backtest_jobs AS PERMISSIVE FOR
+SELECT
+    TO public USING (TRUE);
+
+-- Policy: Enable read access for all users
+CREATE POLICY "Enable read access for all users" ON public.backtest_job_reports AS PERMISSIVE FOR
+SELECT
+    TO public USING (TRUE);
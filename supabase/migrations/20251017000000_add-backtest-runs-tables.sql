@@ -0,0 +1,149 @@
+CREATE TABLE
+    IF NOT EXISTS "public"."backtest_runs" (
+        "id" TEXT NOT NULL PRIMARY KEY,
+        "created_at" TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        "git_revision" TEXT NOT NULL,
+        "scenario" TEXT,
+        "start_epoch" "public"."u_64" NOT NULL,
+        "end_epoch" "public"."u_64" NOT NULL,
+        "seed" "public"."u_64" NOT NULL,
+        "number_of_validator_delegations" BIGINT NOT NULL,
+        "parameters" JSONB NOT NULL,
+        "aggregated_apy" DOUBLE PRECISION NOT NULL,
+        "stake_utilization_ratio" DOUBLE PRECISION NOT NULL,
+        "final_apy" DOUBLE PRECISION NOT NULL
+    );
+
+CREATE TABLE
+    IF NOT EXISTS "public"."backtest_cycles" (
+        "run_id" TEXT NOT NULL REFERENCES "public"."backtest_runs" ("id") ON DELETE CASCADE,
+        "cycle_index" BIGINT NOT NULL,
+        "start_epoch" "public"."u_64" NOT NULL,
+        "end_epoch" "public"."u_64" NOT NULL,
+        "starting_total_lamports" "public"."u_64" NOT NULL,
+        "ending_total_lamports" "public"."u_64" NOT NULL,
+        PRIMARY KEY ("run_id", "cycle_index")
+    );
+
+CREATE TABLE
+    IF NOT EXISTS "public"."backtest_validator_results" (
+        "run_id" TEXT NOT NULL REFERENCES "public"."backtest_runs" ("id") ON DELETE CASCADE,
+        "vote_pubkey" "public"."solana_pubkey" NOT NULL,
+        "cycles_selected" BIGINT NOT NULL,
+        "last_score" DOUBLE PRECISION NOT NULL,
+        "final_stake_lamports" "public"."u_64" NOT NULL,
+        PRIMARY KEY ("run_id", "vote_pubkey")
+    );
+
+
+-- Enable RLS
+ALTER TABLE public.backtest_runs ENABLE ROW LEVEL SECURITY;
+
+ALTER TABLE public.backtest_cycles ENABLE ROW LEVEL SECURITY;
+
+ALTER TABLE public.backtest_validator_results ENABLE ROW LEVEL SECURITY;
+
+-- Grants: anon
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.backtest_runs TO anon;
+
+-- Grants: authenticated
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.backtest_runs TO authenticated;
+
+-- Grants: service_role
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.backtest_runs TO service_role;
+
+-- Grants: anon
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.backtest_cycles TO anon;
+
+-- Grants: authenticated
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.backtest_cycles TO authenticated;
+
+-- Grants: service_role
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.backtest_cycles TO service_role;
+
+-- Grants: anon
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.backtest_validator_results TO anon;
+
+-- Grants: authenticated
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.backtest_validator_results TO authenticated;
+
+-- Grants: service_role
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.backtest_validator_results TO service_role;
+
+-- Policy: Enable read access for all users
+CREATE POLICY "Enable read access for all users" ON public.backtest_runs AS PERMISSIVE FOR
+SELECT
+    TO public USING (TRUE);
+
+-- Policy: Enable read access for all users
+CREATE POLICY "Enable read access for all users" ON public.backtest_cycles AS PERMISSIVE FOR
+SELECT
+    TO public USING (TRUE);
+
+-- Policy: Enable read access for all users
+CREATE POLICY "Enable read access for all users" ON public.backtest_validator_results AS PERMISSIVE FOR
+SELECT
+    TO public USING (TRUE);
@@ -0,0 +1,95 @@
+CREATE TABLE
+    IF NOT EXISTS "public"."steward_delegations" (
+        "epoch" "public"."u_64" NOT NULL,
+        "vote_pubkey" "public"."solana_pubkey" NOT NULL,
+        "validator_list_index" INTEGER NOT NULL,
+        "score" "public"."u_64" NOT NULL,
+        "yield_score" "public"."u_64" NOT NULL,
+        "delegation_numerator" "public"."u_64" NOT NULL,
+        "delegation_denominator" "public"."u_64" NOT NULL,
+        "instant_unstake" BOOLEAN NOT NULL,
+        "active_stake_lamports" "public"."u_64" NOT NULL,
+        PRIMARY KEY ("epoch", "vote_pubkey")
+    );
+
+CREATE TABLE
+    IF NOT EXISTS "public"."steward_config_snapshots" (
+        "epoch" "public"."u_64" NOT NULL PRIMARY KEY,
+        "config_data" BYTEA NOT NULL
+    );
+
+
+-- Enable RLS
+ALTER TABLE public.steward_delegations ENABLE ROW LEVEL SECURITY;
+
+ALTER TABLE public.steward_config_snapshots ENABLE ROW LEVEL SECURITY;
+
+-- Grants: anon
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.steward_delegations TO anon;
+
+-- Grants: authenticated
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.steward_delegations TO authenticated;
+
+-- Grants: service_role
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.steward_delegations TO service_role;
+
+-- Grants: anon
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.steward_config_snapshots TO anon;
+
+-- Grants: authenticated
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.steward_config_snapshots TO authenticated;
+
+-- Grants: service_role
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.steward_config_snapshots TO service_role;
+
+-- Policy: Enable read access for all users
+CREATE POLICY "Enable read access for all users" ON public.steward_delegations AS PERMISSIVE FOR
+SELECT
+    TO public USING (TRUE);
+
+-- Policy: Enable read access for all users
+CREATE POLICY "Enable read access for all users" ON public.steward_config_snapshots AS PERMISSIVE FOR
+SELECT
+    TO public USING (TRUE);
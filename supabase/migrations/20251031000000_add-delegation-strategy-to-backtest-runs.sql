@@ -0,0 +1,2 @@
+ALTER TABLE "public"."backtest_runs"
+ADD COLUMN IF NOT EXISTS "delegation_strategy" TEXT NOT NULL DEFAULT 'steward';
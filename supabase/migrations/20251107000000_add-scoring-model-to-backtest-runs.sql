@@ -0,0 +1,2 @@
+ALTER TABLE "public"."backtest_runs"
+ADD COLUMN IF NOT EXISTS "scoring_model" TEXT NOT NULL DEFAULT 'on-chain';
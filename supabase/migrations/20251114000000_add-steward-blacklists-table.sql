@@ -0,0 +1,44 @@
+CREATE TABLE
+    IF NOT EXISTS "public"."steward_blacklists" (
+        "epoch" "public"."u_64" NOT NULL PRIMARY KEY,
+        "vote_pubkeys" TEXT[] NOT NULL
+    );
+
+
+-- Enable RLS
+ALTER TABLE public.steward_blacklists ENABLE ROW LEVEL SECURITY;
+
+-- Grants: anon
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.steward_blacklists TO anon;
+
+-- Grants: authenticated
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.steward_blacklists TO authenticated;
+
+-- Grants: service_role
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.steward_blacklists TO service_role;
+
+-- Policy: Enable read access for all users
+CREATE POLICY "Enable read access for all users" ON public.steward_blacklists AS PERMISSIVE FOR
+SELECT
+    TO public USING (TRUE);
@@ -0,0 +1,48 @@
+CREATE TABLE
+    IF NOT EXISTS "public"."epoch_schedules" (
+        "id" INTEGER NOT NULL PRIMARY KEY,
+        "slots_per_epoch" "public"."u_64" NOT NULL,
+        "leader_schedule_slot_offset" "public"."u_64" NOT NULL,
+        "warmup" BOOLEAN NOT NULL,
+        "first_normal_epoch" "public"."u_64" NOT NULL,
+        "first_normal_slot" "public"."u_64" NOT NULL
+    );
+
+
+-- Enable RLS
+ALTER TABLE public.epoch_schedules ENABLE ROW LEVEL SECURITY;
+
+-- Grants: anon
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.epoch_schedules TO anon;
+
+-- Grants: authenticated
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.epoch_schedules TO authenticated;
+
+-- Grants: service_role
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.epoch_schedules TO service_role;
+
+-- Policy: Enable read access for all users
+CREATE POLICY "Enable read access for all users" ON public.epoch_schedules AS PERMISSIVE FOR
+SELECT
+    TO public USING (TRUE);
@@ -0,0 +1,62 @@
+CREATE TYPE "public"."validator_history_repair_source" AS ENUM (
+    'recorded',
+    'vote_account',
+    'block_production',
+    'default',
+    'missing'
+);
+
+CREATE TABLE
+    IF NOT EXISTS "public"."validator_history_repairs" (
+        "epoch" "public"."u_64" NOT NULL,
+        "vote_pubkey" "public"."solana_pubkey" NOT NULL,
+        "repair_version" INTEGER NOT NULL,
+        "epoch_credits" BIGINT NOT NULL,
+        "epoch_credits_source" "public"."validator_history_repair_source" NOT NULL,
+        "blocks_produced" BIGINT NOT NULL,
+        "total_leader_slots" BIGINT NOT NULL,
+        "block_production_source" "public"."validator_history_repair_source" NOT NULL,
+        "priority_fee_commission" INTEGER NOT NULL,
+        "priority_fee_tips" "public"."u_64" NOT NULL,
+        "priority_fee_source" "public"."validator_history_repair_source" NOT NULL,
+        PRIMARY KEY ("epoch", "vote_pubkey")
+    );
+
+
+-- Enable RLS
+ALTER TABLE public.validator_history_repairs ENABLE ROW LEVEL SECURITY;
+
+-- Grants: anon
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.validator_history_repairs TO anon;
+
+-- Grants: authenticated
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.validator_history_repairs TO authenticated;
+
+-- Grants: service_role
+GRANT DELETE,
+INSERT,
+REFERENCES,
+SELECT
+,
+    TRIGGER,
+    TRUNCATE,
+UPDATE ON public.validator_history_repairs TO service_role;
+
+-- Policy: Enable read access for all users
+CREATE POLICY "Enable read access for all users" ON public.validator_history_repairs AS PERMISSIVE FOR
+SELECT
+    TO public USING (TRUE);
@@ -0,0 +1,40 @@
+--
+-- Backtest Jobs Tables
+--
+-- These tables store the backtest jobs submitted through the API and the report of every
+-- completed job
+--
+CREATE TYPE "public"."backtest_job_status" AS ENUM ('queued', 'running', 'completed', 'failed');
+
+CREATE TABLE
+    IF NOT EXISTS "public"."backtest_jobs" (
+        "id" TEXT NOT NULL PRIMARY KEY,
+        "status" "public"."backtest_job_status" NOT NULL DEFAULT 'queued',
+        "request" JSONB NOT NULL,
+        "error" TEXT,
+        "created_at" TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        "updated_at" TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    );
+
+CREATE TABLE
+    IF NOT EXISTS "public"."backtest_job_reports" (
+        "job_id" TEXT NOT NULL PRIMARY KEY REFERENCES "public"."backtest_jobs" ("id") ON DELETE CASCADE,
+        "report" JSONB NOT NULL,
+        "created_at" TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    );
+
+--
+-- Row Level Security Policies
+--
+ALTER TABLE "public"."backtest_jobs" ENABLE ROW LEVEL SECURITY;
+
+ALTER TABLE "public"."backtest_job_reports" ENABLE ROW LEVEL SECURITY;
+
+-- Policy: Enable read access for all users
+CREATE POLICY "Enable read access for all users" ON "public"."backtest_jobs" FOR
+SELECT
+    USING (TRUE);
+
+CREATE POLICY "Enable read access for all users" ON "public"."backtest_job_reports" FOR
+SELECT
+    USING (TRUE);
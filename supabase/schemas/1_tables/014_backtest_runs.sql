@@ -0,0 +1,66 @@
+--
+-- Backtest Runs Tables
+--
+-- These tables store the saved backtest runs with the parameters, data window and seed they were
+-- run with, the result of every rebalancing cycle and the outcome per validator
+--
+CREATE TABLE
+    IF NOT EXISTS "public"."backtest_runs" (
+        "id" TEXT NOT NULL PRIMARY KEY,
+        "created_at" TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        "git_revision" TEXT NOT NULL,
+        "scenario" TEXT,
+        "start_epoch" "public"."u_64" NOT NULL,
+        "end_epoch" "public"."u_64" NOT NULL,
+        "seed" "public"."u_64" NOT NULL,
+        "number_of_validator_delegations" BIGINT NOT NULL,
+        "delegation_strategy" TEXT NOT NULL DEFAULT 'steward',
+        "scoring_model" TEXT NOT NULL DEFAULT 'on-chain',
+        "parameters" JSONB NOT NULL,
+        "aggregated_apy" DOUBLE PRECISION NOT NULL,
+        "stake_utilization_ratio" DOUBLE PRECISION NOT NULL,
+        "final_apy" DOUBLE PRECISION NOT NULL
+    );
+
+CREATE TABLE
+    IF NOT EXISTS "public"."backtest_cycles" (
+        "run_id" TEXT NOT NULL REFERENCES "public"."backtest_runs" ("id") ON DELETE CASCADE,
+        "cycle_index" BIGINT NOT NULL,
+        "start_epoch" "public"."u_64" NOT NULL,
+        "end_epoch" "public"."u_64" NOT NULL,
+        "starting_total_lamports" "public"."u_64" NOT NULL,
+        "ending_total_lamports" "public"."u_64" NOT NULL,
+        PRIMARY KEY ("run_id", "cycle_index")
+    );
+
+CREATE TABLE
+    IF NOT EXISTS "public"."backtest_validator_results" (
+        "run_id" TEXT NOT NULL REFERENCES "public"."backtest_runs" ("id") ON DELETE CASCADE,
+        "vote_pubkey" "public"."solana_pubkey" NOT NULL,
+        "cycles_selected" BIGINT NOT NULL,
+        "last_score" DOUBLE PRECISION NOT NULL,
+        "final_stake_lamports" "public"."u_64" NOT NULL,
+        PRIMARY KEY ("run_id", "vote_pubkey")
+    );
+
+--
+-- Row Level Security Policies
+--
+ALTER TABLE "public"."backtest_runs" ENABLE ROW LEVEL SECURITY;
+
+ALTER TABLE "public"."backtest_cycles" ENABLE ROW LEVEL SECURITY;
+
+ALTER TABLE "public"."backtest_validator_results" ENABLE ROW LEVEL SECURITY;
+
+-- Policy: Enable read access for all users
+CREATE POLICY "Enable read access for all users" ON "public"."backtest_runs" FOR
+SELECT
+    USING (TRUE);
+
+CREATE POLICY "Enable read access for all users" ON "public"."backtest_cycles" FOR
+SELECT
+    USING (TRUE);
+
+CREATE POLICY "Enable read access for all users" ON "public"."backtest_validator_results" FOR
+SELECT
+    USING (TRUE);
@@ -0,0 +1,42 @@
+--
+-- Steward Delegations Tables
+--
+-- These tables store the state of the on-chain steward for every epoch the tracker ran in: the
+-- score, delegation target and active stake of each validator of the stake pool, and the raw
+-- steward config account used to score them
+--
+CREATE TABLE
+    IF NOT EXISTS "public"."steward_delegations" (
+        "epoch" "public"."u_64" NOT NULL,
+        "vote_pubkey" "public"."solana_pubkey" NOT NULL,
+        "validator_list_index" INTEGER NOT NULL,
+        "score" "public"."u_64" NOT NULL,
+        "yield_score" "public"."u_64" NOT NULL,
+        "delegation_numerator" "public"."u_64" NOT NULL,
+        "delegation_denominator" "public"."u_64" NOT NULL,
+        "instant_unstake" BOOLEAN NOT NULL,
+        "active_stake_lamports" "public"."u_64" NOT NULL,
+        PRIMARY KEY ("epoch", "vote_pubkey")
+    );
+
+CREATE TABLE
+    IF NOT EXISTS "public"."steward_config_snapshots" (
+        "epoch" "public"."u_64" NOT NULL PRIMARY KEY,
+        "config_data" BYTEA NOT NULL
+    );
+
+--
+-- Row Level Security Policies
+--
+ALTER TABLE "public"."steward_delegations" ENABLE ROW LEVEL SECURITY;
+
+ALTER TABLE "public"."steward_config_snapshots" ENABLE ROW LEVEL SECURITY;
+
+-- Policy: Enable read access for all users
+CREATE POLICY "Enable read access for all users" ON "public"."steward_delegations" FOR
+SELECT
+    USING (TRUE);
+
+CREATE POLICY "Enable read access for all users" ON "public"."steward_config_snapshots" FOR
+SELECT
+    USING (TRUE);
@@ -0,0 +1,21 @@
+--
+-- Steward Blacklists Table
+--
+-- This table stores the vote accounts blacklisted by the steward config for every epoch the
+-- tracker ran in. An epoch with an empty list had no validator blacklisted.
+--
+CREATE TABLE
+    IF NOT EXISTS "public"."steward_blacklists" (
+        "epoch" "public"."u_64" NOT NULL PRIMARY KEY,
+        "vote_pubkeys" TEXT[] NOT NULL
+    );
+
+--
+-- Row Level Security Policies
+--
+ALTER TABLE "public"."steward_blacklists" ENABLE ROW LEVEL SECURITY;
+
+-- Policy: Enable read access for all users
+CREATE POLICY "Enable read access for all users" ON "public"."steward_blacklists" FOR
+SELECT
+    USING (TRUE);